@@ -0,0 +1,79 @@
+//! Interpolation of 100 satellites at one epoch
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+use sp3::prelude::{Constellation, Duration, Epoch, SP3Builder, SP3Entry, TimeScale, SP3, SV};
+
+use std::str::FromStr;
+
+const ORDER: usize = 9;
+
+// Daily file (15' sampling) of 100 satellites
+fn sp3_100_satellites() -> SP3 {
+    let t0 = Epoch::from_str("2023-08-27T00:00:00 GPST").unwrap();
+    let dt = Duration::from_seconds(900.0);
+
+    let satellites = (1..=32)
+        .map(|prn| SV::new(Constellation::GPS, prn))
+        .chain((1..=36).map(|prn| SV::new(Constellation::Galileo, prn)))
+        .chain((1..=32).map(|prn| SV::new(Constellation::BeiDou, prn)))
+        .collect::<Vec<_>>();
+
+    let mut builder = SP3Builder::default()
+        .with_agency("IGS")
+        .with_coord_system("IGS20")
+        .with_constellation(Constellation::Mixed)
+        .with_timescale(TimeScale::GPST)
+        .with_sampling_period(dt);
+
+    for i in 0..96 {
+        let t = t0 + i as f64 * dt;
+        let phase = i as f64 * 0.13;
+
+        for (j, sv) in satellites.iter().enumerate() {
+            let phase = phase + j as f64 * 0.06;
+
+            let position_km = (
+                26_000.0 * phase.cos(),
+                26_000.0 * phase.sin(),
+                1_000.0 * (2.0 * phase).sin(),
+            );
+
+            builder.push_entry(t, *sv, SP3Entry::from_position_km(position_km));
+        }
+    }
+
+    builder.build().unwrap()
+}
+
+fn interpolation(c: &mut Criterion) {
+    let sp3 = sp3_100_satellites();
+    let satellites = sp3.satellites_iter().collect::<Vec<_>>();
+
+    // mid-day, off grid
+    let t = Epoch::from_str("2023-08-27T12:07:30 GPST").unwrap();
+
+    let mut group = c.benchmark_group("interpolation_100sv");
+
+    group.bench_function("per_call", |b| {
+        b.iter(|| {
+            for sv in satellites.iter() {
+                black_box(sp3.satellite_position_lagrangian_interpolation(*sv, t, ORDER));
+            }
+        })
+    });
+
+    group.bench_function("context", |b| {
+        b.iter(|| {
+            let context = sp3.interpolation_context(t, ORDER);
+
+            for sv in satellites.iter() {
+                black_box(context.position(*sv));
+            }
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, interpolation);
+criterion_main!(benches);
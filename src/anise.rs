@@ -1,11 +1,31 @@
 use crate::prelude::{Epoch, SP3, SV};
 
+#[cfg(doc)]
+use crate::prelude::{DataType, Header};
+
+use hifitime::Unit;
+
+use std::collections::BTreeSet;
+
+#[cfg(feature = "log")]
+use log::warn;
+
+#[cfg(feature = "serde")]
+use serde_json::json;
+
 use anise::{
     astro::AzElRange,
+    constants::frames::{EARTH_J2000, IAU_EARTH_FRAME, MOON_J2000, SUN_J2000},
     math::Vector6,
     prelude::{Almanac, Frame, Orbit},
 };
 
+/// Earth gravitational constant (WGS84), in km³.s⁻²
+const EARTH_GM_KM3_S2: f64 = 398600.4418;
+
+/// Earth angular velocity (WGS84), in rad.s⁻¹
+const EARTH_ANGULAR_VELOCITY_RAD_S: f64 = 7.2921151467E-5;
+
 #[cfg(feature = "anise")]
 #[cfg_attr(docsrs, doc(cfg(feature = "anise")))]
 #[derive(Debug, Copy, Clone, PartialEq)]
@@ -23,10 +43,22 @@ pub struct SatelliteOrbitalState {
     pub maneuver: bool,
 }
 
+impl SatelliteOrbitalState {
+    /// Returns true if this [Orbit] is only described by a position vector,
+    /// because the velocity vector was not resolved (null velocity vector).
+    /// Such state cannot be used for propagation.
+    pub fn is_position_only(&self) -> bool {
+        !self.orbit.has_velocity_dynamics()
+    }
+}
+
 #[cfg(feature = "anise")]
 #[cfg_attr(docsrs, doc(cfg(feature = "anise")))]
 #[derive(Debug, Copy, Clone, PartialEq)]
 pub struct SatelliteOrbitalAttitude {
+    /// [Epoch] of the source state
+    pub epoch: Epoch,
+
     /// Satellite as [SV]
     pub satellite: SV,
 
@@ -40,21 +72,30 @@ pub struct SatelliteOrbitalAttitude {
 impl SP3 {
     /// Form a [SatelliteOrbitalState]s [Iterator].
     ///
+    /// When the velocity vector is not defined (for example, [DataType::Position] files),
+    /// the [Orbit] is position only: its velocity vector is null,
+    /// see [SatelliteOrbitalState::is_position_only].
+    /// Such states cannot be used for propagation, you should resolve the velocities
+    /// first, with [SP3::resolve_velocities_mut] or [SP3::resolve_dynamics_mut].
+    ///
     /// ## Inputs
     /// - frame: [Frame] definition that must be an ECEF for this to be correct.
+    ///   The coordinates system is described by [Header::try_reference_frame]:
+    ///   a warning is emitted when the requested [Frame] is not Earth centered Earth fixed.
     #[cfg(feature = "anise")]
     #[cfg_attr(docsrs, doc(cfg(feature = "anise")))]
     pub fn satellites_orbit_iter(
         &self,
         frame: Frame,
     ) -> Box<dyn Iterator<Item = SatelliteOrbitalState> + '_> {
+        #[cfg(feature = "log")]
+        self.warn_on_frame_mismatch(frame);
+
         Box::new(self.data.iter().filter_map(move |(k, v)| {
             let (x_km, y_km, z_km) = v.position_km;
 
-            let (vx_km_s, vy_km_s, vz_km_s) = match v.velocity_km_s {
-                Some((vx_km_s, vy_km_s, vz_km_s)) => (vx_km_s, vy_km_s, vz_km_s),
-                None => (0.0, 0.0, 0.0),
-            };
+            // position only orbit (null velocity) when not resolved
+            let (vx_km_s, vy_km_s, vz_km_s) = v.velocity_km_s.unwrap_or_default();
 
             let pos_vel = Vector6::new(x_km, y_km, z_km, vx_km_s, vy_km_s, vz_km_s);
             let orbit = Orbit::from_cartesian_pos_vel(pos_vel, k.epoch, frame);
@@ -68,7 +109,269 @@ impl SP3 {
         }))
     }
 
-    /// Form a [SatelliteOrbitalAttitude] [Iterator].
+    /// Warns when the requested [Frame] does not match the coordinates system
+    /// hinted by the [Header] (see [Header::try_reference_frame]), which is always
+    /// Earth centered Earth fixed: that is, when the [Frame] is not centered on Earth,
+    /// or not oriented like ITRF93 or IAU Earth.
+    #[cfg(feature = "log")]
+    fn warn_on_frame_mismatch(&self, frame: Frame) {
+        use anise::constants::{
+            celestial_objects::EARTH,
+            orientations::{IAU_EARTH, ITRF93},
+        };
+
+        let hint = match self.header.try_reference_frame() {
+            Some(hint) => hint,
+            None => return,
+        };
+
+        let earth_fixed = frame.ephemeris_id == EARTH
+            && (frame.orientation_id == ITRF93 || frame.orientation_id == IAU_EARTH);
+
+        if !earth_fixed {
+            warn!(
+                "coordinates are expressed in {} (ECEF), but requested frame {} is not Earth fixed",
+                hint, frame
+            );
+        }
+    }
+
+    /// Interpolates the state of this satellite at any [Epoch] `t`,
+    /// directly expressed as an [Orbit]. This is typically used
+    /// to initialize a propagator at an off-grid [Epoch].
+    /// The position vector is obtained by Lagrangian interpolation
+    /// (see [SP3::satellite_position_lagrangian_interpolation]), while the velocity
+    /// vector is obtained by central difference of the interpolated positions.
+    ///
+    /// ## Inputs
+    /// - sv: selected [SV]
+    /// - t: interpolation [Epoch]
+    /// - order: interpolation order. This will panic on even interpolation orders.
+    /// - frame: [Frame] definition that must be an ECEF for this to be correct.
+    ///
+    /// ## Output
+    /// - None if `t` is too early or too late with respect to interpolation order.
+    #[cfg(feature = "anise")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "anise")))]
+    pub fn interpolated_orbit(
+        &self,
+        sv: SV,
+        t: Epoch,
+        order: usize,
+        frame: Frame,
+    ) -> Option<Orbit> {
+        let dt = 1.0 * Unit::Second;
+
+        let (x_km, y_km, z_km) = self.satellite_position_lagrangian_interpolation(sv, t, order)?;
+
+        let (x0_km, y0_km, z0_km) =
+            self.satellite_position_lagrangian_interpolation(sv, t - dt, order)?;
+
+        let (x1_km, y1_km, z1_km) =
+            self.satellite_position_lagrangian_interpolation(sv, t + dt, order)?;
+
+        let dt_s = 2.0 * dt.to_seconds();
+
+        let pos_vel = Vector6::new(
+            x_km,
+            y_km,
+            z_km,
+            (x1_km - x0_km) / dt_s,
+            (y1_km - y0_km) / dt_s,
+            (z1_km - z0_km) / dt_s,
+        );
+
+        Some(Orbit::from_cartesian_pos_vel(pos_vel, t, frame))
+    }
+
+    /// Estimates the number of orbital revolutions this satellite completes
+    /// over the time span it is described in this file.
+    /// The orbital period is estimated by ANISE, from each state vector
+    /// (Earth rotation compensated), and averaged over the file.
+    /// When velocities are not provided, they are approximated
+    /// by the finite difference of consecutive positions.
+    ///
+    /// ## Output
+    /// - None if this satellite is described by less than two states,
+    ///   or the orbital period could not be determined.
+    #[cfg(feature = "anise")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "anise")))]
+    pub fn revolutions(&self, sv: SV) -> Option<f64> {
+        let frame = EARTH_J2000.with_mu_km3_s2(EARTH_GM_KM3_S2);
+
+        let states = self
+            .data
+            .iter()
+            .filter_map(|(k, v)| {
+                // null positions are not valid states
+                if k.sv == sv && v.position_km != (0.0, 0.0, 0.0) {
+                    Some((k.epoch, v.position_km, v.velocity_km_s))
+                } else {
+                    None
+                }
+            })
+            .collect::<Vec<_>>();
+
+        if states.len() < 2 {
+            return None;
+        }
+
+        let (t_first, _, _) = states[0];
+        let (t_last, _, _) = states[states.len() - 1];
+
+        let mut periods_s = Vec::with_capacity(states.len());
+
+        for (i, (t, (x_km, y_km, z_km), velocity_km_s)) in states.iter().enumerate() {
+            let (vx_km_s, vy_km_s, vz_km_s) = match velocity_km_s {
+                Some(velocity_km_s) => *velocity_km_s,
+                None => {
+                    let (t_next, (x1_km, y1_km, z1_km), _) = match states.get(i + 1) {
+                        Some(next) => next,
+                        None => continue,
+                    };
+
+                    let dt_s = (*t_next - *t).to_seconds();
+
+                    (
+                        (x1_km - x_km) / dt_s,
+                        (y1_km - y_km) / dt_s,
+                        (z1_km - z_km) / dt_s,
+                    )
+                },
+            };
+
+            // ECEF to inertial velocity (Earth rotation)
+            let pos_vel = Vector6::new(
+                *x_km,
+                *y_km,
+                *z_km,
+                vx_km_s - EARTH_ANGULAR_VELOCITY_RAD_S * y_km,
+                vy_km_s + EARTH_ANGULAR_VELOCITY_RAD_S * x_km,
+                vz_km_s,
+            );
+
+            let orbit = Orbit::from_cartesian_pos_vel(pos_vel, *t, frame);
+
+            if let Ok(period) = orbit.period() {
+                periods_s.push(period.to_seconds());
+            }
+        }
+
+        if periods_s.is_empty() {
+            return None;
+        }
+
+        let mean_period_s = periods_s.iter().sum::<f64>() / periods_s.len() as f64;
+
+        Some((t_last - t_first).to_seconds() / mean_period_s)
+    }
+
+    /// Verifies that this [Almanac] covers the time frame of this file,
+    /// before using it in [SP3::satellites_attitude_iter] or the spatial prediction.
+    /// ANISE errors are otherwise silently dropped, resulting in empty results.
+    /// Coverage is verified at each [Epoch], by resolving the [IAU_EARTH_FRAME] definition
+    /// (the ECEF frame those methods are used with) and its orientation with respect
+    /// to [EARTH_J2000], then the Sun and Moon with respect to the Earth.
+    ///
+    /// ## Output
+    /// - Err(t) with `t` the first [Epoch] that is not covered.
+    #[cfg(feature = "anise")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "anise")))]
+    pub fn check_almanac_coverage(&self, almanac: &Almanac) -> Result<(), Epoch> {
+        // the record is sorted by satellite first
+        let epochs = self.data.keys().map(|k| k.epoch).collect::<BTreeSet<_>>();
+
+        let frame = match almanac.frame_from_uid(IAU_EARTH_FRAME) {
+            Ok(frame) => frame,
+            Err(_) => match epochs.first() {
+                Some(t0) => return Err(*t0),
+                None => return Ok(()),
+            },
+        };
+
+        for t in epochs {
+            if almanac.rotate(frame, EARTH_J2000, t).is_err() {
+                return Err(t);
+            }
+
+            for body in [SUN_J2000, MOON_J2000] {
+                if almanac.translate_geometric(body, EARTH_J2000, t).is_err() {
+                    return Err(t);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Exports the ground track of each satellite, as a GeoJSON `FeatureCollection`,
+    /// typically for web visualization. Each satellite is described by one `LineString`
+    /// feature, of subsatellite (longitude, latitude) coordinates in degrees,
+    /// in chronological order. States that cannot be converted to (finite) geodetic
+    /// coordinates are omitted.
+    ///
+    /// ## Inputs
+    /// - almanac: [Almanac] used to resolve the `frame` shape (ellipsoid)
+    /// - frame: [Frame] definition that must be an ECEF for this to be correct.
+    #[cfg(feature = "anise")]
+    #[cfg(feature = "serde")]
+    #[cfg_attr(docsrs, doc(cfg(all(feature = "anise", feature = "serde"))))]
+    pub fn ground_tracks_geojson(&self, almanac: &Almanac, frame: Frame) -> String {
+        let frame = almanac.frame_from_uid(frame).unwrap_or(frame);
+
+        // satellite first: states are naturally grouped by satellite, in chronological order
+        let mut tracks = Vec::<(SV, Vec<[f64; 2]>)>::new();
+
+        for state in self.satellites_orbit_iter(frame) {
+            let (lat_deg, long_deg, _) = match state.orbit.latlongalt() {
+                Ok(latlongalt) => latlongalt,
+                Err(_) => continue,
+            };
+
+            // NaN or infinite values are not valid JSON numbers
+            if !lat_deg.is_finite() || !long_deg.is_finite() {
+                continue;
+            }
+
+            let coordinates = [long_deg, lat_deg];
+
+            match tracks.last_mut() {
+                Some((sv, track)) if *sv == state.satellite => track.push(coordinates),
+                _ => tracks.push((state.satellite, vec![coordinates])),
+            }
+        }
+
+        let features = tracks
+            .iter()
+            .map(|(sv, track)| {
+                json!({
+                    "type": "Feature",
+                    "properties": {
+                        "satellite": sv.to_string(),
+                    },
+                    "geometry": {
+                        "type": "LineString",
+                        "coordinates": track,
+                    },
+                })
+            })
+            .collect::<Vec<_>>();
+
+        json!({
+            "type": "FeatureCollection",
+            "features": features,
+        })
+        .to_string()
+    }
+
+    /// Form a [SatelliteOrbitalAttitude] [Iterator], one per (`Epoch`, `SV`) state
+    /// of this file, as seen from a fixed ground station.
+    ///
+    /// ## Inputs
+    /// - almanac: [Almanac]
+    /// - frame: [Frame] definition that must be an ECEF for this to be correct.
+    /// - rx_orbit: ground station, as an [Orbit] in the same ECEF [Frame].
+    ///   Being fixed, it is simply re-dated to each state [Epoch].
     #[cfg(feature = "anise")]
     #[cfg_attr(docsrs, doc(cfg(feature = "anise")))]
     pub fn satellites_attitude_iter(
@@ -78,11 +381,17 @@ impl SP3 {
         rx_orbit: Orbit,
     ) -> Box<dyn Iterator<Item = SatelliteOrbitalAttitude> + '_> {
         Box::new(self.satellites_orbit_iter(frame).filter_map(move |state| {
+            let rx_orbit = Orbit {
+                epoch: state.epoch,
+                ..rx_orbit
+            };
+
             if let Ok(azelrange) =
                 almanac.azimuth_elevation_range_sez(rx_orbit, state.orbit, None, None)
             {
                 Some(SatelliteOrbitalAttitude {
                     azelrange,
+                    epoch: state.epoch,
                     maneuver: state.maneuver,
                     satellite: state.satellite,
                 })
@@ -92,3 +401,176 @@ impl SP3 {
         }))
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::prelude::{Almanac, Epoch, Orbit, SP3Key, EARTH_J2000, IAU_EARTH_FRAME, SP3, SV};
+    use std::collections::BTreeSet;
+    use std::str::FromStr;
+
+    #[test]
+    #[cfg(feature = "flate2")]
+    fn interpolated_orbit() {
+        let sp3 =
+            SP3::from_gzip_file("data/SP3/C/EMR0OPSULT_20232391800_02D_15M_ORB.SP3.gz").unwrap();
+
+        let g01 = SV::from_str("G01").unwrap();
+
+        // mid-window epoch
+        let t = Epoch::from_str("2023-08-28T00:07:30 GPST").unwrap();
+
+        let (x_km, y_km, z_km) = sp3
+            .satellite_position_lagrangian_interpolation(g01, t, 7)
+            .unwrap();
+
+        let orbit = sp3
+            .interpolated_orbit(g01, t, 7, EARTH_J2000)
+            .expect("interpolation should be feasible");
+
+        assert_eq!(orbit.epoch, t);
+
+        let rmag_km = (x_km.powi(2) + y_km.powi(2) + z_km.powi(2)).sqrt();
+        assert!((orbit.rmag_km() - rmag_km).abs() < 1.0E-6);
+
+        // GPS orbital velocity (ECEF) is in the order of 3 km/s
+        let vmag_km_s = orbit.vmag_km_s();
+        assert!(
+            vmag_km_s > 2.0 && vmag_km_s < 5.0,
+            "invalid velocity: {}",
+            vmag_km_s
+        );
+
+        // too early
+        let t0 = Epoch::from_str("2023-08-27T18:00:00 GPST").unwrap();
+        assert!(sp3.interpolated_orbit(g01, t0, 7, EARTH_J2000).is_none());
+    }
+
+    #[test]
+    #[cfg(feature = "flate2")]
+    fn revolutions() {
+        let sp3 =
+            SP3::from_gzip_file("data/SP3/C/ESA0OPSRAP_20232390000_01D_15M_ORB.SP3.gz").unwrap();
+
+        let g01 = SV::from_str("G01").unwrap();
+
+        // GPS orbital period is about 11h58m
+        let revolutions = sp3
+            .revolutions(g01)
+            .expect("revolutions should be determined");
+
+        assert!(
+            revolutions > 1.9 && revolutions < 2.1,
+            "invalid number of revolutions: {}",
+            revolutions
+        );
+
+        let g99 = SV::from_str("G99").unwrap();
+        assert!(sp3.revolutions(g99).is_none());
+    }
+
+    #[test]
+    #[cfg(feature = "flate2")]
+    fn satellites_attitude_iter() {
+        let sp3 =
+            SP3::from_gzip_file("data/SP3/C/ESA0OPSRAP_20232390000_01D_15M_ORB.SP3.gz").unwrap();
+
+        let almanac = Almanac::until_2035().unwrap();
+        let earth_cef = almanac.frame_from_uid(IAU_EARTH_FRAME).unwrap();
+
+        let t0 = sp3.first_epoch().unwrap();
+
+        // fixed ground station (Toulouse)
+        let rx_orbit = Orbit::try_latlongalt(43.6, 1.44, 0.15, t0, earth_cef).unwrap();
+
+        let mut total = 0;
+
+        for attitude in sp3.satellites_attitude_iter(almanac, earth_cef, rx_orbit) {
+            assert!(
+                sp3.data
+                    .keys()
+                    .any(|k| k.epoch == attitude.epoch && k.sv == attitude.satellite),
+                "{}({}) does not exist",
+                attitude.epoch,
+                attitude.satellite
+            );
+
+            assert_eq!(attitude.azelrange.epoch, attitude.epoch);
+
+            let elevation_deg = attitude.azelrange.elevation_deg;
+            assert!(
+                (-90.0..=90.0).contains(&elevation_deg),
+                "{}({}): invalid elevation {}",
+                attitude.epoch,
+                attitude.satellite,
+                elevation_deg
+            );
+
+            total += 1;
+        }
+
+        assert!(total > 0, "no attitude was resolved");
+    }
+
+    #[test]
+    #[cfg(feature = "flate2")]
+    fn check_almanac_coverage() {
+        let mut sp3 =
+            SP3::from_gzip_file("data/SP3/C/ESA0OPSRAP_20232390000_01D_15M_ORB.SP3.gz").unwrap();
+
+        let t0 = sp3.data.keys().map(|k| k.epoch).min().unwrap();
+
+        let almanac = Almanac::until_2035().unwrap();
+        assert_eq!(sp3.check_almanac_coverage(&almanac), Ok(()));
+
+        // no ephemeris nor frame definition at all
+        assert_eq!(sp3.check_almanac_coverage(&Almanac::default()), Err(t0));
+
+        // file extending past the almanac time frame (1849-2150):
+        // only the last epoch is not covered
+        let (key, entry) = sp3
+            .data
+            .first_key_value()
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .unwrap();
+
+        let t = Epoch::from_str("2200-01-01T00:00:00 GPST").unwrap();
+
+        sp3.data.insert(SP3Key { epoch: t, ..key }, entry);
+
+        assert_eq!(sp3.check_almanac_coverage(&almanac), Err(t));
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    #[cfg(feature = "flate2")]
+    fn ground_tracks_geojson() {
+        let sp3 =
+            SP3::from_gzip_file("data/SP3/C/ESA0OPSRAP_20232390000_01D_15M_ORB.SP3.gz").unwrap();
+
+        let almanac = Almanac::until_2035().unwrap();
+
+        let geojson = sp3.ground_tracks_geojson(&almanac, IAU_EARTH_FRAME);
+
+        let parsed = serde_json::from_str::<serde_json::Value>(&geojson).unwrap();
+        assert_eq!(parsed["type"], "FeatureCollection");
+
+        let satellites = sp3.data.keys().map(|k| k.sv).collect::<BTreeSet<_>>();
+
+        // one feature per satellite, in the same order
+        let features = parsed["features"].as_array().unwrap();
+        assert_eq!(features.len(), satellites.len());
+
+        for (feature, sv) in features.iter().zip(satellites.iter()) {
+            assert_eq!(feature["properties"]["satellite"], sv.to_string());
+            assert_eq!(feature["geometry"]["type"], "LineString");
+
+            for coordinates in feature["geometry"]["coordinates"].as_array().unwrap() {
+                let long_deg = coordinates[0].as_f64().unwrap();
+                let lat_deg = coordinates[1].as_f64().unwrap();
+
+                assert!((-180.0..=180.0).contains(&long_deg));
+                assert!((-90.0..=90.0).contains(&lat_deg));
+            }
+        }
+    }
+}
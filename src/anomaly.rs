@@ -0,0 +1,150 @@
+//! Axis anomalies (forensics)
+use crate::{
+    prelude::{SP3, SV},
+    Vector3D,
+};
+
+use std::collections::BTreeMap;
+
+/// A satellite is flagged when its position differs from the reference by more than this
+/// (RMS, in kilometers), while one [AxisAnomaly] brings it back below this threshold.
+/// Products of different agencies agree within a few centimeters, so we have a large margin.
+const AXIS_ANOMALY_THRESHOLD_KM: f64 = 1.0;
+
+/// [AxisAnomaly] describes a sign-flipped or axis-swapped position vector,
+/// typically caused by producer or parser bugs. See [SP3::detect_axis_anomalies].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum AxisAnomaly {
+    /// X coordinate has the wrong sign
+    SignFlipX,
+
+    /// Y coordinate has the wrong sign
+    SignFlipY,
+
+    /// Z coordinate has the wrong sign
+    SignFlipZ,
+
+    /// X and Y coordinates are swapped
+    SwapXY,
+
+    /// X and Z coordinates are swapped
+    SwapXZ,
+
+    /// Y and Z coordinates are swapped
+    SwapYZ,
+}
+
+impl std::fmt::Display for AxisAnomaly {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::SignFlipX => f.write_str("sign-flipped x"),
+            Self::SignFlipY => f.write_str("sign-flipped y"),
+            Self::SignFlipZ => f.write_str("sign-flipped z"),
+            Self::SwapXY => f.write_str("swapped x/y"),
+            Self::SwapXZ => f.write_str("swapped x/z"),
+            Self::SwapYZ => f.write_str("swapped y/z"),
+        }
+    }
+}
+
+impl AxisAnomaly {
+    const ALL: [Self; 6] = [
+        Self::SignFlipX,
+        Self::SignFlipY,
+        Self::SignFlipZ,
+        Self::SwapXY,
+        Self::SwapXZ,
+        Self::SwapYZ,
+    ];
+
+    /// Repairs this (x, y, z) vector affected by this [AxisAnomaly].
+    /// Each anomaly is its own inverse: this also introduces the anomaly in a sane vector.
+    pub fn repair(&self, (x, y, z): Vector3D) -> Vector3D {
+        match self {
+            Self::SignFlipX => (-x, y, z),
+            Self::SignFlipY => (x, -y, z),
+            Self::SignFlipZ => (x, y, -z),
+            Self::SwapXY => (y, x, z),
+            Self::SwapXZ => (z, y, x),
+            Self::SwapYZ => (x, z, y),
+        }
+    }
+}
+
+// Sum of squared 3D differences
+fn squared_distance_km2(lhs: Vector3D, rhs: Vector3D) -> f64 {
+    (lhs.0 - rhs.0).powi(2) + (lhs.1 - rhs.1).powi(2) + (lhs.2 - rhs.2).powi(2)
+}
+
+impl SP3 {
+    /// Compares this [SP3] to a `reference` product (same satellite, same epoch)
+    /// and flags the satellites whose residuals suggest a sign-flipped or swapped axis:
+    /// a large residual (over 1 km RMS) that one [AxisAnomaly] explains (under 1 km RMS
+    /// once repaired). This is a forensic tool, to catch producer or parser bugs.
+    /// Combined anomalies (for example a swap and a sign flip) are not identified.
+    /// Use [SP3::repair_axis_anomalies_mut] to repair the flagged satellites.
+    pub fn detect_axis_anomalies(&self, reference: &SP3) -> Vec<(SV, AxisAnomaly)> {
+        // (identity, each anomaly) squared residuals, and count, per satellite
+        let mut residuals = BTreeMap::<SV, ([f64; 7], usize)>::new();
+
+        for (k, v) in self.data.iter() {
+            let ref_position_km = match reference.data.get(k) {
+                Some(reference) => reference.position_km,
+                None => continue,
+            };
+
+            if v.position_km == (0.0, 0.0, 0.0) || ref_position_km == (0.0, 0.0, 0.0) {
+                continue;
+            }
+
+            let (sums, count) = residuals.entry(k.sv).or_insert(([0.0; 7], 0));
+
+            sums[0] += squared_distance_km2(v.position_km, ref_position_km);
+
+            for (i, anomaly) in AxisAnomaly::ALL.iter().enumerate() {
+                sums[i + 1] += squared_distance_km2(anomaly.repair(v.position_km), ref_position_km);
+            }
+
+            *count += 1;
+        }
+
+        residuals
+            .into_iter()
+            .filter_map(|(sv, (sums, count))| {
+                let rms_km = sums.map(|sum| (sum / count as f64).sqrt());
+
+                if rms_km[0] < AXIS_ANOMALY_THRESHOLD_KM {
+                    return None;
+                }
+
+                let (index, best_km) = rms_km[1..]
+                    .iter()
+                    .enumerate()
+                    .min_by(|(_, a), (_, b)| a.total_cmp(b))?;
+
+                if *best_km < AXIS_ANOMALY_THRESHOLD_KM {
+                    Some((sv, AxisAnomaly::ALL[index]))
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+
+    /// Repairs the positions (and velocities) of the satellites affected by these
+    /// [AxisAnomaly], typically reported by [SP3::detect_axis_anomalies].
+    pub fn repair_axis_anomalies_mut(&mut self, anomalies: &[(SV, AxisAnomaly)]) {
+        for (k, v) in self.data.iter_mut() {
+            let anomaly = match anomalies.iter().find(|(sv, _)| *sv == k.sv) {
+                Some((_, anomaly)) => anomaly,
+                None => continue,
+            };
+
+            v.position_km = anomaly.repair(v.position_km);
+
+            if let Some(velocity_km_s) = v.velocity_km_s {
+                v.velocity_km_s = Some(anomaly.repair(velocity_km_s));
+            }
+        }
+    }
+}
@@ -0,0 +1,77 @@
+use crate::prelude::{Error, SP3};
+
+impl SP3 {
+    /// Encodes this [SP3] into a compact binary representation,
+    /// using [bincode](https://docs.rs/bincode). This is much faster to load back
+    /// (see [SP3::from_bincode]) than parsing the SP3 text format again,
+    /// and is therefore well suited for caching parsed products.
+    pub fn to_bincode(&self) -> Vec<u8> {
+        // encoding our structures into memory cannot fail
+        bincode::serde::encode_to_vec(self, bincode::config::standard())
+            .unwrap_or_else(|e| panic!("bincode encoding: {}", e))
+    }
+
+    /// Decodes [SP3] from binary content, previously
+    /// encoded with [SP3::to_bincode].
+    pub fn from_bincode(content: &[u8]) -> Result<Self, Error> {
+        let (sp3, _) = bincode::serde::decode_from_slice(content, bincode::config::standard())?;
+        Ok(sp3)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::prelude::*;
+    use crate::tests::fixtures::t0;
+    use hifitime::Unit;
+    use std::str::FromStr;
+
+    #[test]
+    fn bincode_roundtrip() {
+        let g01 = SV::from_str("G01").unwrap();
+        let e05 = SV::from_str("E05").unwrap();
+
+        let t0 = t0();
+
+        let mut sp3 = SP3::default();
+
+        sp3.header.version = Version::D;
+        sp3.header.data_type = DataType::Velocity;
+        sp3.header.agency = "IGS".to_string();
+        sp3.header.coord_system = "IGS20".to_string();
+        sp3.header.timescale = TimeScale::GPST;
+        sp3.header.constellation = Constellation::Mixed;
+        sp3.header.sampling_period = 15.0 * Unit::Minute;
+        sp3.header.release_epoch = t0;
+
+        sp3.comments.push("bincode test".to_string());
+        sp3.prod_attributes = Some(ProductionAttributes::default());
+
+        for i in 0..4 {
+            let epoch = t0 + i as f64 * 15.0 * Unit::Minute;
+
+            sp3.data.insert(
+                SP3Key { epoch, sv: g01 },
+                SP3Entry::from_position_km((13_000.123456, -20_000.0, 15_000.0 + i as f64))
+                    .with_velocity_km_s((1.2345, -0.5, 3.1))
+                    .with_clock_offset_us(-10.5),
+            );
+
+            sp3.data.insert(
+                SP3Key { epoch, sv: e05 },
+                SP3Entry::from_predicted_position_km((21_000.0, 1_000.0, -2_000.0)),
+            );
+        }
+
+        sp3.canonicalize_mut();
+
+        let encoded = sp3.to_bincode();
+        assert!(!encoded.is_empty());
+
+        let decoded = SP3::from_bincode(&encoded).unwrap();
+        assert_eq!(decoded, sp3);
+
+        // corrupt content
+        assert!(SP3::from_bincode(&encoded[..encoded.len() / 2]).is_err());
+    }
+}
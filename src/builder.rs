@@ -0,0 +1,151 @@
+//! Programmatic SP3 synthesis
+use crate::prelude::{
+    Constellation, DataType, Duration, Epoch, Error, Header, OrbitType, SP3Entry, SP3Key,
+    TimeScale, Version, SP3, SV,
+};
+
+use std::collections::BTreeMap;
+
+/// [SP3Builder] is the ergonomic way to synthesize an [SP3] from scratch.
+/// Define the production context, push your [SP3Entry]s, and the [Header]
+/// fields that depend on the content are derived when building.
+/// ```
+/// use sp3::prelude::*;
+/// use std::str::FromStr;
+///
+/// let g01 = SV::from_str("G01").unwrap();
+/// let t0 = Epoch::from_str("2023-08-27T00:00:00 GPST").unwrap();
+/// let dt = Duration::from_seconds(900.0);
+///
+/// let mut builder = SP3Builder::default()
+///     .with_agency("TEST")
+///     .with_constellation(Constellation::GPS)
+///     .with_timescale(TimeScale::GPST)
+///     .with_sampling_period(dt)
+///     .with_coord_system("IGS20");
+///
+/// builder.push_entry(t0, g01, SP3Entry::from_position_km((1.0, 2.0, 3.0)));
+/// builder.push_entry(t0 + dt, g01, SP3Entry::from_position_km((1.0, 2.0, 3.0)));
+///
+/// let sp3 = builder.build()
+///     .unwrap();
+///
+/// assert_eq!(sp3.header.num_epochs, 2);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct SP3Builder {
+    /// Production context
+    header: Header,
+
+    /// [TimeScale] must be defined prior building
+    timescale: Option<TimeScale>,
+
+    /// File comments
+    comments: Vec<String>,
+
+    /// Record
+    data: BTreeMap<SP3Key, SP3Entry>,
+}
+
+impl SP3Builder {
+    /// Defines the file [Version] (latest revision by default).
+    pub fn with_version(mut self, version: Version) -> Self {
+        self.header.version = version;
+        self
+    }
+
+    /// Defines the production agency.
+    pub fn with_agency(mut self, agency: &str) -> Self {
+        self.header.agency = agency.to_string();
+        self
+    }
+
+    /// Defines the [Constellation] described by this file.
+    /// Use [Constellation::Mixed] for multi-GNSS files.
+    pub fn with_constellation(mut self, constellation: Constellation) -> Self {
+        self.header.constellation = constellation;
+        self
+    }
+
+    /// Defines the [TimeScale] in which all [Epoch]s are expressed.
+    /// This is mandatory.
+    pub fn with_timescale(mut self, timescale: TimeScale) -> Self {
+        self.timescale = Some(timescale);
+        self
+    }
+
+    /// Defines the sampling period. This only prevails for single epoch files:
+    /// otherwise the sampling period is derived from the record (see [SP3Builder::build]).
+    pub fn with_sampling_period(mut self, sampling_period: Duration) -> Self {
+        self.header.sampling_period = sampling_period;
+        self
+    }
+
+    /// Defines the coordinates system.
+    pub fn with_coord_system(mut self, coord_system: &str) -> Self {
+        self.header.coord_system = coord_system.to_string();
+        self
+    }
+
+    /// Defines the [OrbitType].
+    pub fn with_orbit_type(mut self, orbit_type: OrbitType) -> Self {
+        self.header.orbit_type = orbit_type;
+        self
+    }
+
+    /// Adds one file comment.
+    pub fn with_comment(mut self, comment: &str) -> Self {
+        self.comments.push(comment.to_string());
+        self
+    }
+
+    /// Pushes a new [SP3Entry] for this [SV] at this [Epoch],
+    /// expressed in the [TimeScale] of this file.
+    /// Any previous entry for this [SV] and [Epoch] is replaced.
+    pub fn push_entry(&mut self, epoch: Epoch, sv: SV, entry: SP3Entry) {
+        self.data.insert(SP3Key { epoch, sv }, entry);
+    }
+
+    /// Builds the [SP3]. The following [Header] fields are derived
+    /// from the record:
+    /// - the list of satellites and total number of epochs
+    /// - the sampling period, when at least two epochs were pushed
+    ///   (see [SP3::canonicalize_mut]), otherwise the declared value is preserved
+    /// - the week counter, time of week, MJD and fraction of day (earliest [Epoch])
+    /// - the publication [Epoch] (earliest [Epoch])
+    /// - the [DataType], which is [DataType::Velocity] when velocities were provided.
+    ///
+    /// Returns an error if no entry was pushed or the [TimeScale] is not defined.
+    pub fn build(self) -> Result<SP3, Error> {
+        let timescale = self.timescale.ok_or(Error::UndefinedTimescale)?;
+
+        let mut sp3 = SP3 {
+            header: self.header,
+            comments: self.comments,
+            prod_attributes: None,
+            data: self.data,
+        };
+
+        // the record is sorted by satellite first
+        let first = sp3
+            .data
+            .keys()
+            .map(|k| k.epoch)
+            .min()
+            .ok_or(Error::EmptyBuilder)?;
+
+        sp3.header.timescale = timescale;
+        sp3.header.release_epoch = first;
+
+        if sp3.data.values().any(|v| v.velocity_km_s.is_some()) {
+            sp3.header.data_type = DataType::Velocity;
+        }
+
+        sp3.header.align_mjd_to_first_epoch(first);
+
+        // derives satellites, num_epochs, sampling period and week counter
+        sp3.canonicalize_mut();
+
+        Ok(sp3)
+    }
+}
@@ -0,0 +1,148 @@
+//! Satellite clock analysis
+use crate::prelude::{Constellation, Epoch, SP3, SV};
+
+use std::collections::BTreeMap;
+
+// Lagrangian interpolation of a scalar time series
+fn lagrange_scalar_interpolation(t: Epoch, x: &[(Epoch, f64)]) -> f64 {
+    let mut value = 0.0_f64;
+
+    for (i, (t_i, x_i)) in x.iter().enumerate() {
+        let mut l_i = 1.0_f64;
+
+        for (j, (t_j, _)) in x.iter().enumerate() {
+            if j != i {
+                l_i *= (t - *t_j).to_seconds();
+                l_i /= (*t_i - *t_j).to_seconds();
+            }
+        }
+
+        value += x_i * l_i;
+    }
+
+    value
+}
+
+/// Interpolates a scalar time series at `t`, using a perfectly centered window
+/// of `order + 1` samples. Returns None when `t` is too close to either edge.
+pub(crate) fn centered_scalar_interpolation(
+    order: usize,
+    t: Epoch,
+    series: &[(Epoch, f64)],
+) -> Option<f64> {
+    let target_len_2 = order.div_ceil(2);
+
+    // central point: first sample not earlier than t
+    let t_x = series.partition_point(|(t_i, _)| *t_i < t);
+
+    // window must be correctly centered on central point
+    if t_x < target_len_2 || t_x + target_len_2 > series.len() {
+        return None;
+    }
+
+    let window = &series[t_x - target_len_2..t_x + target_len_2];
+
+    Some(lagrange_scalar_interpolation(t, window))
+}
+
+impl SP3 {
+    /// Estimates the inter-system clock bias, for each pair of constellations
+    /// that have clock data at common [Epoch]s. The bias is the mean
+    /// difference between the average satellite clock offset of each constellation,
+    /// and surfaces the offset between the system times.
+    ///
+    /// ## Output
+    /// - Bias in seconds, indexed by (A, B) constellations, with A < B.
+    ///   A positive value means A is ahead of B.
+    pub fn inter_system_clock_bias(&self) -> BTreeMap<(Constellation, Constellation), f64> {
+        // average clock offset, per constellation, per epoch
+        let mut averages = BTreeMap::<Epoch, BTreeMap<Constellation, (f64, usize)>>::new();
+
+        for (t, sv, clock_s) in self.satellites_clock_offset_sec_iter() {
+            let (sum, count) = averages
+                .entry(t)
+                .or_default()
+                .entry(sv.constellation)
+                .or_insert((0.0, 0));
+
+            *sum += clock_s;
+            *count += 1;
+        }
+
+        let mut biases = BTreeMap::<(Constellation, Constellation), (f64, usize)>::new();
+
+        for constellations in averages.values() {
+            for (lhs, (lhs_sum, lhs_count)) in constellations.iter() {
+                for (rhs, (rhs_sum, rhs_count)) in constellations.range(..*lhs) {
+                    let bias_s = rhs_sum / *rhs_count as f64 - lhs_sum / *lhs_count as f64;
+
+                    let (sum, count) = biases.entry((*rhs, *lhs)).or_insert((0.0, 0));
+
+                    *sum += bias_s;
+                    *count += 1;
+                }
+            }
+        }
+
+        biases
+            .into_iter()
+            .map(|(pair, (sum, count))| (pair, sum / count as f64))
+            .collect()
+    }
+
+    /// Interpolates the clock offset of this [SV] at desired [Epoch] `t`,
+    /// using the Lagrangian method and desired interpolation order.
+    /// This mirrors [SP3::satellite_position_lagrangian_interpolation]:
+    /// - any odd order is supported, but clock states are usually better
+    ///   interpolated with lower orders than positions.
+    ///   This will panic on even interpolation orders.
+    /// - this will not interpolate (returns None) if `t` is either too early
+    ///   or too late with respect to the interpolation order, because
+    ///   we only generate perfectly centered time frames.
+    ///
+    /// ## Output
+    /// - Clock offset in microseconds
+    pub fn satellite_clock_offset_interpolate(
+        &self,
+        sv: SV,
+        t: Epoch,
+        order: usize,
+    ) -> Option<f64> {
+        let odd_order = order % 2 > 0;
+        if !odd_order {
+            panic!("even interpolation order is not supported");
+        }
+
+        let series = self
+            .data
+            .iter()
+            .filter_map(|(k, v)| {
+                if k.sv == sv {
+                    Some((k.epoch, v.clock_us?))
+                } else {
+                    None
+                }
+            })
+            .collect::<Vec<_>>();
+
+        centered_scalar_interpolation(order, t, &series)
+    }
+
+    /// Collects all satellite clock offsets, in seconds, indexed by [SV] and [Epoch].
+    /// This is a standalone clock product, that does not involve the orbit data.
+    /// See [Self::satellites_clock_offset_sec_iter].
+    pub fn clock_offsets_map(&self) -> BTreeMap<(SV, Epoch), f64> {
+        self.satellites_clock_offset_sec_iter()
+            .map(|(t, sv, offset_s)| ((sv, t), offset_s))
+            .collect()
+    }
+
+    /// Collects all satellite clock drifts, in s.s⁻¹, indexed by [SV] and [Epoch].
+    /// This is a standalone clock product, that does not involve the orbit data.
+    /// See [Self::satellites_clock_drift_sec_sec_iter].
+    pub fn clock_drifts_map(&self) -> BTreeMap<(SV, Epoch), f64> {
+        self.satellites_clock_drift_sec_sec_iter()
+            .map(|(t, sv, drift_s_s)| ((sv, t), drift_s_s))
+            .collect()
+    }
+}
@@ -0,0 +1,100 @@
+//! daily files concatenation
+use crate::prelude::{DataType, Duration, Error, SP3};
+
+impl SP3 {
+    /// Concatenates consecutive files (typically daily files) into one continuous [SP3].
+    /// The files must be provided in chronological order, and each must start exactly one
+    /// sampling period after the last [Epoch] of the previous one: they may not overlap
+    /// nor leave a gap. They must also share the same agency, timescale, coordinates
+    /// system (reference frame) and sampling period.
+    /// The [Header] of the first file is preserved, except for the list of satellites
+    /// (union of all files, in order of appearance) and the number of epochs.
+    ///
+    /// ## Errors
+    /// - [Error::EmptyConcatenation] if no file is provided
+    /// - [Error::ConcatenationMismatch] if the production contexts do not match
+    /// - [Error::ConcatenationOverlap] if one file does not follow the previous one
+    /// - [Error::ConcatenationGap] if the files are not contiguous
+    pub fn concatenate(files: &[SP3]) -> Result<SP3, Error> {
+        let (first, others) = files.split_first().ok_or(Error::EmptyConcatenation)?;
+
+        let mut concatenated = first.clone();
+
+        let dt = first.header.sampling_period;
+        let mut last = first.epochs_iter().max();
+
+        for file in others.iter() {
+            for (field, lhs, rhs) in [
+                (
+                    "agency",
+                    first.header.agency.clone(),
+                    file.header.agency.clone(),
+                ),
+                (
+                    "timescale",
+                    first.header.timescale.to_string(),
+                    file.header.timescale.to_string(),
+                ),
+                (
+                    "coordinates system",
+                    first.header.coord_system.trim().to_string(),
+                    file.header.coord_system.trim().to_string(),
+                ),
+                (
+                    "sampling period",
+                    dt.to_string(),
+                    file.header.sampling_period.to_string(),
+                ),
+            ] {
+                if lhs != rhs {
+                    return Err(Error::ConcatenationMismatch(field, lhs, rhs));
+                }
+            }
+
+            let next = match file.epochs_iter().min() {
+                Some(next) => next,
+                None => continue,
+            };
+
+            if let Some(last) = last {
+                if next <= last {
+                    return Err(Error::ConcatenationOverlap(last, next));
+                }
+
+                if dt > Duration::ZERO && next - last != dt {
+                    return Err(Error::ConcatenationGap(last, next));
+                }
+            }
+
+            for (key, entry) in file.data.iter() {
+                concatenated.data.insert(key.clone(), *entry);
+            }
+
+            for sv in file.header.satellites.iter() {
+                if let Err(index) = concatenated.header.satellites.binary_search(sv) {
+                    concatenated.header.satellites.insert(index, *sv);
+                }
+            }
+
+            concatenated.header.num_satellites = concatenated.header.satellites.len();
+
+            for (sv, exponent) in file.header.accuracy_exponents.iter() {
+                concatenated
+                    .header
+                    .accuracy_exponents
+                    .entry(*sv)
+                    .or_insert(*exponent);
+            }
+
+            if file.header.data_type == DataType::Velocity {
+                concatenated.header.data_type = DataType::Velocity;
+            }
+
+            last = file.epochs_iter().max();
+        }
+
+        concatenated.header.num_epochs = concatenated.total_epochs() as u64;
+
+        Ok(concatenated)
+    }
+}
@@ -1,16 +1,67 @@
 use crate::{
-    prelude::{DataType, Epoch, SP3Entry, SP3, SV},
+    prelude::{DataType, Epoch, SP3Entry, SP3Key, SP3, SV},
     Vector3D,
 };
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+
+/// [DynamicsSummary] describes what [SP3::resolve_dynamics_mut] actually resolved.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct DynamicsSummary {
+    /// Number of velocity vectors that were resolved
+    pub velocities_added: usize,
+
+    /// Number of clock drifts that were resolved
+    pub drifts_added: usize,
+
+    /// Number of satellites for which at least one value was resolved
+    pub satellites_touched: usize,
+}
+
+impl DynamicsSummary {
+    /// Returns true if nothing was resolved
+    pub fn is_empty(&self) -> bool {
+        self.velocities_added == 0 && self.drifts_added == 0
+    }
+}
+
+// Derivative of the Lagrangian basis polynomials, evaluated at `t`.
+// Returns one weight per data point: the derivative of the Lagrange fit
+// is then the weighted sum of the data points.
+pub(crate) fn lagrange_derivative_weights(t: Epoch, epochs: &[Epoch]) -> Vec<f64> {
+    let n = epochs.len();
+    let mut weights = vec![0.0_f64; n];
+
+    for i in 0..n {
+        for m in 0..n {
+            if m == i {
+                continue;
+            }
+
+            let mut product = 1.0 / (epochs[i] - epochs[m]).to_seconds();
+
+            for j in 0..n {
+                if j != i && j != m {
+                    product *= (t - epochs[j]).to_seconds();
+                    product /= (epochs[i] - epochs[j]).to_seconds();
+                }
+            }
+
+            weights[i] += product;
+        }
+    }
+
+    weights
+}
 
 impl SP3 {
     /// Resolve all satellite dynamics for each [Epoch]
     /// where they were not originally defined (both spatial and temporal).  
     /// Modifies the file type to [DataType::Velocity] on success.
-    pub fn resolve_dynamics_mut(&mut self) {
-        let mut success = false;
+    /// Returns a [DynamicsSummary] describing what was actually resolved.
+    pub fn resolve_dynamics_mut(&mut self) -> DynamicsSummary {
+        let mut summary = DynamicsSummary::default();
+        let mut touched = HashSet::<SV>::with_capacity(8);
         let mut past_states = HashMap::<SV, (Epoch, SP3Entry)>::with_capacity(8);
 
         for (k, v) in self.data.iter_mut() {
@@ -25,14 +76,16 @@ impl SP3 {
                     );
 
                     v.velocity_km_s = Some((x_km, y_km, z_km));
-                    success = true;
+                    summary.velocities_added += 1;
+                    touched.insert(k.sv);
                 }
 
                 if v.clock_drift_ns.is_none() {
                     if let Some(past_clock_us) = past_state.clock_us {
                         if let Some(clock_us) = v.clock_us {
                             v.clock_drift_ns = Some((clock_us - past_clock_us) / dt * 1000.0);
-                            success = true;
+                            summary.drifts_added += 1;
+                            touched.insert(k.sv);
                         }
                     }
                 }
@@ -41,9 +94,13 @@ impl SP3 {
             past_states.insert(k.sv, (k.epoch, *v));
         }
 
-        if success {
+        summary.satellites_touched = touched.len();
+
+        if !summary.is_empty() {
             self.header.data_type = DataType::Velocity;
         }
+
+        summary
     }
 
     /// See [SP3::resolve_dynamics_mut].
@@ -53,6 +110,111 @@ impl SP3 {
         s
     }
 
+    /// Resolve all satellite dynamics for each [Epoch]
+    /// where they were not originally defined (both spatial and temporal),
+    /// as the analytic derivative of the Lagrangian fit of desired order.
+    /// This is far more accurate than [SP3::resolve_dynamics_mut], which uses
+    /// first order differences, and consistent with our interpolation methods.
+    /// The fit window (order +1 points) is centered on each [Epoch] when feasible,
+    /// so this also applies to the first [Epoch].
+    /// Satellites that do not have enough data points are not resolved.
+    /// Modifies the file type to [DataType::Velocity] on success.
+    /// Returns a [DynamicsSummary] describing what was actually resolved.
+    pub fn resolve_dynamics_interpolated_mut(&mut self, order: usize) -> DynamicsSummary {
+        let mut summary = DynamicsSummary::default();
+        let window_len = order + 1;
+
+        let satellites = self.data.keys().map(|k| k.sv).collect::<HashSet<_>>();
+
+        for sv in satellites {
+            let states = self
+                .data
+                .iter()
+                .filter_map(|(k, v)| {
+                    if k.sv == sv {
+                        Some((k.epoch, *v))
+                    } else {
+                        None
+                    }
+                })
+                .collect::<Vec<_>>();
+
+            let num_states = states.len();
+
+            if order == 0 || num_states < window_len {
+                continue;
+            }
+
+            let mut touched = false;
+
+            for (i, (t, state)) in states.iter().enumerate() {
+                if state.velocity_km_s.is_some() && state.clock_drift_ns.is_some() {
+                    continue;
+                }
+
+                let start = i
+                    .saturating_sub(window_len / 2)
+                    .min(num_states - window_len);
+                let window = &states[start..start + window_len];
+
+                let epochs = window.iter().map(|(t_i, _)| *t_i).collect::<Vec<_>>();
+                let weights = lagrange_derivative_weights(*t, &epochs);
+
+                let key = SP3Key { epoch: *t, sv };
+
+                let entry = self.data.get_mut(&key).unwrap();
+
+                if entry.velocity_km_s.is_none() {
+                    let mut velocity_km_s = Vector3D::default();
+
+                    for (w_i, (_, state_i)) in weights.iter().zip(window.iter()) {
+                        velocity_km_s.0 += w_i * state_i.position_km.0;
+                        velocity_km_s.1 += w_i * state_i.position_km.1;
+                        velocity_km_s.2 += w_i * state_i.position_km.2;
+                    }
+
+                    entry.velocity_km_s = Some(velocity_km_s);
+                    summary.velocities_added += 1;
+                    touched = true;
+                }
+
+                if entry.clock_drift_ns.is_none() && entry.clock_us.is_some() {
+                    let mut drift_us_s = Some(0.0);
+
+                    for (w_i, (_, state_i)) in weights.iter().zip(window.iter()) {
+                        drift_us_s = match (drift_us_s, state_i.clock_us) {
+                            (Some(drift), Some(clock_us)) => Some(drift + w_i * clock_us),
+                            _ => None,
+                        };
+                    }
+
+                    if let Some(drift_us_s) = drift_us_s {
+                        entry.clock_drift_ns = Some(drift_us_s * 1000.0);
+                        summary.drifts_added += 1;
+                        touched = true;
+                    }
+                }
+            }
+
+            if touched {
+                summary.satellites_touched += 1;
+            }
+        }
+
+        if !summary.is_empty() {
+            self.header.data_type = DataType::Velocity;
+        }
+
+        summary
+    }
+
+    /// See [SP3::resolve_dynamics_interpolated_mut].
+    pub fn resolve_dynamics_interpolated(&self, order: usize) -> Self {
+        let mut s = self.clone();
+        s.resolve_dynamics_interpolated_mut(order);
+        s
+    }
+
     /// Resolve the clock drift for each satellite
     /// and each [Epoch] where it was not originally defined.
     pub fn resolve_clock_drift_mut(&mut self) {
@@ -142,6 +304,7 @@ impl SP3 {
 mod test {
     use crate::prelude::{DataType, Duration, Epoch, SP3, SV};
 
+    use std::collections::HashSet;
     use std::str::FromStr;
 
     #[test]
@@ -405,6 +568,91 @@ mod test {
         assert_eq!(tests, 4);
     }
 
+    #[test]
+    fn dynamics_summary() {
+        let mut sp3 =
+            SP3::from_gzip_file("data/SP3/C/GRG0MGXFIN_20201770000_01D_15M_ORB.SP3.gz").unwrap();
+
+        // each satellite but its first epoch
+        let mut satellites = HashSet::<SV>::new();
+        let mut expected_velocities = 0;
+
+        for k in sp3.data.keys() {
+            if !satellites.insert(k.sv) {
+                expected_velocities += 1;
+            }
+        }
+
+        let summary = sp3.resolve_dynamics_mut();
+
+        assert_eq!(summary.velocities_added, expected_velocities);
+        assert!(summary.drifts_added > 0);
+        assert!(summary.drifts_added <= expected_velocities);
+        assert_eq!(summary.satellites_touched, satellites.len());
+
+        // nothing left to resolve
+        let summary = sp3.resolve_dynamics_mut();
+        assert!(summary.is_empty());
+        assert_eq!(summary.satellites_touched, 0);
+    }
+
+    #[test]
+    fn interpolated_dynamics() {
+        // velocity product: native velocities
+        let sp3 = SP3::from_file("data/SP3/C/em108871.sp3").unwrap();
+
+        assert_eq!(sp3.header.data_type, DataType::Velocity);
+        assert!(sp3.has_satellite_velocity());
+
+        let position_only = sp3.without_dynamics();
+
+        let differences = position_only.resolve_dynamics();
+        let interpolated = position_only.resolve_dynamics_interpolated(9);
+
+        assert_eq!(interpolated.header.data_type, DataType::Velocity);
+
+        let mut compared = 0;
+        let (mut worst_differences, mut worst_interpolated) = (0.0_f64, 0.0_f64);
+
+        for (k, v) in sp3.data.iter() {
+            let native = match v.velocity_km_s {
+                Some(native) => native,
+                None => continue,
+            };
+
+            let (interpolated, differences) = match (
+                interpolated.data[k].velocity_km_s,
+                differences.data[k].velocity_km_s,
+            ) {
+                (Some(interpolated), Some(differences)) => (interpolated, differences),
+                _ => continue,
+            };
+
+            let err_interpolated = ((interpolated.0 - native.0).powi(2)
+                + (interpolated.1 - native.1).powi(2)
+                + (interpolated.2 - native.2).powi(2))
+            .sqrt();
+
+            let err_differences = ((differences.0 - native.0).powi(2)
+                + (differences.1 - native.1).powi(2)
+                + (differences.2 - native.2).powi(2))
+            .sqrt();
+
+            worst_interpolated = worst_interpolated.max(err_interpolated);
+            worst_differences = worst_differences.max(err_differences);
+            compared += 1;
+        }
+
+        assert!(compared > 0, "no native velocity to compare to");
+
+        assert!(
+            worst_interpolated < worst_differences,
+            "interpolated={} differences={}",
+            worst_interpolated,
+            worst_differences
+        );
+    }
+
     #[test]
     fn dynamics_clock_drift() {
         let sp3 =
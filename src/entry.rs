@@ -1,7 +1,12 @@
 #[cfg(doc)]
 use crate::prelude::SP3Key;
 
-use crate::{formatting::CoordsFormatter, prelude::SV, FormattingError, Vector3D};
+use crate::{
+    formatting::{CoordsFormatter, RoundingMode},
+    prelude::SV,
+    velocity::{clock_drift_ns_to_field, km_s_to_sp3_velocity_field},
+    FormattingError, Vector3D,
+};
 
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
@@ -37,6 +42,14 @@ pub struct SP3Entry {
 
     /// Clock drift in nanoseconds with 10⁻¹⁶ precision.
     pub clock_drift_ns: Option<f64>,
+
+    /// (x, y, z) position standard deviation exponents, as found in the record
+    /// (SP3-c/d): σ = base^n mm, with the base declared in the `%f` header line.
+    pub position_std_mm: Option<(u16, u16, u16)>,
+
+    /// Clock standard deviation exponent, as found in the record
+    /// (SP3-c/d): σ = base^n ps, with the base declared in the `%f` header line.
+    pub clock_std_ps: Option<u32>,
 }
 
 impl std::ops::Sub for SP3Entry {
@@ -46,7 +59,7 @@ impl std::ops::Sub for SP3Entry {
         Self {
             position_km: (
                 self.position_km.0 - rhs.position_km.0,
-                self.position_km.1 - self.position_km.1,
+                self.position_km.1 - rhs.position_km.1,
                 self.position_km.2 - rhs.position_km.2,
             ),
             velocity_km_s: if let Some(velocity_km_s) = self.velocity_km_s {
@@ -74,6 +87,8 @@ impl std::ops::Sub for SP3Entry {
             } else {
                 None
             },
+            position_std_mm: None,
+            clock_std_ps: None,
         }
     }
 }
@@ -119,6 +134,8 @@ impl SP3Entry {
             predicted_clock: false,
             predicted_orbit: false,
             clock_event: false,
+            position_std_mm: None,
+            clock_std_ps: None,
         }
     }
 
@@ -133,6 +150,8 @@ impl SP3Entry {
             predicted_clock: false,
             predicted_orbit: true,
             clock_event: false,
+            position_std_mm: None,
+            clock_std_ps: None,
         }
     }
 
@@ -148,6 +167,8 @@ impl SP3Entry {
             predicted_clock: false,
             predicted_orbit: false,
             clock_event: false,
+            position_std_mm: None,
+            clock_std_ps: None,
         }
     }
 
@@ -166,26 +187,50 @@ impl SP3Entry {
             predicted_clock: false,
             predicted_orbit: true,
             clock_event: false,
+            position_std_mm: None,
+            clock_std_ps: None,
         }
     }
 
     /// Formats this [SP3Entry] according to SP3 standards
     pub fn format<W: Write>(&self, sv: SV, w: &mut BufWriter<W>) -> Result<(), FormattingError> {
+        self.format_with_rounding(sv, w, RoundingMode::default())
+    }
+
+    /// Formats this [SP3Entry] according to SP3 standards, using desired [RoundingMode]
+    pub fn format_with_rounding<W: Write>(
+        &self,
+        sv: SV,
+        w: &mut BufWriter<W>,
+        rounding: RoundingMode,
+    ) -> Result<(), FormattingError> {
+        let coordinates = |value: f64| CoordsFormatter::coordinates(value).with_rounding(rounding);
+
         let mut formatted = format!(
             "P{}{}{}{}",
             sv,
-            CoordsFormatter::coordinates(self.position_km.0),
-            CoordsFormatter::coordinates(self.position_km.1),
-            CoordsFormatter::coordinates(self.position_km.2),
+            coordinates(self.position_km.0),
+            coordinates(self.position_km.1),
+            coordinates(self.position_km.2),
         );
 
         if let Some(value) = self.clock_us {
-            formatted.push_str(&format!("{}", CoordsFormatter::coordinates(value)));
+            formatted.push_str(&format!("{}", coordinates(value)));
         } else {
             formatted.push_str("              ");
         }
 
-        formatted.push_str("              "); // deviations not handled yet
+        if let Some((x_std, y_std, z_std)) = self.position_std_mm {
+            formatted.push_str(&format!(" {:2} {:2} {:2}", x_std, y_std, z_std));
+        } else {
+            formatted.push_str("         ");
+        }
+
+        if let Some(clock_std) = self.clock_std_ps {
+            formatted.push_str(&format!(" {:3} ", clock_std));
+        } else {
+            formatted.push_str("     ");
+        }
 
         if self.clock_event {
             formatted.push('E');
@@ -215,16 +260,16 @@ impl SP3Entry {
             formatted.push_str(&format!(
                 "\nV{}{}{}{}",
                 sv,
-                CoordsFormatter::coordinates(vel_x_km * 1.0E4), // dm/s
-                CoordsFormatter::coordinates(vel_y_km * 1.0E4), // dm/s
-                CoordsFormatter::coordinates(vel_z_km * 1.0E4), // dm/s
+                coordinates(km_s_to_sp3_velocity_field(vel_x_km)),
+                coordinates(km_s_to_sp3_velocity_field(vel_y_km)),
+                coordinates(km_s_to_sp3_velocity_field(vel_z_km)),
             ));
 
             if let Some(drift_ns) = self.clock_drift_ns {
                 formatted.push_str(&format!(
                     "{}",
-                    CoordsFormatter::coordinates(drift_ns * 1.0E-2)
-                )); // 0.1 us
+                    coordinates(clock_drift_ns_to_field(drift_ns))
+                ));
             }
         }
 
@@ -333,6 +378,8 @@ mod test {
                     maneuver: false,
                     clock_drift_ns: None,
                     clock_event: false,
+                    position_std_mm: None,
+                    clock_std_ps: None,
                     predicted_clock: false,
                     clock_us: Some(10.571484),
                 },
@@ -364,6 +411,8 @@ mod test {
                     maneuver: false,
                     clock_drift_ns: None,
                     clock_event: false,
+                    position_std_mm: None,
+                    clock_std_ps: None,
                     predicted_clock: false,
                     clock_us: None,
                 },
@@ -395,6 +444,8 @@ mod test {
                     maneuver: true,
                     clock_drift_ns: None,
                     clock_event: false,
+                    position_std_mm: None,
+                    clock_std_ps: None,
                     predicted_clock: false,
                     clock_us: None,
                 },
@@ -426,6 +477,8 @@ mod test {
                     maneuver: false,
                     clock_drift_ns: None,
                     clock_event: false,
+                    position_std_mm: None,
+                    clock_std_ps: None,
                     predicted_clock: true,
                     clock_us: None,
                 },
@@ -457,6 +510,8 @@ mod test {
                 maneuver: false,
                 clock_drift_ns: None,
                 clock_event: false,
+                position_std_mm: None,
+                clock_std_ps: None,
                 predicted_clock: false,
                 clock_us: None,
             },
@@ -488,6 +543,8 @@ mod test {
                 maneuver: false,
                 clock_drift_ns: None,
                 clock_event: false,
+                position_std_mm: None,
+                clock_std_ps: None,
                 predicted_clock: true,
                 clock_us: None,
             },
@@ -523,6 +580,8 @@ mod test {
                     maneuver: false,
                     clock_drift_ns: None,
                     clock_event: false,
+                    position_std_mm: None,
+                    clock_std_ps: None,
                     predicted_clock: false,
                     clock_us: None,
                 },
@@ -560,6 +619,8 @@ VG01  -8880.949046 -23142.274905 -14050.679881\n",
                     maneuver: false,
                     clock_drift_ns: Some(8.9376),
                     clock_event: false,
+                    position_std_mm: None,
+                    clock_std_ps: None,
                     predicted_clock: false,
                     clock_us: None,
                 },
@@ -591,6 +652,8 @@ VG01  -8880.949046 -23142.274905 -14050.679881      0.089376\n",
             predicted_orbit: true,
             maneuver: true,
             clock_event: true,
+            position_std_mm: None,
+            clock_std_ps: None,
             clock_us: Some(-176.397152),
             clock_drift_ns: None,
         };
@@ -609,4 +672,32 @@ VG01  -8880.949046 -23142.274905 -14050.679881      0.089376\n",
             "PG01 -22335.782004 -14656.280389  -1218.238499   -176.397152              EP  MP\n"
         );
     }
+
+    #[test]
+    fn entry_substraction() {
+        let lhs = SP3Entry::from_position_km((1000.0, 2000.0, 3000.0))
+            .with_velocity_km_s((1.0, 2.0, 3.0))
+            .with_clock_offset_us(10.0);
+
+        let rhs = SP3Entry::from_position_km((999.0, 1998.5, 3002.0))
+            .with_velocity_km_s((0.5, 2.5, 2.0))
+            .with_clock_offset_us(4.0);
+
+        let residual = lhs - rhs;
+
+        assert_eq!(residual.position_km, (1.0, 1.5, -2.0));
+        assert_eq!(residual.velocity_km_s, Some((0.5, -0.5, 1.0)));
+        assert_eq!(residual.clock_us, Some(6.0));
+
+        // both implementations agree
+        let mut assigned = lhs;
+        assigned -= rhs;
+        assert_eq!(assigned, residual);
+
+        // self - self is null
+        let null = lhs - lhs;
+        assert_eq!(null.position_km, (0.0, 0.0, 0.0));
+        assert_eq!(null.velocity_km_s, Some((0.0, 0.0, 0.0)));
+        assert_eq!(null.clock_us, Some(0.0));
+    }
 }
@@ -1,9 +1,12 @@
 use thiserror::Error;
 
 use gnss_rs::constellation::ParsingError as ConstellationParsingError;
-use hifitime::errors::ParsingError as EpochParsingError;
+use gnss_rs::prelude::SV;
+use hifitime::{errors::ParsingError as EpochParsingError, Duration, Epoch, TimeScale};
 use std::io::Error as IoError;
 
+use crate::header::version::Version;
+
 #[derive(Debug, Error)]
 pub enum Error {
     #[error("Parsing error: {0}")]
@@ -17,6 +20,44 @@ pub enum Error {
 
     #[error("File i/o error: {0}")]
     FileIo(#[from] IoError),
+
+    #[error("SP3Builder: no entry to build from")]
+    EmptyBuilder,
+
+    #[error("SP3Builder: undefined timescale")]
+    UndefinedTimescale,
+
+    #[error("interpolation order {0} is not supported (odd orders only)")]
+    InterpolationOrder(usize),
+
+    #[error("invalid sampling period {0}")]
+    SamplingPeriod(Duration),
+
+    #[error("appended epoch {0} does not follow the last epoch")]
+    NonIncreasingEpoch(Epoch),
+
+    #[error("epoch spacing {0} does not match the sampling period {1}")]
+    EpochSpacing(Duration, Duration),
+
+    #[error("no file to concatenate")]
+    EmptyConcatenation,
+
+    #[error("cannot concatenate: {0} mismatch (\"{1}\" and \"{2}\")")]
+    ConcatenationMismatch(&'static str, String, String),
+
+    #[error("cannot concatenate: overlapping files ({1} does not follow {0})")]
+    ConcatenationOverlap(Epoch, Epoch),
+
+    #[error("cannot concatenate: gap between {0} and {1}")]
+    ConcatenationGap(Epoch, Epoch),
+
+    #[cfg(feature = "serde")]
+    #[error("json error: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[cfg(feature = "bincode")]
+    #[error("bincode decoding error: {0}")]
+    BincodeDecoding(#[from] bincode::error::DecodeError),
 }
 
 #[derive(Debug, Error)]
@@ -24,6 +65,9 @@ pub enum ParsingError {
     #[error("Non supported SP3 revision")]
     NonSupportedRevision,
 
+    #[error("expecting SP3-{0} revision, got SP3-{1}")]
+    UnexpectedRevision(Version, Version),
+
     #[error("Unknown SP3 orbit type")]
     UnknownOrbitType,
 
@@ -69,6 +113,12 @@ pub enum ParsingError {
     #[error("failed to parse clock data from \"{0}\"")]
     Clock(String),
 
+    #[error("duplicate entry for {sv} at {epoch}")]
+    DuplicateEntry { sv: SV, epoch: Epoch },
+
+    #[error("failed to parse additional parameters from \"{0}\"")]
+    AdditionalParameters(String),
+
     #[error("Not a standardized filename")]
     InvalidFilename,
 
@@ -84,4 +134,10 @@ pub enum ParsingError {
 pub enum FormattingError {
     #[error("i/o: output error")]
     OutputError(#[from] IoError),
+
+    #[error("empty dataset: no epoch to format")]
+    EmptyDataset,
+
+    #[error("timescale {0} cannot be described in SP3 format")]
+    UnsupportedTimescale(TimeScale),
 }
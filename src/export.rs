@@ -0,0 +1,148 @@
+//! CSV export
+use crate::prelude::{FormattingError, SP3};
+
+use itertools::Itertools;
+
+use std::io::{BufWriter, Write};
+
+/// Header row of [SP3::to_csv_writer]
+const CSV_HEADER: &str =
+    "epoch,sv,x_km,y_km,z_km,vel_x_km_s,vel_y_km_s,vel_z_km_s,clock_us,clock_drift_ns";
+
+// Formats an optional value, as an empty cell when missing
+fn optional_cell(value: Option<f64>) -> String {
+    value.map(|value| value.to_string()).unwrap_or_default()
+}
+
+impl SP3 {
+    /// Exports the satellite states of this [SP3] as CSV (comma separated values),
+    /// which is convenient to share with non Rust users. The first row is the header:
+    /// `epoch,sv,x_km,y_km,z_km,vel_x_km_s,vel_y_km_s,vel_z_km_s,clock_us,clock_drift_ns`,
+    /// followed by one row per (epoch, satellite), in chronological order.
+    /// ECEF positions are expressed in kilometers, velocities in km.s⁻¹,
+    /// clock offsets in microseconds and clock drifts in nanoseconds.
+    /// Missing velocities and clock states are left as empty cells.
+    pub fn to_csv_writer<W: Write>(&self, writer: W) -> Result<(), FormattingError> {
+        let mut writer = BufWriter::new(writer);
+
+        writeln!(writer, "{}", CSV_HEADER)?;
+
+        for (k, v) in self.data.iter().sorted_by_key(|(k, _)| (k.epoch, k.sv)) {
+            let (x_km, y_km, z_km) = v.position_km;
+
+            let (vel_x_km_s, vel_y_km_s, vel_z_km_s) = match v.velocity_km_s {
+                Some((vel_x_km_s, vel_y_km_s, vel_z_km_s)) => {
+                    (Some(vel_x_km_s), Some(vel_y_km_s), Some(vel_z_km_s))
+                },
+                None => (None, None, None),
+            };
+
+            writeln!(
+                writer,
+                "{},{},{},{},{},{},{},{},{},{}",
+                k.epoch,
+                k.sv,
+                x_km,
+                y_km,
+                z_km,
+                optional_cell(vel_x_km_s),
+                optional_cell(vel_y_km_s),
+                optional_cell(vel_z_km_s),
+                optional_cell(v.clock_us),
+                optional_cell(v.clock_drift_ns),
+            )?;
+        }
+
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::prelude::*;
+    use crate::tests::fixtures::t0;
+    use hifitime::Unit;
+    use std::str::FromStr;
+
+    #[test]
+    fn csv_export() {
+        let g01 = SV::from_str("G01").unwrap();
+        let e05 = SV::from_str("E05").unwrap();
+
+        let t0 = t0();
+        let t1 = t0 + 15.0 * Unit::Minute;
+
+        let mut sp3 = SP3::default();
+
+        for epoch in [t0, t1] {
+            sp3.data.insert(
+                SP3Key { epoch, sv: g01 },
+                SP3Entry::from_position_km((13_000.123456, -20_000.0, 15_000.5))
+                    .with_velocity_km_s((1.2345, -0.5, 3.1))
+                    .with_clock_offset_us(-10.5)
+                    .with_clock_drift_ns(0.25),
+            );
+
+            sp3.data.insert(
+                SP3Key { epoch, sv: e05 },
+                SP3Entry::from_position_km((21_000.0, 1_000.0, -2_000.0)),
+            );
+        }
+
+        let mut content = Vec::new();
+        sp3.to_csv_writer(&mut content).unwrap();
+
+        let content = String::from_utf8(content).unwrap();
+        let lines = content.lines().collect::<Vec<_>>();
+
+        assert_eq!(lines.len(), 1 + 4);
+        assert_eq!(
+            lines[0],
+            "epoch,sv,x_km,y_km,z_km,vel_x_km_s,vel_y_km_s,vel_z_km_s,clock_us,clock_drift_ns"
+        );
+
+        for line in lines.iter() {
+            assert_eq!(line.split(',').count(), 10, "invalid row \"{}\"", line);
+        }
+
+        // chronological order
+        let epochs = lines[1..]
+            .iter()
+            .map(|line| Epoch::from_str(line.split(',').next().unwrap()).unwrap())
+            .collect::<Vec<_>>();
+
+        assert_eq!(epochs, vec![t0, t0, t1, t1]);
+
+        // known rows
+        let g01_row = lines[1..]
+            .iter()
+            .find(|line| line.split(',').nth(1) == Some("G01"))
+            .unwrap();
+
+        let cells = g01_row.split(',').skip(2).collect::<Vec<_>>();
+
+        assert_eq!(
+            cells,
+            vec![
+                "13000.123456",
+                "-20000",
+                "15000.5",
+                "1.2345",
+                "-0.5",
+                "3.1",
+                "-10.5",
+                "0.25"
+            ]
+        );
+
+        let e05_row = lines[1..]
+            .iter()
+            .find(|line| line.split(',').nth(1) == Some("E05"))
+            .unwrap();
+
+        let cells = e05_row.split(',').skip(2).collect::<Vec<_>>();
+
+        assert_eq!(cells, vec!["21000", "1000", "-2000", "", "", "", "", ""]);
+    }
+}
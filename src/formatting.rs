@@ -7,17 +7,54 @@ use std::{
 
 use itertools::Itertools;
 
-use crate::{errors::FormattingError, prelude::SP3};
+use crate::{
+    errors::FormattingError,
+    header::timescale_descriptor,
+    prelude::{Epoch, Header, SP3Entry, TimeScale, SP3, SV},
+};
 
 #[cfg(feature = "flate2")]
 use flate2::{write::GzEncoder, Compression as GzCompression};
 
 use hifitime::efmt::{Format, Formatter};
 
+/// Number of decimals of the coordinates (km) and clock (us) fields,
+/// which is the same for all revisions.
+const COORDINATES_DECIMALS: usize = 6;
+
+/// [RoundingMode] of the formatted coordinates, clock, velocity and clock drift fields.
+/// Some producers round differently than we do by default, this allows
+/// bit-exact reproduction of their files (the last digit may differ otherwise).
+#[derive(Default, Copy, Clone, Debug, PartialEq, Eq)]
+pub enum RoundingMode {
+    /// Rust's float formatting: the binary value is correctly rounded,
+    /// exact ties are rounded to even.
+    #[default]
+    HalfEven,
+
+    /// Ties are rounded away from zero, the value being scaled
+    /// prior rounding, like `floor(x * 10ⁿ + 0.5)` producers.
+    HalfUp,
+}
+
+impl RoundingMode {
+    /// Rounds this value to this number of decimals
+    fn round(&self, value: f64, precision: usize) -> f64 {
+        match self {
+            Self::HalfEven => value,
+            Self::HalfUp => {
+                let scaling = 10.0_f64.powi(precision as i32);
+                (value * scaling).round() / scaling
+            },
+        }
+    }
+}
+
 pub(crate) struct CoordsFormatter {
     value: f64,
     width: usize,
     precision: usize,
+    rounding: RoundingMode,
 }
 
 impl CoordsFormatter {
@@ -25,7 +62,8 @@ impl CoordsFormatter {
         Self {
             value,
             width: 13,
-            precision: 6,
+            precision: COORDINATES_DECIMALS,
+            rounding: RoundingMode::default(),
         }
     }
 
@@ -34,13 +72,19 @@ impl CoordsFormatter {
             value,
             width: 15,
             precision: 13,
+            rounding: RoundingMode::default(),
         }
     }
+
+    pub fn with_rounding(mut self, rounding: RoundingMode) -> Self {
+        self.rounding = rounding;
+        self
+    }
 }
 
 impl std::fmt::Display for CoordsFormatter {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        let value = self.value;
+        let value = self.rounding.round(self.value, self.precision);
         let sign_str = if self.precision == 13 {
             ""
         } else {
@@ -71,22 +115,168 @@ impl std::fmt::Display for CoordsFormatter {
     }
 }
 
+/// Formats the file comments
+fn format_comments<W: Write>(
+    comments: &[String],
+    writer: &mut BufWriter<W>,
+) -> Result<(), FormattingError> {
+    for comment in comments.iter() {
+        writeln!(writer, "/* {}", comment)?;
+    }
+    Ok(())
+}
+
+/// Formats the [Epoch] descriptor, that initiates a new epoch
+fn format_epoch<W: Write>(epoch: Epoch, writer: &mut BufWriter<W>) -> Result<(), FormattingError> {
+    let efmt = Format::from_str("%Y %m %d %H %M %S.%f").unwrap();
+    let formatter = Formatter::new(epoch, efmt);
+    writeln!(writer, "*  {}", formatter)?;
+    Ok(())
+}
+
+/// [SP3Writer] formats [SP3] content epoch by epoch, into any [Write]able I/O.
+/// This is dedicated to (real-time) producers, that generate epochs over time
+/// and do not want to hold the whole dataset in memory prior formatting.
+/// The resulting content is identical to [SP3::format].
+/// ```
+/// use sp3::prelude::*;
+///
+/// let sp3 = SP3::from_file("data/SP3/D/example.txt")
+///     .unwrap();
+///
+/// let mut writer = SP3Writer::new(Vec::new());
+///
+/// writer.write_header(&sp3.header, &sp3.comments)
+///     .unwrap();
+///
+/// for epoch in sp3.epochs_iter() {
+///     let entries = sp3.data.iter()
+///         .filter_map(|(k, v)| {
+///             if k.epoch == epoch {
+///                 Some((k.sv, *v))
+///             } else {
+///                 None
+///             }
+///         })
+///         .collect::<Vec<_>>();
+///
+///     writer.write_epoch(epoch, &entries)
+///         .unwrap();
+/// }
+///
+/// let _ = writer.finish()
+///     .unwrap();
+/// ```
+pub struct SP3Writer<W: Write> {
+    writer: BufWriter<W>,
+    rounding: RoundingMode,
+}
+
+impl<W: Write> SP3Writer<W> {
+    /// Creates a new [SP3Writer], using efficient buffered writer.
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer: BufWriter::new(writer),
+            rounding: RoundingMode::default(),
+        }
+    }
+
+    /// Copies and returns [SP3Writer] with desired [RoundingMode].
+    pub fn with_rounding(mut self, rounding: RoundingMode) -> Self {
+        self.rounding = rounding;
+        self
+    }
+
+    /// Formats the [Header] and file comments. This should be called only once,
+    /// prior any [Self::write_epoch]. Note that the number of epochs, declared
+    /// in the [Header], should match the number of epochs you are about to write.
+    pub fn write_header(
+        &mut self,
+        header: &Header,
+        comments: &[String],
+    ) -> Result<(), FormattingError> {
+        header.format(&mut self.writer)?;
+        format_comments(comments, &mut self.writer)?;
+        Ok(())
+    }
+
+    /// Formats a new [Epoch] and all its [SP3Entry]s, in the order they were provided.
+    /// [SP3::format] sorts the entries by [SV].
+    pub fn write_epoch(
+        &mut self,
+        epoch: Epoch,
+        entries: &[(SV, SP3Entry)],
+    ) -> Result<(), FormattingError> {
+        format_epoch(epoch, &mut self.writer)?;
+
+        for (sv, entry) in entries.iter() {
+            entry.format_with_rounding(*sv, &mut self.writer, self.rounding)?;
+        }
+
+        Ok(())
+    }
+
+    /// Terminates the file (`EOF`) and returns the underlying I/O.
+    pub fn finish(mut self) -> Result<W, FormattingError> {
+        writeln!(self.writer, "EOF")?;
+        self.writer.flush()?;
+
+        let writer = self.writer.into_inner().map_err(|e| e.into_error())?;
+        Ok(writer)
+    }
+}
+
 impl SP3 {
+    /// Returns true if this [TimeScale] can be described in SP3 format.
+    /// [SP3::format] returns [FormattingError::UnsupportedTimescale] otherwise,
+    /// rather than mislabeling the file.
+    pub fn can_format_timescale(timescale: TimeScale) -> bool {
+        timescale_descriptor(timescale).is_some()
+    }
+
+    /// Returns the position resolution (in meters) of this [SP3], that is, the quantization
+    /// floor of the coordinates field: 1 mm for all revisions.
+    /// Position residuals smaller than this are not significant.
+    /// The `%f` base (see [Header::pos_vel_base]) does not affect this resolution:
+    /// it only scales the standard deviation exponents (see [Header::position_std_mm]),
+    /// never the coordinates themselves.
+    pub fn position_resolution_m(&self) -> f64 {
+        10.0_f64.powi(-(COORDINATES_DECIMALS as i32)) * 1.0E3
+    }
+
+    /// Returns the clock resolution (in seconds) of this [SP3], that is, the quantization
+    /// floor of the clock field: 1 ps for all revisions.
+    /// Clock residuals smaller than this are not significant.
+    /// Like [Self::position_resolution_m], this does not depend on the `%f` base,
+    /// which only scales the standard deviation exponents (see [Header::clock_std_ps]).
+    pub fn clock_resolution_s(&self) -> f64 {
+        10.0_f64.powi(-(COORDINATES_DECIMALS as i32)) * 1.0E-6
+    }
+
     /// Formats [SP3] into writable I/O using efficient buffered writer
     /// and following standard specifications.
+    /// Returns [FormattingError::EmptyDataset] when this [SP3] does not contain
+    /// any epoch, because that would not produce a valid file.
     pub fn format<W: Write>(&self, writer: &mut BufWriter<W>) -> Result<(), FormattingError> {
-        let efmt = Format::from_str("%Y %m %d %H %M %S.%f").unwrap();
+        self.format_with_rounding(writer, RoundingMode::default())
+    }
+
+    /// Formats [SP3] like [SP3::format], using desired [RoundingMode].
+    pub fn format_with_rounding<W: Write>(
+        &self,
+        writer: &mut BufWriter<W>,
+        rounding: RoundingMode,
+    ) -> Result<(), FormattingError> {
+        if self.data.is_empty() {
+            return Err(FormattingError::EmptyDataset);
+        }
 
         self.header.format(writer)?;
 
-        for comment in self.comments.iter() {
-            writeln!(writer, "/* {}", comment)?;
-        }
+        format_comments(&self.comments, writer)?;
 
         for epoch in self.data.keys().map(|k| k.epoch).unique().sorted() {
-            let formatter = Formatter::new(epoch, efmt);
-
-            writeln!(writer, "*  {}", formatter)?;
+            format_epoch(epoch, writer)?;
 
             for key in self
                 .data
@@ -96,7 +286,7 @@ impl SP3 {
                 .sorted()
             {
                 if let Some(entry) = self.data.get(key) {
-                    entry.format(key.sv, writer)?;
+                    entry.format_with_rounding(key.sv, writer, rounding)?;
                 }
             }
         }
@@ -162,7 +352,175 @@ impl SP3 {
 
 #[cfg(test)]
 mod test {
-    use crate::prelude::SP3;
+    use crate::prelude::{
+        Constellation, Epoch, FormattingError, RoundingMode, SP3Builder, SP3Entry, SP3Writer,
+        TimeScale, SP3, SV,
+    };
+    use crate::tests::fixtures::{dummy_entry, sp3_builder, sp3_d_header, t0};
+    use crate::tests::formatting::Utf8Buffer;
+
+    use itertools::Itertools;
+    use std::io::BufWriter;
+    use std::str::FromStr;
+
+    #[test]
+    fn empty_dataset_formatting() {
+        let sp3 = SP3::default();
+
+        let mut buffer = BufWriter::new(Utf8Buffer::new(1024));
+
+        match sp3.format(&mut buffer) {
+            Err(FormattingError::EmptyDataset) => {},
+            Err(e) => panic!("invalid error: {}", e),
+            Ok(_) => panic!("formatting an empty dataset should fail"),
+        }
+
+        // should not panic either
+        let _ = sp3.standardized_filename();
+    }
+
+    #[test]
+    fn resolution() {
+        let content = sp3_d_header(1)
+            + "*  2023  8 27  0  0  0.00000000
+PG01 -22335.782004 -14656.280389  -1218.238499   -176.397152
+EOF";
+
+        for revision in ["#d", "#c", "#a"] {
+            let content = content.replace("#d", revision);
+            let sp3 = SP3::from_str(&content).unwrap();
+
+            assert_eq!(sp3.position_resolution_m(), 1.0E-3);
+            assert_eq!(sp3.clock_resolution_s(), 1.0E-12);
+        }
+
+        // the standard deviation base does not affect the resolution
+        let content = content.replace("%f  1.2500000  1.025000000", "%f  2.0000000  2.000000000");
+        let sp3 = SP3::from_str(&content).unwrap();
+
+        assert_eq!(sp3.header.pos_vel_base, (2.0, 2.0));
+        assert_eq!(sp3.position_resolution_m(), 1.0E-3);
+        assert_eq!(sp3.clock_resolution_s(), 1.0E-12);
+
+        // sub-resolution values are quantized
+        let g01 = SV::from_str("G01").unwrap();
+        let t0 = t0();
+
+        let mut builder = sp3_builder(Constellation::GPS);
+
+        builder.push_entry(
+            t0,
+            g01,
+            SP3Entry::from_position_km((1.0000004, 2.0, 3.0)).with_clock_offset_us(1.0000004),
+        );
+
+        let sp3 = builder.build().unwrap();
+
+        let mut buffer = BufWriter::new(Vec::new());
+        sp3.format(&mut buffer).unwrap();
+
+        let formatted = String::from_utf8(buffer.into_inner().unwrap()).unwrap();
+        let parsed = SP3::from_str(&formatted).unwrap();
+
+        let entry = parsed.data.values().next().unwrap();
+
+        let error_m = (entry.position_km.0 - 1.0000004).abs() * 1.0E3;
+        assert!(error_m > 0.0 && error_m < sp3.position_resolution_m());
+
+        let error_s = (entry.clock_us.unwrap() - 1.0000004).abs() * 1.0E-6;
+        assert!(error_s > 0.0 && error_s < sp3.clock_resolution_s());
+    }
+
+    #[test]
+    fn rounding_mode() {
+        let g01 = SV::from_str("G01").unwrap();
+        let t0 = t0();
+
+        // exact binary tie: 2⁻⁷ = 0.0078125
+        let mut builder = sp3_builder(Constellation::GPS);
+
+        builder.push_entry(
+            t0,
+            g01,
+            SP3Entry::from_position_km((20000.0078125, -20000.0078125, 3.0))
+                .with_clock_offset_us(1.0),
+        );
+
+        let sp3 = builder.build().unwrap();
+
+        for (rounding, x_km, y_km) in [
+            (RoundingMode::HalfEven, "20000.007812", "-20000.007812"),
+            (RoundingMode::HalfUp, "20000.007813", "-20000.007813"),
+        ] {
+            let mut buffer = BufWriter::new(Vec::new());
+            sp3.format_with_rounding(&mut buffer, rounding).unwrap();
+
+            let formatted = String::from_utf8(buffer.into_inner().unwrap()).unwrap();
+
+            let line = formatted
+                .lines()
+                .find(|line| line.starts_with("PG01"))
+                .unwrap();
+
+            assert_eq!(&line[4..18], format!("{:>14}", x_km), "{:?}", rounding);
+            assert_eq!(&line[18..32], format!("{:>14}", y_km), "{:?}", rounding);
+
+            // streamed formatting is identical
+            let mut writer = SP3Writer::new(Vec::new()).with_rounding(rounding);
+            writer.write_header(&sp3.header, &sp3.comments).unwrap();
+
+            writer
+                .write_epoch(t0, &[(g01, *sp3.data.values().next().unwrap())])
+                .unwrap();
+
+            let streamed = String::from_utf8(writer.finish().unwrap()).unwrap();
+            assert_eq!(streamed, formatted);
+        }
+
+        // default is unchanged
+        let mut buffer = BufWriter::new(Vec::new());
+        sp3.format(&mut buffer).unwrap();
+        let formatted = String::from_utf8(buffer.into_inner().unwrap()).unwrap();
+        assert!(formatted.contains("20000.007812"));
+    }
+
+    #[test]
+    fn unsupported_timescale_formatting() {
+        let g01 = SV::from_str("G01").unwrap();
+        let t0 = Epoch::from_str("2023-08-27T00:00:00 BDT").unwrap();
+
+        for (timescale, supported) in [
+            (TimeScale::GPST, true),
+            (TimeScale::GST, true),
+            (TimeScale::QZSST, true),
+            (TimeScale::UTC, true),
+            (TimeScale::TAI, true),
+            (TimeScale::BDT, false),
+        ] {
+            assert_eq!(
+                SP3::can_format_timescale(timescale),
+                supported,
+                "{}",
+                timescale
+            );
+        }
+
+        let mut builder = SP3Builder::default()
+            .with_constellation(Constellation::BeiDou)
+            .with_timescale(TimeScale::BDT);
+
+        builder.push_entry(t0, g01, dummy_entry());
+
+        let sp3 = builder.build().unwrap();
+
+        let mut buffer = BufWriter::new(Utf8Buffer::new(1024));
+
+        match sp3.format(&mut buffer) {
+            Err(FormattingError::UnsupportedTimescale(TimeScale::BDT)) => {},
+            Err(e) => panic!("invalid error: {}", e),
+            Ok(_) => panic!("BDT should not be mislabeled"),
+        }
+    }
 
     #[test]
     fn sp3_c_formatting() {
@@ -195,6 +553,45 @@ mod test {
         // assert_eq!(parsed, sp3);
     }
 
+    #[test]
+    fn streamed_formatting() {
+        let sp3 = SP3::from_file("data/SP3/D/example.txt").unwrap();
+
+        let mut buffer = BufWriter::new(Vec::<u8>::new());
+        sp3.format(&mut buffer).unwrap();
+
+        let formatted = buffer.into_inner().unwrap();
+
+        let mut writer = SP3Writer::new(Vec::<u8>::new());
+
+        writer.write_header(&sp3.header, &sp3.comments).unwrap();
+
+        for epoch in sp3.epochs_iter().sorted() {
+            let mut entries = sp3
+                .data
+                .iter()
+                .filter_map(|(k, v)| {
+                    if k.epoch == epoch {
+                        Some((k.sv, *v))
+                    } else {
+                        None
+                    }
+                })
+                .collect::<Vec<_>>();
+
+            entries.sort_by_key(|(sv, _)| *sv);
+
+            writer.write_epoch(epoch, &entries).unwrap();
+        }
+
+        let streamed = writer.finish().unwrap();
+
+        assert_eq!(
+            String::from_utf8(streamed).unwrap(),
+            String::from_utf8(formatted).unwrap()
+        );
+    }
+
     #[test]
     fn sp3_c_predicted_orbits() {
         let sp3 =
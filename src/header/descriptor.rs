@@ -0,0 +1,168 @@
+//! `%c` file descriptor lines
+use std::io::{BufWriter, Write};
+
+use crate::errors::FormattingError;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+#[cfg(doc)]
+use crate::prelude::Header;
+
+/// Width of each field of one `%c` line (columns 4-60)
+const FIELD_WIDTHS: [usize; 12] = [2, 2, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5];
+
+/// Index of the file type and time system fields, on the first `%c` line
+const FILE_TYPE_FIELD: usize = 0;
+const TIME_SYSTEM_FIELD: usize = 2;
+
+/// [FileDescriptor] gathers the fields of the two `%c` header lines that we do not interpret.
+/// The specifications only define the file type and time system of the first line,
+/// which we interpret as [Header::constellation] and [Header::timescale].
+/// All other fields are reserved (they usually contain "c" placeholders),
+/// but are preserved so they survive a parsing / formatting round trip.
+/// Values longer than their field are truncated when formatting.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct FileDescriptor {
+    /// Reserved fields of the first `%c` line: columns 7-8, then 14-60.
+    pub line1_reserved: [String; 10],
+
+    /// Reserved fields of the second `%c` line (columns 4-60).
+    pub line2_reserved: [String; 12],
+}
+
+impl Default for FileDescriptor {
+    /// Placeholders, as specified.
+    fn default() -> Self {
+        let placeholders = FIELD_WIDTHS.map(|width| "c".repeat(width));
+
+        let mut descriptor = Self {
+            line1_reserved: Default::default(),
+            line2_reserved: placeholders.clone(),
+        };
+
+        descriptor.set_reserved_fields(0, placeholders);
+        descriptor
+    }
+}
+
+/// Splits one `%c` line into its 12 fields (trailing whitespaces are dropped).
+/// Returns None if the line is too short.
+pub(crate) fn descriptor_fields(line: &str) -> Option<[String; 12]> {
+    let mut start = 3;
+    let mut fields: [String; 12] = Default::default();
+
+    for (field, width) in fields.iter_mut().zip(FIELD_WIDTHS) {
+        *field = line.get(start..start + width)?.trim_end().to_string();
+        start += width + 1;
+    }
+
+    Some(fields)
+}
+
+// Formats one `%c` line
+fn format_fields<W: Write>(
+    fields: &[&str; 12],
+    writer: &mut BufWriter<W>,
+) -> Result<(), FormattingError> {
+    write!(writer, "%c")?;
+
+    for (field, width) in fields.iter().zip(FIELD_WIDTHS) {
+        write!(writer, " {:<width$.width$}", field, width = width)?;
+    }
+
+    writeln!(writer)?;
+    Ok(())
+}
+
+impl FileDescriptor {
+    /// Stores the reserved fields of this `%c` line (#0 or #1).
+    pub(crate) fn set_reserved_fields(&mut self, line_index: u8, fields: [String; 12]) {
+        match line_index {
+            0 => {
+                let mut reserved = fields
+                    .into_iter()
+                    .enumerate()
+                    .filter(|(i, _)| *i != FILE_TYPE_FIELD && *i != TIME_SYSTEM_FIELD)
+                    .map(|(_, field)| field);
+
+                for field in self.line1_reserved.iter_mut() {
+                    *field = reserved.next().unwrap_or_default();
+                }
+            },
+            1 => self.line2_reserved = fields,
+            _ => {},
+        }
+    }
+
+    /// Formats both `%c` lines, with this file type and time system descriptor.
+    pub(crate) fn format<W: Write>(
+        &self,
+        file_type: &str,
+        time_system: &str,
+        writer: &mut BufWriter<W>,
+    ) -> Result<(), FormattingError> {
+        let mut reserved = self.line1_reserved.iter();
+
+        let line1 = std::array::from_fn(|i| match i {
+            FILE_TYPE_FIELD => file_type,
+            TIME_SYSTEM_FIELD => time_system,
+            _ => reserved
+                .next()
+                .map(|field| field.as_str())
+                .unwrap_or_default(),
+        });
+
+        format_fields(&line1, writer)?;
+        format_fields(
+            &self.line2_reserved.each_ref().map(|field| field.as_str()),
+            writer,
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{descriptor_fields, FileDescriptor};
+    use std::io::BufWriter;
+
+    #[test]
+    fn file_descriptor() {
+        let line1 = "%c M  cc GPS ccc cccc cccc cccc cccc ccccc ccccc ccccc ccccc";
+        let line2 = "%c cc cc ccc ccc cccc cccc cccc cccc ccccc ccccc ccccc ccccc";
+
+        let fields = descriptor_fields(line1).unwrap();
+        assert_eq!(fields[0], "M");
+        assert_eq!(fields[2], "GPS");
+        assert_eq!(fields[11], "ccccc");
+
+        let mut descriptor = FileDescriptor::default();
+        descriptor.set_reserved_fields(0, fields);
+        descriptor.set_reserved_fields(1, descriptor_fields(line2).unwrap());
+
+        assert_eq!(descriptor, FileDescriptor::default());
+
+        // customized reserved fields
+        let custom1 = "%c G  ab GPS xyz 1234 cccc cccc   99 ccccc ccccc ccccc 00042";
+        let custom2 = "%c R  cc ccc ccc cccc cccc cccc cccc ccccc abcde ccccc ccccc";
+
+        let mut descriptor = FileDescriptor::default();
+        descriptor.set_reserved_fields(0, descriptor_fields(custom1).unwrap());
+        descriptor.set_reserved_fields(1, descriptor_fields(custom2).unwrap());
+
+        assert_eq!(descriptor.line1_reserved[0], "ab");
+        assert_eq!(descriptor.line1_reserved[1], "xyz");
+        assert_eq!(descriptor.line1_reserved[5], "  99");
+        assert_eq!(descriptor.line2_reserved[0], "R");
+
+        let mut buffer = BufWriter::new(Vec::new());
+        descriptor.format("G", "GPS", &mut buffer).unwrap();
+
+        let formatted = String::from_utf8(buffer.into_inner().unwrap()).unwrap();
+        assert_eq!(formatted, format!("{}\n{}\n", custom1, custom2));
+
+        // too short
+        assert!(descriptor_fields("%c M  cc GPS").is_none());
+    }
+}
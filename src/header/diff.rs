@@ -0,0 +1,163 @@
+//! header comparison
+use crate::prelude::{Constellation, DataType, Duration, Header, OrbitType, TimeScale, Version};
+
+/// [HeaderFieldDiff] describes one descriptive [Header] field
+/// that differs between two [Header]s, as (lhs, rhs) values.
+#[derive(Debug, Clone, PartialEq)]
+pub enum HeaderFieldDiff {
+    /// Different file [Version]s
+    Version(Version, Version),
+
+    /// Different [DataType]s
+    DataType(DataType, DataType),
+
+    /// Different production agencies
+    Agency(String, String),
+
+    /// Different [Constellation]s
+    Constellation(Constellation, Constellation),
+
+    /// Different coordinates systems (reference frames)
+    CoordSystem(String, String),
+
+    /// Different [TimeScale]s
+    TimeScale(TimeScale, TimeScale),
+
+    /// Different [OrbitType]s
+    OrbitType(OrbitType, OrbitType),
+
+    /// Different sampling periods
+    SamplingPeriod(Duration, Duration),
+}
+
+impl std::fmt::Display for HeaderFieldDiff {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Version(lhs, rhs) => write!(f, "version: {} != {}", lhs, rhs),
+            Self::DataType(lhs, rhs) => write!(f, "data type: {} != {}", lhs, rhs),
+            Self::Agency(lhs, rhs) => write!(f, "agency: {} != {}", lhs, rhs),
+            Self::Constellation(lhs, rhs) => write!(f, "constellation: {} != {}", lhs, rhs),
+            Self::CoordSystem(lhs, rhs) => write!(f, "coordinates system: {} != {}", lhs, rhs),
+            Self::TimeScale(lhs, rhs) => write!(f, "timescale: {} != {}", lhs, rhs),
+            Self::OrbitType(lhs, rhs) => write!(f, "orbit type: {} != {}", lhs, rhs),
+            Self::SamplingPeriod(lhs, rhs) => write!(f, "sampling period: {} != {}", lhs, rhs),
+        }
+    }
+}
+
+impl Header {
+    /// Lists the descriptive fields that differ between self and `other` [Header].
+    /// The list is empty when both [Header]s describe similar products.
+    /// Time references (publication epoch, week counter..), number of epochs
+    /// and satellites are not compared, because they naturally differ between products.
+    pub fn diff(&self, other: &Header) -> Vec<HeaderFieldDiff> {
+        let mut diff = Vec::new();
+
+        if self.version != other.version {
+            diff.push(HeaderFieldDiff::Version(self.version, other.version));
+        }
+
+        if self.data_type != other.data_type {
+            diff.push(HeaderFieldDiff::DataType(self.data_type, other.data_type));
+        }
+
+        if self.agency != other.agency {
+            diff.push(HeaderFieldDiff::Agency(
+                self.agency.clone(),
+                other.agency.clone(),
+            ));
+        }
+
+        if self.constellation != other.constellation {
+            diff.push(HeaderFieldDiff::Constellation(
+                self.constellation,
+                other.constellation,
+            ));
+        }
+
+        if self.coord_system != other.coord_system {
+            diff.push(HeaderFieldDiff::CoordSystem(
+                self.coord_system.clone(),
+                other.coord_system.clone(),
+            ));
+        }
+
+        if self.timescale != other.timescale {
+            diff.push(HeaderFieldDiff::TimeScale(self.timescale, other.timescale));
+        }
+
+        if self.orbit_type != other.orbit_type {
+            diff.push(HeaderFieldDiff::OrbitType(
+                self.orbit_type,
+                other.orbit_type,
+            ));
+        }
+
+        if self.sampling_period != other.sampling_period {
+            diff.push(HeaderFieldDiff::SamplingPeriod(
+                self.sampling_period,
+                other.sampling_period,
+            ));
+        }
+
+        diff
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::HeaderFieldDiff;
+    use crate::prelude::{Duration, OrbitType, TimeScale, SP3};
+    use crate::tests::fixtures::sp3_d_header;
+    use std::str::FromStr;
+
+    #[test]
+    fn header_diff() {
+        let content = sp3_d_header(1)
+            + "*  2023  8 27  0  0  0.00000000
+PG01 -22335.782004 -14656.280389  -1218.238499   -176.397152
+EOF";
+
+        let lhs = SP3::from_str(&content).unwrap();
+        assert!(lhs.header.diff(&lhs.header).is_empty());
+
+        // same product, published later
+        let later = content
+            .replace("#dP2023  8 27  0  0", "#dP2023  8 27  0 15")
+            .replace("*  2023  8 27  0  0", "*  2023  8 27  0 15");
+
+        let later = SP3::from_str(&later).unwrap();
+        assert!(lhs.header.diff(&later.header).is_empty());
+
+        let rhs = content
+            .replace("IGS20 FIT  IGS", "IGb14 BHN  ESA")
+            .replace("   900.00000000", "   300.00000000");
+
+        let rhs = SP3::from_str(&rhs).unwrap();
+
+        let diff = lhs.header.diff(&rhs.header);
+
+        assert_eq!(
+            diff,
+            vec![
+                HeaderFieldDiff::Agency("IGS".to_string(), "ESA".to_string()),
+                HeaderFieldDiff::CoordSystem("IGS20".to_string(), "IGb14".to_string()),
+                HeaderFieldDiff::OrbitType(OrbitType::FIT, OrbitType::BHN),
+                HeaderFieldDiff::SamplingPeriod(
+                    Duration::from_seconds(900.0),
+                    Duration::from_seconds(300.0)
+                ),
+            ]
+        );
+
+        assert_eq!(diff[0].to_string(), "agency: IGS != ESA");
+
+        let mut rhs = lhs.header.clone();
+        rhs.timescale = TimeScale::GST;
+
+        assert_eq!(
+            lhs.header.diff(&rhs),
+            vec![HeaderFieldDiff::TimeScale(TimeScale::GPST, TimeScale::GST)]
+        );
+    }
+}
@@ -0,0 +1,143 @@
+//! reference frame identification
+use crate::prelude::Header;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// [ReferenceFrameHint] identifies the terrestrial reference frame
+/// (coordinates system) a file is expressed in, from the raw [Header] descriptor.
+/// All of them are Earth centered Earth fixed (ECEF) frames.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ReferenceFrameHint {
+    /// ITRF of unspecified realization, usually because the descriptor
+    /// was truncated (for example "ITRF2").
+    ITRF,
+
+    /// ITRF2000 realization
+    ITRF2000,
+
+    /// ITRF2005 realization
+    ITRF2005,
+
+    /// ITRF2008 realization
+    ITRF2008,
+
+    /// ITRF2014 realization
+    ITRF2014,
+
+    /// ITRF2020 realization
+    ITRF2020,
+
+    /// IGS realization of ITRF2005
+    IGS05,
+
+    /// IGS realization of ITRF2008
+    IGS08,
+
+    /// Updated IGS realization of ITRF2008
+    IGb08,
+
+    /// IGS realization of ITRF2014
+    IGS14,
+
+    /// Updated IGS realization of ITRF2014
+    IGb14,
+
+    /// IGS realization of ITRF2020
+    IGS20,
+
+    /// World Geodetic System 84
+    WGS84,
+}
+
+impl std::fmt::Display for ReferenceFrameHint {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::ITRF => f.write_str("ITRF"),
+            Self::ITRF2000 => f.write_str("ITRF2000"),
+            Self::ITRF2005 => f.write_str("ITRF2005"),
+            Self::ITRF2008 => f.write_str("ITRF2008"),
+            Self::ITRF2014 => f.write_str("ITRF2014"),
+            Self::ITRF2020 => f.write_str("ITRF2020"),
+            Self::IGS05 => f.write_str("IGS05"),
+            Self::IGS08 => f.write_str("IGS08"),
+            Self::IGb08 => f.write_str("IGb08"),
+            Self::IGS14 => f.write_str("IGS14"),
+            Self::IGb14 => f.write_str("IGb14"),
+            Self::IGS20 => f.write_str("IGS20"),
+            Self::WGS84 => f.write_str("WGS84"),
+        }
+    }
+}
+
+impl ReferenceFrameHint {
+    /// Identifies the [ReferenceFrameHint] from a raw coordinates system descriptor,
+    /// like "IGS20" or "ITR14" (case insensitive). Returns None for unknown descriptors.
+    pub fn from_descriptor(descriptor: &str) -> Option<Self> {
+        match descriptor.trim().to_uppercase().as_str() {
+            "ITRF" | "ITRF2" => Some(Self::ITRF),
+            "ITR00" | "ITRF00" | "ITRF2000" => Some(Self::ITRF2000),
+            "ITR05" | "ITRF05" | "ITRF2005" => Some(Self::ITRF2005),
+            "ITR08" | "ITRF08" | "ITRF2008" => Some(Self::ITRF2008),
+            "ITR14" | "ITRF14" | "ITRF2014" => Some(Self::ITRF2014),
+            "ITR20" | "ITRF20" | "ITRF2020" => Some(Self::ITRF2020),
+            "IGS05" => Some(Self::IGS05),
+            "IGS08" => Some(Self::IGS08),
+            "IGB08" => Some(Self::IGb08),
+            "IGS14" => Some(Self::IGS14),
+            "IGB14" => Some(Self::IGb14),
+            "IGS20" => Some(Self::IGS20),
+            "WGS84" | "WGS-84" => Some(Self::WGS84),
+            _ => None,
+        }
+    }
+}
+
+impl Header {
+    /// Identifies the reference frame this file is expressed in,
+    /// from the [Header] coordinates system descriptor.
+    /// This helps selecting the matching ECEF frame, when converting to orbits.
+    /// Returns None if the descriptor is unknown.
+    pub fn try_reference_frame(&self) -> Option<ReferenceFrameHint> {
+        ReferenceFrameHint::from_descriptor(&self.coord_system)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::ReferenceFrameHint;
+    use crate::prelude::Header;
+
+    #[test]
+    fn reference_frame_hint() {
+        for (coord_system, expected) in [
+            ("IGS14", Some(ReferenceFrameHint::IGS14)),
+            ("IGS20", Some(ReferenceFrameHint::IGS20)),
+            ("IGb14", Some(ReferenceFrameHint::IGb14)),
+            ("IGb08", Some(ReferenceFrameHint::IGb08)),
+            ("ITRF2", Some(ReferenceFrameHint::ITRF)),
+            ("ITR14", Some(ReferenceFrameHint::ITRF2014)),
+            ("ITRF2020", Some(ReferenceFrameHint::ITRF2020)),
+            ("WGS84", Some(ReferenceFrameHint::WGS84)),
+            (" igs20 ", Some(ReferenceFrameHint::IGS20)),
+            ("ITRF93", None),
+            ("", None),
+        ] {
+            let header = Header {
+                coord_system: coord_system.to_string(),
+                ..Default::default()
+            };
+
+            assert_eq!(
+                header.try_reference_frame(),
+                expected,
+                "failed for \"{}\"",
+                coord_system
+            );
+        }
+
+        assert_eq!(ReferenceFrameHint::IGb14.to_string(), "IGb14");
+        assert_eq!(ReferenceFrameHint::ITRF2014.to_string(), "ITRF2014");
+    }
+}
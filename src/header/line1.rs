@@ -15,7 +15,9 @@ pub(crate) fn is_header_line1(content: &str) -> bool {
 pub(crate) struct Line1 {
     pub version: Version,
     pub data_type: DataType,
-    pub epoch: Epoch,
+    /// None when the epoch field is malformed (or zeroed)
+    pub epoch: Option<Epoch>,
+    /// 5-character column, parsed and formatted as is
     pub observables: String,
     pub num_epochs: u64,
     pub coord_system: String,
@@ -41,15 +43,18 @@ impl std::str::FromStr for Line1 {
             &line[23..31].trim(),
         );
 
-        let y = y.parse::<i32>().or(Err(ParsingError::Epoch))?;
-        let m = m.parse::<u8>().or(Err(ParsingError::Epoch))?;
-        let d = d.parse::<u8>().or(Err(ParsingError::Epoch))?;
-        let hh = hh.parse::<u8>().or(Err(ParsingError::Epoch))?;
-        let mm = mm.parse::<u8>().or(Err(ParsingError::Epoch))?;
-        let ss = ss.parse::<u8>().or(Err(ParsingError::Epoch))?;
-        let nanos = nanos.parse::<u32>().or(Err(ParsingError::Epoch))?;
+        // a malformed epoch may be recovered from line #2 later on
+        let epoch = (|| {
+            let y = y.parse::<i32>().ok()?;
+            let m = m.parse::<u8>().ok()?;
+            let d = d.parse::<u8>().ok()?;
+            let hh = hh.parse::<u8>().ok()?;
+            let mm = mm.parse::<u8>().ok()?;
+            let ss = ss.parse::<u8>().ok()?;
+            let nanos = nanos.parse::<u32>().ok()?;
 
-        let epoch = Epoch::from_gregorian_utc(y, m, d, hh, mm, ss, nanos * 10);
+            Epoch::maybe_from_gregorian_utc(y, m, d, hh, mm, ss, nanos * 10).ok()
+        })();
 
         let num_epochs = &line[32..40].trim();
         let num_epochs = num_epochs.parse::<u64>().or(Err(ParsingError::Epoch))?;
@@ -70,11 +75,14 @@ impl std::str::FromStr for Line1 {
 impl Line1 {
     /// Formats [Line1] according to SP3 standards.
     pub fn format<W: Write>(&self, w: &mut BufWriter<W>) -> Result<(), FormattingError> {
-        let (y, m, d, hh, mm, ss, nanos) = self.epoch.to_gregorian_utc();
+        let (y, m, d, hh, mm, ss, nanos) = match self.epoch {
+            Some(epoch) => epoch.to_gregorian_utc(),
+            None => (0, 0, 0, 0, 0, 0, 0),
+        };
 
         write!(
             w,
-            "#{}{}{:04} {:2} {:2} {:2} {:2} {:2}.{:08} {:7} {} {} {}  {}",
+            "#{}{}{:04} {:2} {:2} {:2} {:2} {:2}.{:08} {:7} {:>5.5} {:>5} {}  {}",
             self.version,
             self.data_type,
             y,
@@ -205,7 +213,7 @@ mod test {
             assert_eq!(line1.version, version);
             assert_eq!(line1.coord_system, coord_system);
             assert_eq!(line1.orbit_type, orbit_type);
-            assert_eq!(line1.epoch, epoch);
+            assert_eq!(line1.epoch, Some(epoch));
             assert_eq!(line1.data_type, dtype);
             assert_eq!(line1.observables, observables);
             assert_eq!(line1.num_epochs, num_epochs);
@@ -1,9 +1,16 @@
 //! header parsing utilities
 pub(crate) mod line1;
 pub(crate) mod line2;
+pub(crate) mod satellites;
 
-use std::io::{BufWriter, Write};
+use std::{
+    collections::BTreeMap,
+    io::{BufWriter, Write},
+};
 
+pub mod descriptor;
+pub mod diff;
+pub mod frame;
 pub mod version;
 
 use crate::{
@@ -15,8 +22,10 @@ use crate::{
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
+use descriptor::FileDescriptor;
 use line1::Line1;
 use line2::Line2;
+use satellites::format_satellites_accuracy;
 
 #[derive(Default, Copy, Clone, Debug, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -96,6 +105,19 @@ impl std::str::FromStr for OrbitType {
     }
 }
 
+/// Returns the `%c` descriptor of this [TimeScale],
+/// or None if we do not support it (file descriptor support is incomplete).
+pub(crate) fn timescale_descriptor(timescale: TimeScale) -> Option<&'static str> {
+    match timescale {
+        TimeScale::GPST => Some("GPS"),
+        TimeScale::GST => Some("GAL"),
+        TimeScale::QZSST => Some("QZS"),
+        TimeScale::UTC => Some("UTC"),
+        TimeScale::TAI => Some("TAI"),
+        _ => None,
+    }
+}
+
 #[derive(Debug, Default, PartialEq, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Header {
@@ -113,10 +135,14 @@ pub struct Header {
     /// Coordinates system description.
     pub coord_system: String,
 
-    /// [OrbitType] used in the fitting process prior publication.
+    /// [OrbitType] used in the fitting process prior publication:
+    /// the "orbit type" (or fit type) descriptor of line #1 (columns 53-55), like "FIT".
+    /// This is not to be confused with [Self::observables].
     pub orbit_type: OrbitType,
 
-    /// "Observables" used for this fit, we parse "as is".
+    /// "Observables" used for this fit: the "data used" descriptor of line #1
+    /// (columns 41-45), like "__u+U". We parse "as is"
+    /// and format "as is" (values longer than 5 characters are truncated).
     /// Explanations on typical values:
     /// - `u`  undifferenced carrier phase
     /// - `du` change in u with time
@@ -161,17 +187,132 @@ pub struct Header {
     /// Sampling period, as [Duration].
     pub sampling_period: Duration,
 
-    /// [SV] to be found in this record.
+    /// [SV]s described in this file, sorted. When parsing, these are the satellites
+    /// declared in the satellite (`+`) lines, completed by the satellites found in the record.
     pub satellites: Vec<SV>,
+
+    /// Number of satellites declared in the satellite (`+`) lines,
+    /// which is the number of records expected at each [Epoch].
+    /// It may differ from the length of [Self::satellites] when parsing
+    /// inconsistent files (see [crate::SP3::validate]).
+    pub num_satellites: usize,
+
+    /// Accuracy exponents of each [SV], as declared in the `++` lines.
+    /// Unknown (null) exponents are not stored.
+    /// See [Self::satellite_accuracy].
+    pub accuracy_exponents: BTreeMap<SV, u8>,
+
+    /// Floating point bases used to decode the standard deviation exponents
+    /// of the record, as declared in the first `%f` line:
+    /// (position and velocity base, clock and clock rate base).
+    /// Null when not defined.
+    /// See [Self::position_std_mm] and [Self::clock_std_ps].
+    pub pos_vel_base: (f64, f64),
+
+    /// Other floating point parameters (`%f` lines), which are not used
+    /// by the current specifications but are preserved as is.
+    pub float_params: [f64; 6],
+
+    /// Integer parameters (`%i` lines), which are not used
+    /// by the current specifications but are preserved as is.
+    pub int_params: [i32; 18],
+
+    /// Reserved fields of the `%c` lines, which are not used
+    /// by the current specifications but are preserved as is.
+    pub file_descriptor: FileDescriptor,
 }
 
 impl Header {
+    /// Updates the GPS week and time of week (`##` line) so they match the
+    /// provided first [Epoch] of the record, expressed in [Self.timescale].
+    /// This should be called when synthesizing or modifying an [SP3](crate::prelude::SP3),
+    /// to keep the header consistent.
+    pub fn align_week_to_first_epoch(&mut self, first: Epoch) {
+        (self.week, self.week_nanos) = first.to_time_scale(self.timescale).to_time_of_week();
+    }
+
+    /// Updates the MJD and fraction of day (`##` line) so they match the
+    /// provided first [Epoch] of the record, expressed in [Self.timescale].
+    /// Like [Self::align_week_to_first_epoch], this keeps the header consistent.
+    pub fn align_mjd_to_first_epoch(&mut self, first: Epoch) {
+        let mjd_days = (first - Epoch::from_mjd_in_time_scale(0.0, self.timescale))
+            .to_unit(hifitime::Unit::Day);
+
+        self.mjd = mjd_days.floor() as u32;
+        self.mjd_fraction = mjd_days.fract();
+    }
+
+    /// Recovers the publication [Epoch] from the time fields of the second
+    /// header line (`##` line): GPS week and time of week, or MJD and fraction of day
+    /// when the week is not specified. Both are expressed in [Self.timescale].
+    /// This is used when the line #1 epoch is malformed.
+    /// Returns None if the time fields are not specified either.
+    pub fn release_epoch_from_time_fields(&self) -> Option<Epoch> {
+        if self.week > 0 || self.week_nanos > 0 {
+            Some(Epoch::from_time_of_week(
+                self.week,
+                self.week_nanos,
+                self.timescale,
+            ))
+        } else if self.mjd > 0 {
+            Some(Epoch::from_mjd_in_time_scale(
+                self.mjd as f64 + self.mjd_fraction,
+                self.timescale,
+            ))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the declared orbit accuracy of this [SV], in meters.
+    /// The accuracy exponent (`++` lines) stands for 2^exp millimeters.
+    /// Returns None if this [SV] was not declared or its accuracy is unknown.
+    pub fn satellite_accuracy(&self, sv: SV) -> Option<f64> {
+        let exponent = self.accuracy_exponents.get(&sv)?;
+        Some(2.0_f64.powi(*exponent as i32) / 1000.0)
+    }
+
+    /// Decodes the (x, y, z) position standard deviation exponents
+    /// of a record entry (see [SP3Entry](crate::prelude::SP3Entry)), using the `%f` base.
+    /// Returns (σx, σy, σz) in millimeters, or None if the base is not defined.
+    pub fn position_std_mm(&self, exponents: (u16, u16, u16)) -> Option<(f64, f64, f64)> {
+        let base = self.pos_vel_base.0;
+
+        if base <= 0.0 {
+            return None;
+        }
+
+        Some((
+            base.powi(exponents.0 as i32),
+            base.powi(exponents.1 as i32),
+            base.powi(exponents.2 as i32),
+        ))
+    }
+
+    /// Decodes the clock standard deviation exponent of a record entry
+    /// (see [SP3Entry](crate::prelude::SP3Entry)), using the `%f` base.
+    /// Returns σ in picoseconds, or None if the base is not defined.
+    pub fn clock_std_ps(&self, exponent: u32) -> Option<f64> {
+        let base = self.pos_vel_base.1;
+
+        if base <= 0.0 {
+            return None;
+        }
+
+        Some(base.powi(exponent as i32))
+    }
+
     /// Format this SP3 [Header] according to standard specifications.
+    /// Returns [FormattingError::UnsupportedTimescale] if [Self.timescale]
+    /// cannot be described (see [SP3::can_format_timescale](crate::prelude::SP3::can_format_timescale)).
     pub fn format<W: Write>(&self, writer: &mut BufWriter<W>) -> Result<(), FormattingError> {
+        let gnss_timescale = timescale_descriptor(self.timescale)
+            .ok_or(FormattingError::UnsupportedTimescale(self.timescale))?;
+
         let line1 = Line1 {
             version: self.version,
             data_type: self.data_type,
-            epoch: self.release_epoch,
+            epoch: Some(self.release_epoch),
             num_epochs: self.num_epochs,
             orbit_type: self.orbit_type,
             agency: self.agency.to_string(),
@@ -192,30 +333,41 @@ impl Header {
         line2.format(writer)?;
         writeln!(writer)?;
 
-        // file descriptor support is incomplete
-        let gnss_timescale = match self.timescale {
-            TimeScale::GPST => "GPS",
-            TimeScale::GST => "GAL",
-            TimeScale::QZSST => "QZS",
-            TimeScale::UTC => "UTC",
-            _ => "TAI",
-        };
+        format_satellites_accuracy(&self.satellites, &self.accuracy_exponents, writer)?;
 
         // TODO: `L` exists here in case only LEO vehicles are to be found
+        let file_type = format!("{:x}", self.constellation);
+
+        self.file_descriptor
+            .format(&file_type, gnss_timescale, writer)?;
+
         writeln!(
             writer,
-            "%c {:x}  cc {} ccc cccc cccc cccc cccc ccccc ccccc ccccc ccccc",
-            self.constellation, gnss_timescale,
+            "%f {:10.7} {:12.9} {:14.11} {:18.15}",
+            self.pos_vel_base.0, self.pos_vel_base.1, self.float_params[0], self.float_params[1],
         )?;
 
         writeln!(
             writer,
-            "%c cc cc ccc ccc cccc cccc cccc cccc ccccc ccccc ccccc ccccc"
+            "%f {:10.7} {:12.9} {:14.11} {:18.15}",
+            self.float_params[2], self.float_params[3], self.float_params[4], self.float_params[5],
         )?;
 
-        // other fields are not supported
-        // %i
-        // %f
+        for params in self.int_params.chunks(9) {
+            writeln!(
+                writer,
+                "%i {:4} {:4} {:4} {:4} {:6} {:6} {:6} {:6} {:9}",
+                params[0],
+                params[1],
+                params[2],
+                params[3],
+                params[4],
+                params[5],
+                params[6],
+                params[7],
+                params[8],
+            )?;
+        }
 
         Ok(())
     }
@@ -226,11 +378,78 @@ mod test {
     use crate::prelude::{
         Constellation, DataType, Duration, Epoch, Header, OrbitType, TimeScale, Version, SV,
     };
-    use crate::tests::formatting::Utf8Buffer;
+    use crate::tests::fixtures::t0;
+    use crate::{header::line1::Line1, tests::formatting::Utf8Buffer};
 
     use std::io::BufWriter;
     use std::str::FromStr;
 
+    #[test]
+    fn align_week_to_first_epoch() {
+        let mut header = Header {
+            timescale: TimeScale::GPST,
+            ..Default::default()
+        };
+
+        for first in [
+            "2020-01-01T00:00:00 GPST",
+            "2023-08-27T00:00:00 GPST",
+            "2023-08-29T17:45:00 GPST",
+            "2019-10-27T00:00:00 UTC",
+        ] {
+            let first = Epoch::from_str(first).unwrap();
+
+            header.align_week_to_first_epoch(first);
+
+            assert!(
+                header.week_nanos < Duration::from_days(7.0).total_nanoseconds() as u64,
+                "time of week should be truncated to the week"
+            );
+
+            assert_eq!(
+                Epoch::from_time_of_week(header.week, header.week_nanos, header.timescale),
+                first,
+            );
+        }
+
+        let first = t0();
+        header.align_week_to_first_epoch(first);
+
+        assert_eq!(header.week, 2277);
+        assert_eq!(header.week_nanos, 0);
+    }
+
+    #[test]
+    fn line1_fields_roundtrip() {
+        let header = Header {
+            observables: "__u+U".to_string(),
+            coord_system: "IGS20".to_string(),
+            orbit_type: OrbitType::BHN,
+            agency: "ESA".to_string(),
+            num_epochs: 96,
+            timescale: TimeScale::GPST,
+            ..Default::default()
+        };
+
+        let mut buffer = BufWriter::new(Vec::new());
+        header.format(&mut buffer).unwrap();
+
+        let formatted = String::from_utf8(buffer.into_inner().unwrap()).unwrap();
+        let line1 = formatted.lines().next().unwrap();
+
+        // observables (data used) and orbit type occupy distinct columns
+        assert_eq!(&line1[40..45], "__u+U");
+        assert_eq!(&line1[52..55], "BHN");
+
+        let parsed = Line1::from_str(line1).unwrap();
+
+        assert_eq!(parsed.observables, header.observables);
+        assert_eq!(parsed.coord_system, header.coord_system);
+        assert_eq!(parsed.orbit_type, header.orbit_type);
+        assert_eq!(parsed.agency, header.agency);
+        assert_eq!(parsed.num_epochs, header.num_epochs);
+    }
+
     #[test]
     #[ignore]
     fn header_formatting() {
@@ -254,6 +473,12 @@ mod test {
                 .split(',')
                 .map(|s| SV::from_str(s).unwrap())
                 .collect(),
+            num_satellites: 5,
+            accuracy_exponents: Default::default(),
+            pos_vel_base: (1.25, 1.025),
+            float_params: Default::default(),
+            int_params: Default::default(),
+            file_descriptor: Default::default(),
         };
 
         let mut buffer = BufWriter::new(Utf8Buffer::new(8192));
@@ -269,8 +494,22 @@ mod test {
             formatted,
             "#cP2019 12 31 23 59 42.00000000      10 __u+U ITRF93 FIT  GRGS
 ## 1234      0.00000567   900.00000000 00012 33999999.0000000999999
++    5   G01G02G03G04G05  0  0  0  0  0  0  0  0  0  0  0  0
++          0  0  0  0  0  0  0  0  0  0  0  0  0  0  0  0  0
++          0  0  0  0  0  0  0  0  0  0  0  0  0  0  0  0  0
++          0  0  0  0  0  0  0  0  0  0  0  0  0  0  0  0  0
++          0  0  0  0  0  0  0  0  0  0  0  0  0  0  0  0  0
+++         0  0  0  0  0  0  0  0  0  0  0  0  0  0  0  0  0
+++         0  0  0  0  0  0  0  0  0  0  0  0  0  0  0  0  0
+++         0  0  0  0  0  0  0  0  0  0  0  0  0  0  0  0  0
+++         0  0  0  0  0  0  0  0  0  0  0  0  0  0  0  0  0
+++         0  0  0  0  0  0  0  0  0  0  0  0  0  0  0  0  0
 %c G  cc GPS ccc cccc cccc cccc cccc ccccc ccccc ccccc ccccc
-%c cc cc ccc ccc cccc cccc cccc cccc ccccc ccccc ccccc ccccc\n"
+%c cc cc ccc ccc cccc cccc cccc cccc ccccc ccccc ccccc ccccc
+%f  1.2500000  1.025000000  0.00000000000  0.000000000000000
+%f  0.0000000  0.000000000  0.00000000000  0.000000000000000
+%i    0    0    0    0      0      0      0      0         0
+%i    0    0    0    0      0      0      0      0         0\n"
         );
     }
 }
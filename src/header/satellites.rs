@@ -0,0 +1,197 @@
+//! header satellites (`+`) and accuracy (`++`) lines helpers
+use std::{
+    collections::BTreeMap,
+    io::{BufWriter, Write},
+    str::FromStr,
+};
+
+use crate::{
+    prelude::{Constellation, Version, SV},
+    FormattingError, ParsingError,
+};
+
+/// Number of items per `+` / `++` line
+const ITEMS_PER_LINE: usize = 17;
+
+/// Minimal number of `+` / `++` lines
+const MIN_NUM_LINES: usize = 5;
+
+pub(crate) fn is_header_satellites_line(content: &str) -> bool {
+    content.starts_with('+') && !content.starts_with("++")
+}
+
+pub(crate) fn is_header_accuracy_line(content: &str) -> bool {
+    content.starts_with("++")
+}
+
+/// Iterates the (up to 17) 3-character fields of a `+` or `++` line.
+fn fields(line: &str) -> impl Iterator<Item = &str> {
+    (0..ITEMS_PER_LINE).filter_map(move |i| {
+        let start = 9 + i * 3;
+        line.get(start..(start + 3).min(line.len()))
+    })
+}
+
+/// Parses the [SV]s listed in a `+` line. Padding (null) fields are dropped.
+/// Old revisions (SP3-a/b) may omit the constellation letter,
+/// in which case we assume the header-declared `constellation`
+/// (GPS in case of mixed files).
+pub(crate) fn parse_satellites_line(
+    line: &str,
+    revision: Version,
+    constellation: Constellation,
+) -> Result<Vec<SV>, ParsingError> {
+    let mut satellites = Vec::with_capacity(ITEMS_PER_LINE);
+
+    for field in fields(line) {
+        let trimmed = field.trim();
+
+        if trimmed.is_empty() || trimmed.chars().all(|c| c == '0') {
+            continue;
+        }
+
+        let omitted = match revision {
+            Version::A | Version::B => trimmed.chars().all(|c| c.is_ascii_digit()),
+            _ => false,
+        };
+
+        let sv = if omitted {
+            let prn = trimmed.parse::<u8>().or(Err(ParsingError::SV))?;
+
+            let constellation = match constellation {
+                Constellation::Mixed => Constellation::GPS,
+                constellation => constellation,
+            };
+
+            SV::new(constellation, prn)
+        } else {
+            SV::from_str(&field.replace(' ', "0")).or(Err(ParsingError::SV))?
+        };
+
+        satellites.push(sv);
+    }
+
+    Ok(satellites)
+}
+
+/// Parses the accuracy exponents of a `++` line, including null (unknown) exponents,
+/// so they remain aligned with the satellites of the `+` lines.
+pub(crate) fn parse_accuracy_line(line: &str) -> Vec<u8> {
+    fields(line)
+        .map(|field| field.trim().parse::<u8>().unwrap_or(0))
+        .collect()
+}
+
+/// Formats the `+` and `++` lines, for these satellites and accuracy exponents.
+pub(crate) fn format_satellites_accuracy<W: Write>(
+    satellites: &[SV],
+    accuracy_exponents: &BTreeMap<SV, u8>,
+    w: &mut BufWriter<W>,
+) -> Result<(), FormattingError> {
+    let chunks = satellites.chunks(ITEMS_PER_LINE).collect::<Vec<_>>();
+    let num_lines = chunks.len().max(MIN_NUM_LINES);
+
+    for i in 0..num_lines {
+        if i == 0 {
+            write!(w, "+{:5}   ", satellites.len())?;
+        } else {
+            write!(w, "+        ")?;
+        }
+
+        let chunk = chunks.get(i).copied().unwrap_or_default();
+
+        for j in 0..ITEMS_PER_LINE {
+            match chunk.get(j) {
+                Some(sv) => write!(w, "{:x}{:02}", sv.constellation, sv.prn)?,
+                None => write!(w, "  0")?,
+            }
+        }
+
+        writeln!(w)?;
+    }
+
+    for i in 0..num_lines {
+        write!(w, "++       ")?;
+
+        let chunk = chunks.get(i).copied().unwrap_or_default();
+
+        for j in 0..ITEMS_PER_LINE {
+            let exponent = chunk
+                .get(j)
+                .and_then(|sv| accuracy_exponents.get(sv))
+                .copied()
+                .unwrap_or(0);
+
+            write!(w, "{:3}", exponent)?;
+        }
+
+        writeln!(w)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::{format_satellites_accuracy, parse_accuracy_line, parse_satellites_line};
+    use crate::{
+        prelude::{Constellation, Version, SV},
+        tests::formatting::Utf8Buffer,
+    };
+
+    use std::collections::BTreeMap;
+    use std::io::BufWriter;
+    use std::str::FromStr;
+
+    #[test]
+    fn satellites_accuracy_lines() {
+        let g01 = SV::from_str("G01").unwrap();
+        let e05 = SV::from_str("E05").unwrap();
+
+        let line = "+    2   G01E05  0  0  0  0  0  0  0  0  0  0  0  0  0  0  0";
+        let satellites = parse_satellites_line(line, Version::D, Constellation::Mixed).unwrap();
+        assert_eq!(satellites, vec![g01, e05]);
+
+        let line = "++         5  7  0  0  0  0  0  0  0  0  0  0  0  0  0  0  0";
+        let exponents = parse_accuracy_line(line);
+        assert_eq!(exponents.len(), 17);
+        assert_eq!(exponents[..3], [5, 7, 0]);
+
+        // constellation omitted
+        let line = "+    3     1  2 12  0  0  0  0  0  0  0  0  0  0  0  0  0  0";
+        let satellites = parse_satellites_line(line, Version::A, Constellation::GPS).unwrap();
+        assert_eq!(
+            satellites,
+            vec![
+                SV::new(Constellation::GPS, 1),
+                SV::new(Constellation::GPS, 2),
+                SV::new(Constellation::GPS, 12),
+            ]
+        );
+
+        let accuracy_exponents = BTreeMap::from([(g01, 5), (e05, 7)]);
+
+        let mut buf = BufWriter::new(Utf8Buffer::new(2048));
+        format_satellites_accuracy(&[g01, e05], &accuracy_exponents, &mut buf).unwrap();
+
+        let formatted = buf.into_inner().unwrap().to_ascii_utf8();
+        let lines = formatted.lines().collect::<Vec<_>>();
+
+        assert_eq!(lines.len(), 10);
+
+        assert_eq!(
+            lines[0],
+            "+    2   G01E05  0  0  0  0  0  0  0  0  0  0  0  0  0  0  0"
+        );
+
+        assert_eq!(
+            lines[1],
+            "+          0  0  0  0  0  0  0  0  0  0  0  0  0  0  0  0  0"
+        );
+
+        assert_eq!(
+            lines[5],
+            "++         5  7  0  0  0  0  0  0  0  0  0  0  0  0  0  0  0"
+        );
+    }
+}
@@ -0,0 +1,171 @@
+//! Helmert (7-parameter) frame transformation
+use crate::{prelude::SP3, Vector3D};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+// Normalization of the position vectors, in kilometers,
+// to keep the normal equations well conditioned
+const NORMALIZATION_KM: f64 = 1.0E4;
+
+/// [HelmertParams] describes a 7-parameter (Helmert) transformation,
+/// as estimated by [SP3::estimate_helmert].
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct HelmertParams {
+    /// (Tx, Ty, Tz) translation, in kilometers
+    pub translation_km: Vector3D,
+
+    /// (Rx, Ry, Rz) rotation angles around each axis, in radians
+    pub rotation_rad: Vector3D,
+
+    /// Scale factor, in parts per billion
+    pub scale_ppb: f64,
+}
+
+// Applies the small-angle Helmert transform to a single position vector:
+// X' = T + (1 + s) X + R X, with R the rotation (skew) matrix
+//   |  0  -rz  ry |
+//   |  rz  0  -rx |
+//   | -ry  rx  0  |
+pub(crate) fn helmert_transform(
+    position_km: Vector3D,
+    translation_km: Vector3D,
+    rotation_rad: Vector3D,
+    scale_ppb: f64,
+) -> Vector3D {
+    let (x_km, y_km, z_km) = position_km;
+    let (tx_km, ty_km, tz_km) = translation_km;
+    let (rx_rad, ry_rad, rz_rad) = rotation_rad;
+    let d = 1.0 + scale_ppb * 1.0E-9;
+
+    (
+        tx_km + d * x_km - rz_rad * y_km + ry_rad * z_km,
+        ty_km + rz_rad * x_km + d * y_km - rx_rad * z_km,
+        tz_km - ry_rad * x_km + rx_rad * y_km + d * z_km,
+    )
+}
+
+// Solves the linear system A x = b (Gauss elimination with partial pivoting).
+// Returns None if the system is singular.
+fn solve_linear_system<const N: usize>(mut a: [[f64; N]; N], mut b: [f64; N]) -> Option<[f64; N]> {
+    let max_diag = (0..N).map(|i| a[i][i].abs()).fold(0.0_f64, f64::max);
+
+    if max_diag == 0.0 {
+        return None;
+    }
+
+    for col in 0..N {
+        let pivot = (col..N).max_by(|i, j| a[*i][col].abs().total_cmp(&a[*j][col].abs()))?;
+
+        if a[pivot][col].abs() < max_diag * 1.0E-12 {
+            return None;
+        }
+
+        a.swap(col, pivot);
+        b.swap(col, pivot);
+
+        for row in col + 1..N {
+            let pivot_row = a[col];
+            let factor = a[row][col] / pivot_row[col];
+
+            for (k, a_k) in a[row].iter_mut().enumerate().skip(col) {
+                *a_k -= factor * pivot_row[k];
+            }
+
+            b[row] -= factor * b[col];
+        }
+    }
+
+    let mut x = [0.0_f64; N];
+
+    for row in (0..N).rev() {
+        let mut sum = b[row];
+
+        for k in row + 1..N {
+            sum -= a[row][k] * x[k];
+        }
+
+        x[row] = sum / a[row][row];
+    }
+
+    Some(x)
+}
+
+impl SP3 {
+    /// Applies a 7-parameter (Helmert) transformation to every satellite position.
+    /// This is typically used to align two products expressed in slightly different frames.
+    /// We use the small-angle approximation (IERS conventions):
+    /// X' = T + (1 + s) X + R X.
+    /// Velocities and clock states are not modified.
+    ///
+    /// ## Input
+    /// - translation_km: (Tx, Ty, Tz) translation, in kilometers
+    /// - rotation_rad: (Rx, Ry, Rz) rotation angles around each axis, in radians
+    /// - scale_ppb: scale factor, in parts per billion
+    pub fn apply_helmert_mut(
+        &mut self,
+        translation_km: Vector3D,
+        rotation_rad: Vector3D,
+        scale_ppb: f64,
+    ) {
+        for v in self.data.values_mut() {
+            v.position_km =
+                helmert_transform(v.position_km, translation_km, rotation_rad, scale_ppb);
+        }
+    }
+
+    /// Estimates the 7-parameter (Helmert) transformation from this [SP3]
+    /// to the `reference` [SP3], by least squares fit of all position pairs
+    /// they have in common (same satellite, same epoch).
+    /// This quantifies the systematic frame difference between two products:
+    /// applying the resulting [HelmertParams] to Self (see [SP3::apply_helmert_mut])
+    /// aligns it to the reference.
+    /// Returns None if there are not enough common positions to solve the system.
+    pub fn estimate_helmert(&self, reference: &SP3) -> Option<HelmertParams> {
+        let mut normal = [[0.0_f64; 7]; 7];
+        let mut rhs = [0.0_f64; 7];
+
+        for (k, v) in self.data.iter() {
+            let ref_position_km = match reference.data.get(k) {
+                Some(reference) => reference.position_km,
+                None => continue,
+            };
+
+            let (x_km, y_km, z_km) = v.position_km;
+            let (x, y, z) = (
+                x_km / NORMALIZATION_KM,
+                y_km / NORMALIZATION_KM,
+                z_km / NORMALIZATION_KM,
+            );
+
+            // unknowns: (Tx, Ty, Tz, Rx, Ry, Rz, s)
+            let rows = [
+                ([1.0, 0.0, 0.0, 0.0, z, -y, x], ref_position_km.0 - x_km),
+                ([0.0, 1.0, 0.0, -z, 0.0, x, y], ref_position_km.1 - y_km),
+                ([0.0, 0.0, 1.0, y, -x, 0.0, z], ref_position_km.2 - z_km),
+            ];
+
+            for (row, residual_km) in rows.iter() {
+                for i in 0..7 {
+                    for j in 0..7 {
+                        normal[i][j] += row[i] * row[j];
+                    }
+                    rhs[i] += row[i] * residual_km;
+                }
+            }
+        }
+
+        let solution = solve_linear_system(normal, rhs)?;
+
+        Some(HelmertParams {
+            translation_km: (solution[0], solution[1], solution[2]),
+            rotation_rad: (
+                solution[3] / NORMALIZATION_KM,
+                solution[4] / NORMALIZATION_KM,
+                solution[5] / NORMALIZATION_KM,
+            ),
+            scale_ppb: solution[6] / NORMALIZATION_KM * 1.0E9,
+        })
+    }
+}
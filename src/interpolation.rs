@@ -0,0 +1,91 @@
+//! interpolation context
+use std::collections::BTreeMap;
+
+use crate::{
+    lagrange_interpolation,
+    prelude::{Epoch, SP3, SV},
+    Vector3D,
+};
+
+/// [InterpolationContext] is fixed at one [Epoch] and one interpolation order,
+/// and caches the interpolation time frame of each satellite.
+/// This is the most efficient option when interpolating many satellites at the same [Epoch]
+/// (typical per-epoch processing): the record is scanned only once, when forming the context,
+/// while each [InterpolationContext::position] is then O(order).
+/// Obtain one with [SP3::interpolation_context].
+#[derive(Debug, Clone, PartialEq)]
+pub struct InterpolationContext {
+    /// Interpolation [Epoch]
+    t: Epoch,
+
+    /// Interpolation order
+    order: usize,
+
+    /// Centered time frame, per satellite.
+    /// Satellites for which interpolation is not feasible are not stored.
+    windows: BTreeMap<SV, Vec<(Epoch, Vector3D)>>,
+}
+
+impl InterpolationContext {
+    /// Interpolation [Epoch] of this [InterpolationContext]
+    pub fn epoch(&self) -> Epoch {
+        self.t
+    }
+
+    /// Interpolation order of this [InterpolationContext]
+    pub fn order(&self) -> usize {
+        self.order
+    }
+
+    /// Returns an [Iterator] over the [SV]s that can be interpolated
+    pub fn satellites_iter(&self) -> impl Iterator<Item = SV> + '_ {
+        self.windows.keys().copied()
+    }
+
+    /// Interpolates the position of this [SV] (in km ECEF), using the Lagrangian method.
+    /// Result is identical to [SP3::satellite_position_lagrangian_interpolation].
+    /// Returns None if interpolation is not feasible for this [SV].
+    pub fn position(&self, sv: SV) -> Option<Vector3D> {
+        let window = self.windows.get(&sv)?;
+        lagrange_interpolation(self.order, self.t, window.clone())
+    }
+}
+
+impl SP3 {
+    /// Forms an [InterpolationContext] at this [Epoch] `t`, for this interpolation order,
+    /// to efficiently interpolate many satellites at the same [Epoch].
+    /// The time frames follow [SP3::satellite_position_lagrangian_interpolation]:
+    /// `order + 1` stable (not maneuvered) data points, perfectly centered on `t`.
+    /// This will panic on even interpolation orders.
+    pub fn interpolation_context(&self, t: Epoch, order: usize) -> InterpolationContext {
+        let odd_order = order % 2 > 0;
+        if !odd_order {
+            panic!("even interpolation order is not supported");
+        }
+
+        let target_len_2 = order.div_ceil(2);
+
+        let mut states = BTreeMap::<SV, Vec<(Epoch, Vector3D)>>::new();
+
+        for (t_i, sv_i, _, position_km) in self.satellites_stable_position_km_iter() {
+            states.entry(sv_i).or_default().push((t_i, position_km));
+        }
+
+        let windows = states
+            .into_iter()
+            .filter_map(|(sv, states)| {
+                // central point: first state not earlier than t
+                let t_x = states.partition_point(|(t_i, _)| *t_i < t);
+
+                // window must be correctly centered on central point
+                if t_x < target_len_2 || t_x + target_len_2 > states.len() {
+                    None
+                } else {
+                    Some((sv, states[t_x - target_len_2..t_x + target_len_2].to_vec()))
+                }
+            })
+            .collect();
+
+        InterpolationContext { t, order, windows }
+    }
+}
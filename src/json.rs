@@ -0,0 +1,225 @@
+//! JSON / NDJSON serialization, for data-science pipelines
+use crate::prelude::{Epoch, Error, SP3Entry, SP3Key, SP3, SV};
+use crate::Vector3D;
+
+use serde::{Deserialize, Serialize, Serializer};
+
+use std::io::{BufWriter, Write};
+
+/// [SP3Record] is the flat representation of one record (one satellite at one [Epoch]),
+/// as emitted by [SP3::to_json_writer] and [SP3::to_ndjson_writer].
+/// The satellite and [Epoch] are serialized as readable strings (like "G01" and
+/// "2023-08-27T00:00:00 GPST"), so the output can be ingested directly by pandas or polars.
+/// It deserializes back, with [serde_json] for example.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SP3Record {
+    /// Satellite
+    #[serde(with = "as_string")]
+    pub sv: SV,
+
+    /// [Epoch] of this record
+    #[serde(with = "as_string")]
+    pub epoch: Epoch,
+
+    /// ECEF position in kilometers
+    pub position_km: Vector3D,
+
+    /// ECEF velocity in km.s⁻¹, if provided
+    pub velocity_km_s: Option<Vector3D>,
+
+    /// Clock offset in microseconds, if provided
+    pub clock_us: Option<f64>,
+
+    /// Clock drift in nanoseconds, if provided
+    pub clock_drift_ns: Option<f64>,
+
+    /// True if the state vector is predicted
+    pub predicted_orbit: bool,
+
+    /// True if the clock state is predicted
+    pub predicted_clock: bool,
+
+    /// True if the satellite was maneuvered since last state
+    pub maneuver: bool,
+
+    /// True on clock discontinuity
+    pub clock_event: bool,
+}
+
+impl From<(&SP3Key, &SP3Entry)> for SP3Record {
+    fn from((key, entry): (&SP3Key, &SP3Entry)) -> Self {
+        Self {
+            sv: key.sv,
+            epoch: key.epoch,
+            position_km: entry.position_km,
+            velocity_km_s: entry.velocity_km_s,
+            clock_us: entry.clock_us,
+            clock_drift_ns: entry.clock_drift_ns,
+            predicted_orbit: entry.predicted_orbit,
+            predicted_clock: entry.predicted_clock,
+            maneuver: entry.maneuver,
+            clock_event: entry.clock_event,
+        }
+    }
+}
+
+/// (de)serializes through [std::fmt::Display] and [std::str::FromStr]
+mod as_string {
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<T: std::fmt::Display, S: Serializer>(
+        value: &T,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(value)
+    }
+
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+    where
+        T: std::str::FromStr,
+        T::Err: std::fmt::Display,
+        D: Deserializer<'de>,
+    {
+        let content = String::deserialize(deserializer)?;
+        content.parse::<T>().map_err(Error::custom)
+    }
+}
+
+impl SP3 {
+    /// Returns an [Iterator] over all records of this [SP3], flattened as [SP3Record]s.
+    /// Records are sorted by satellite, then by [Epoch] (like [SP3::data]).
+    pub fn records_iter(&self) -> impl Iterator<Item = SP3Record> + '_ {
+        self.data.iter().map(SP3Record::from)
+    }
+
+    /// Serializes all records of this [SP3] as one JSON array of [SP3Record]s.
+    /// The [Header] is not serialized. See [SP3::to_ndjson_writer] for line delimited JSON,
+    /// which is better suited to very large files.
+    pub fn to_json_writer<W: Write>(&self, writer: W) -> Result<(), Error> {
+        let mut writer = BufWriter::new(writer);
+
+        let mut serializer = serde_json::Serializer::new(&mut writer);
+        serializer.collect_seq(self.records_iter())?;
+
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Serializes all records of this [SP3] as newline delimited JSON (NDJSON):
+    /// one [SP3Record] object per line.
+    /// The [Header] is not serialized.
+    pub fn to_ndjson_writer<W: Write>(&self, writer: W) -> Result<(), Error> {
+        let mut writer = BufWriter::new(writer);
+
+        for record in self.records_iter() {
+            serde_json::to_writer(&mut writer, &record)?;
+            writer.write_all(b"\n")?;
+        }
+
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::prelude::*;
+    use crate::tests::fixtures::t0;
+    use hifitime::Unit;
+    use std::str::FromStr;
+
+    fn subset() -> SP3 {
+        let g01 = SV::from_str("G01").unwrap();
+        let e05 = SV::from_str("E05").unwrap();
+
+        let t0 = t0();
+
+        let mut sp3 = SP3::default();
+
+        for i in 0..2 {
+            let epoch = t0 + i as f64 * 15.0 * Unit::Minute;
+
+            sp3.data.insert(
+                SP3Key { epoch, sv: g01 },
+                SP3Entry::from_position_km((13_000.123456, -20_000.0, 15_000.0 + i as f64))
+                    .with_velocity_km_s((1.2345, -0.5, 3.1))
+                    .with_clock_offset_us(-10.5),
+            );
+
+            sp3.data.insert(
+                SP3Key { epoch, sv: e05 },
+                SP3Entry::from_predicted_position_km((21_000.0, 1_000.0, -2_000.0)),
+            );
+        }
+
+        sp3
+    }
+
+    #[test]
+    fn json_roundtrip() {
+        let sp3 = subset();
+
+        let mut content = Vec::new();
+        sp3.to_json_writer(&mut content).unwrap();
+
+        let records = serde_json::from_slice::<Vec<SP3Record>>(&content).unwrap();
+        assert_eq!(records, sp3.records_iter().collect::<Vec<_>>());
+        assert_eq!(records.len(), 4);
+
+        // field values
+        let value = serde_json::from_slice::<serde_json::Value>(&content).unwrap();
+
+        let e05 = value
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|record| record["sv"] == "E05")
+            .unwrap();
+
+        assert_eq!(e05["epoch"], "2023-08-27T00:00:00 GPST");
+        assert_eq!(e05["position_km"][0], 21_000.0);
+        assert_eq!(e05["position_km"][2], -2_000.0);
+        assert!(e05["velocity_km_s"].is_null());
+        assert!(e05["clock_us"].is_null());
+        assert_eq!(e05["predicted_orbit"], true);
+        assert_eq!(e05["maneuver"], false);
+    }
+
+    #[test]
+    fn ndjson_roundtrip() {
+        let sp3 = subset();
+        let g01 = SV::from_str("G01").unwrap();
+
+        let mut content = Vec::new();
+        sp3.to_ndjson_writer(&mut content).unwrap();
+
+        let content = String::from_utf8(content).unwrap();
+        assert_eq!(content.lines().count(), 4);
+
+        for (line, (key, entry)) in content.lines().zip(sp3.data.iter()) {
+            let record = serde_json::from_str::<SP3Record>(line).unwrap();
+
+            assert_eq!(record.sv, key.sv);
+            assert_eq!(record.epoch, key.epoch);
+            assert_eq!(record.position_km, entry.position_km);
+            assert_eq!(record.velocity_km_s, entry.velocity_km_s);
+            assert_eq!(record.clock_us, entry.clock_us);
+            assert_eq!(record.predicted_orbit, entry.predicted_orbit);
+
+            if record.sv == g01 {
+                assert_eq!(record.position_km.0, 13_000.123456);
+                assert_eq!(record.velocity_km_s, Some((1.2345, -0.5, 3.1)));
+                assert_eq!(record.clock_us, Some(-10.5));
+            }
+        }
+
+        // invalid satellite
+        let invalid = content
+            .lines()
+            .next()
+            .unwrap()
+            .replace("G01", "invalid")
+            .replace("E05", "invalid");
+        assert!(serde_json::from_str::<SP3Record>(&invalid).is_err());
+    }
+}
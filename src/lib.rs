@@ -22,16 +22,23 @@ use itertools::Itertools;
 extern crate gnss_qc_traits as qc_traits;
 
 use gnss::prelude::{Constellation, SV};
-use hifitime::Epoch;
+use hifitime::{Duration, Epoch};
 use prelude::ProductionAttributes;
-use production::Campaign;
 
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 
 #[cfg(feature = "anise")]
 #[cfg_attr(docsrs, doc(cfg(feature = "anise")))]
 mod anise;
 
+#[cfg(feature = "bincode")]
+#[cfg_attr(docsrs, doc(cfg(feature = "bincode")))]
+mod bincode;
+
+#[cfg(feature = "serde")]
+#[cfg_attr(docsrs, doc(cfg(feature = "serde")))]
+mod json;
+
 #[cfg(feature = "qc")]
 #[cfg_attr(docsrs, doc(cfg(feature = "qc")))]
 mod qc;
@@ -47,14 +54,23 @@ mod nyx;
 #[cfg(test)]
 mod tests;
 
+mod anomaly;
+mod builder;
+mod clock;
+mod concatenation;
 mod dynamics;
 mod entry;
 mod errors;
+mod export;
 mod formatting;
 mod header;
+mod helmert;
+mod interpolation;
 mod parsing;
 mod position;
 mod production;
+mod quality;
+mod validation;
 mod velocity;
 
 #[cfg(feature = "serde")]
@@ -69,23 +85,55 @@ pub use nyx::{SpacecraftModel, SpacecraftTrajectory};
 use header::Header;
 use hifitime::Unit;
 
+use clock::centered_scalar_interpolation;
 use entry::SP3Entry;
 use errors::*;
+use interpolation::InterpolationContext;
 
 type Vector3D = (f64, f64, f64);
 
+/// Commonly used types. The ANISE and Nyx helper types are
+/// also re-exported, when the related features are activated:
+/// ```
+/// #[cfg(feature = "anise")]
+/// use sp3::prelude::{
+///     Almanac, Frame, Orbit, SatelliteOrbitalAttitude, SatelliteOrbitalState, EARTH_J2000,
+/// };
+///
+/// #[cfg(feature = "nyx-space")]
+/// use sp3::prelude::{SpacecraftModel, SpacecraftTrajectory};
+/// ```
 pub mod prelude {
     pub use crate::{
+        anomaly::AxisAnomaly,
+        builder::SP3Builder,
+        dynamics::DynamicsSummary,
         entry::SP3Entry,
         errors::{Error, FormattingError, ParsingError},
-        header::{version::Version, DataType, Header, OrbitType},
-        production::{Availability, ProductionAttributes, ReleaseDate, ReleasePeriod},
+        formatting::{RoundingMode, SP3Writer},
+        header::{
+            descriptor::FileDescriptor, diff::HeaderFieldDiff, frame::ReferenceFrameHint,
+            version::Version, DataType, Header, OrbitType,
+        },
+        helmert::HelmertParams,
+        interpolation::InterpolationContext,
+        parsing::{ParseOptions, SP3EpochStream},
+        production::{
+            Availability, ProductSummary, ProductionAttributes, ReleaseDate, ReleasePeriod,
+        },
+        validation::ValidationIssue,
         SP3Key, SP3,
     };
 
+    #[cfg(feature = "serde")]
+    pub use crate::json::SP3Record;
+
     #[cfg(feature = "qc")]
     pub use gnss_qc_traits::{Merge, Timeshift};
 
+    #[cfg(feature = "qc")]
+    pub use crate::qc::{MergeConflictError, MergeConflictPolicy, MergeWarning, MergeWarnings};
+
     #[cfg(feature = "processing")]
     pub use gnss_qc_traits::Split;
 
@@ -96,8 +144,14 @@ pub mod prelude {
     #[cfg(feature = "anise")]
     pub use anise::{
         constants::frames::{EARTH_J2000, IAU_EARTH_FRAME},
-        prelude::{Almanac, Frame},
+        prelude::{Almanac, Frame, Orbit},
     };
+
+    #[cfg(feature = "anise")]
+    pub use crate::anise::{SatelliteOrbitalAttitude, SatelliteOrbitalState};
+
+    #[cfg(feature = "nyx-space")]
+    pub use crate::nyx::{PredictionError, SpacecraftModel, SpacecraftTrajectory};
 }
 
 /// SP3 dataset is a list of [SP3Entry] indexed by [SP3Key].
@@ -128,7 +182,7 @@ pub struct SP3 {
     pub data: BTreeMap<SP3Key, SP3Entry>,
 }
 
-use crate::prelude::{Availability, DataType, ReleasePeriod};
+use crate::prelude::DataType;
 
 // Lagrangian interpolator
 pub(crate) fn lagrange_interpolation(
@@ -163,6 +217,40 @@ pub(crate) fn lagrange_interpolation(
     Some(polynomials)
 }
 
+// Hermite interpolator: uses both positions and velocities at each node.
+// H(t) = Σ [1 - 2 (t - t_i) l'_i(t_i)] l_i(t)² x_i + (t - t_i) l_i(t)² v_i,
+// with l_i the Lagrangian basis polynomials. This is the cubic Hermite
+// blending when only two nodes are used.
+pub(crate) fn hermite_interpolation(t: Epoch, x: &[(Epoch, Vector3D, Vector3D)]) -> Vector3D {
+    let mut polynomials = Vector3D::default();
+
+    for (i, (t_i, (x_km_i, y_km_i, z_km_i), (vx_km_s_i, vy_km_s_i, vz_km_s_i))) in
+        x.iter().enumerate()
+    {
+        let dt_s = (t - *t_i).to_seconds();
+
+        let (mut l_i, mut dl_i) = (1.0_f64, 0.0_f64);
+
+        for (j, (t_j, _, _)) in x.iter().enumerate() {
+            if j != i {
+                let dt_ij_s = (*t_i - *t_j).to_seconds();
+                l_i *= (t - *t_j).to_seconds() / dt_ij_s;
+                dl_i += 1.0 / dt_ij_s;
+            }
+        }
+
+        let l_i_2 = l_i * l_i;
+        let position_weight = (1.0 - 2.0 * dt_s * dl_i) * l_i_2;
+        let velocity_weight = dt_s * l_i_2;
+
+        polynomials.0 += x_km_i * position_weight + vx_km_s_i * velocity_weight;
+        polynomials.1 += y_km_i * position_weight + vy_km_s_i * velocity_weight;
+        polynomials.2 += z_km_i * position_weight + vz_km_s_i * velocity_weight;
+    }
+
+    polynomials
+}
+
 // // 2D Linear interpolation
 // pub(crate) fn linear_interpolation(
 //     order: usize,
@@ -194,6 +282,56 @@ impl SP3 {
         self.epochs_iter().last()
     }
 
+    /// Returns the first [Epoch] at which this [SV] is described.
+    pub fn satellite_first_epoch(&self, sv: SV) -> Option<Epoch> {
+        self.satellite_epochs_iter(sv).next()
+    }
+
+    /// Returns the last [Epoch] at which this [SV] is described.
+    pub fn satellite_last_epoch(&self, sv: SV) -> Option<Epoch> {
+        self.satellite_epochs_iter(sv).last()
+    }
+
+    /// Returns an [Epoch] [Iterator] over the epochs at which this [SV] is described.
+    /// Use this to verify the coverage of a satellite, prior interpolating.
+    pub fn satellite_epochs_iter(&self, sv: SV) -> impl Iterator<Item = Epoch> + '_ {
+        self.data
+            .keys()
+            .filter_map(move |k| if k.sv == sv { Some(k.epoch) } else { None })
+    }
+
+    /// Returns the [SP3Entry] of this [SV] at this [Epoch], if it exists.
+    /// The [Epoch] must exactly match the record (no interpolation is performed).
+    pub fn get(&self, sv: SV, epoch: Epoch) -> Option<&SP3Entry> {
+        self.data.get(&SP3Key { epoch, sv })
+    }
+
+    /// Returns the [SP3Entry] of this [SV] that is the closest to this [Epoch],
+    /// within the tolerance (inclusive). Unlike [SP3::get], this does not require an exact
+    /// [Epoch] match, which is convenient when the query results from a timescale conversion.
+    pub fn get_near(&self, sv: SV, epoch: Epoch, tol: Duration) -> Option<&SP3Entry> {
+        let start = SP3Key {
+            epoch: epoch - tol.abs(),
+            sv,
+        };
+
+        let end = SP3Key {
+            epoch: epoch + tol.abs(),
+            sv,
+        };
+
+        self.data
+            .range(start..=end)
+            .min_by_key(|(k, _)| (k.epoch - epoch).abs())
+            .map(|(_, entry)| entry)
+    }
+
+    /// Returns the position vector (in km, ECEF) of this [SV] at this [Epoch], if it exists.
+    /// The [Epoch] must exactly match the record (no interpolation is performed).
+    pub fn get_position_km(&self, sv: SV, epoch: Epoch) -> Option<Vector3D> {
+        self.get(sv, epoch).map(|entry| entry.position_km)
+    }
+
     /// Returns true if this [SP3] has satellites velocity vector
     pub fn has_satellite_velocity(&self) -> bool {
         self.header.data_type == DataType::Velocity
@@ -261,27 +399,350 @@ impl SP3 {
         true
     }
 
+    /// Returns the effective sampling period, computed as the most frequent
+    /// (modal) spacing between successive epochs of the record.
+    /// It may differ from the declared [Header] sampling period, for example
+    /// after a decimation: use [SP3::canonicalize_mut] to update the [Header].
+    /// Returns None if the record contains less than two epochs.
+    pub fn effective_sampling_period(&self) -> Option<Duration> {
+        let epochs = self.epochs_iter().collect::<BTreeSet<_>>();
+
+        let mut histogram = BTreeMap::<Duration, usize>::new();
+
+        for (prev, next) in epochs.iter().zip(epochs.iter().skip(1)) {
+            *histogram.entry(*next - *prev).or_insert(0) += 1;
+        }
+
+        // shortest spacing wins on ties
+        histogram
+            .into_iter()
+            .max_by(|(dt_a, count_a), (dt_b, count_b)| count_a.cmp(count_b).then(dt_b.cmp(dt_a)))
+            .map(|(dt, _)| dt)
+    }
+
+    /// Returns the histogram of inter-epoch intervals, that is, how often
+    /// each spacing between successive epochs of a same satellite occurs.
+    /// A clean (steady) file has a single bucket. This pinpoints irregular sampling.
+    pub fn interval_histogram(&self) -> BTreeMap<Duration, usize> {
+        let mut histogram = BTreeMap::<Duration, usize>::new();
+        let mut past = Option::<&SP3Key>::None;
+
+        // record is sorted by satellite, then by epoch
+        for key in self.data.keys() {
+            if let Some(past) = past {
+                if past.sv == key.sv {
+                    *histogram.entry(key.epoch - past.epoch).or_insert(0) += 1;
+                }
+            }
+            past = Some(key);
+        }
+
+        histogram
+    }
+
+    /// Reports each place where the interval between successive epochs of a same satellite
+    /// deviates from the [Header] sampling period, as a (satellite, epoch, interval) tuple,
+    /// where the epoch is the last epoch preceding the gap and the interval is the one actually found.
+    /// The report is empty for steady files (see [SP3::has_steady_sampling]),
+    /// and permits targeted interpolation or rejection of the affected satellites.
+    pub fn sampling_gaps(&self) -> Vec<(SV, Epoch, Duration)> {
+        let dt = self.header.sampling_period;
+
+        let mut gaps = Vec::new();
+        let mut past = Option::<&SP3Key>::None;
+
+        // record is sorted by satellite, then by epoch
+        for key in self.data.keys() {
+            if let Some(past) = past {
+                if past.sv == key.sv {
+                    let interval = key.epoch - past.epoch;
+
+                    if interval != dt {
+                        gaps.push((key.sv, past.epoch, interval));
+                    }
+                }
+            }
+            past = Some(key);
+        }
+
+        gaps
+    }
+
+    /// Re-derives the [Header] fields that depend on the record content, which
+    /// may be stale after direct modifications of [SP3::data], or after a merge:
+    /// - the list of satellites (sorted)
+    /// - the total number of epochs
+    /// - the sampling period (see [SP3::effective_sampling_period]). The declared value
+    ///   is overwritten whenever the record contains at least two epochs,
+    ///   and only preserved otherwise.
+    /// - the week counter and time of week, aligned to the earliest epoch.
+    ///
+    /// The record itself is always sorted by [SP3Key].
+    /// We recommend calling this prior formatting (see [SP3::to_file]).
+    /// Returns true if the epoch grid is steady (see [SP3::has_steady_sampling]).
+    pub fn canonicalize_mut(&mut self) -> bool {
+        let mut satellites = self.data.keys().map(|k| k.sv).unique().collect::<Vec<_>>();
+        satellites.sort();
+
+        self.header.num_satellites = satellites.len();
+        self.header.satellites = satellites;
+        self.header.num_epochs = self.total_epochs() as u64;
+
+        if let Some(sampling_period) = self.effective_sampling_period() {
+            self.header.sampling_period = sampling_period;
+        }
+
+        // the record is sorted by satellite first
+        if let Some(first) = self.data.keys().map(|k| k.epoch).min() {
+            self.header.align_week_to_first_epoch(first);
+        }
+
+        self.has_steady_sampling()
+    }
+
+    /// Retains only the [Epoch]s where all satellites declared in the [Header]
+    /// have data, dropping partial epochs. This is useful for analyses that
+    /// require complete geometry. The [Header] is rebuilt afterwards
+    /// (see [SP3::canonicalize_mut]).
+    pub fn retain_full_epochs_mut(&mut self) {
+        let declared = self
+            .header
+            .satellites
+            .iter()
+            .copied()
+            .collect::<BTreeSet<_>>();
+        let mut epochs = BTreeMap::<Epoch, BTreeSet<SV>>::new();
+
+        for k in self.data.keys() {
+            epochs.entry(k.epoch).or_default().insert(k.sv);
+        }
+
+        let full_epochs = epochs
+            .into_iter()
+            .filter_map(|(epoch, satellites)| {
+                if declared.is_subset(&satellites) {
+                    Some(epoch)
+                } else {
+                    None
+                }
+            })
+            .collect::<BTreeSet<_>>();
+
+        self.data.retain(|k, _| full_epochs.contains(&k.epoch));
+        self.canonicalize_mut();
+    }
+
+    /// Appends a new [Epoch] to this [SP3], described by these entries (one per satellite).
+    /// This is the bulk counterpart of direct [SP3::data] insertions, for incremental building:
+    /// the [Header] remains consistent (sorted satellites, number of epochs and, for the
+    /// very first epoch, the release [Epoch], week counter and MJD). When the [Header]
+    /// sampling period is defined (not null), the spacing with the last [Epoch] of the record
+    /// is validated.
+    /// Appending no entries does nothing.
+    ///
+    /// ## Errors
+    /// - [Error::NonIncreasingEpoch] if `epoch` does not follow the last [Epoch] of the record
+    /// - [Error::EpochSpacing] if the spacing with the last [Epoch] does not match the sampling period
+    pub fn append_epoch(&mut self, epoch: Epoch, entries: &[(SV, SP3Entry)]) -> Result<(), Error> {
+        if entries.is_empty() {
+            return Ok(());
+        }
+
+        // epochs_iter is sorted by satellite first
+        match self.epochs_iter().max() {
+            Some(last) => {
+                if epoch <= last {
+                    return Err(Error::NonIncreasingEpoch(epoch));
+                }
+
+                let dt = self.header.sampling_period;
+
+                if dt > Duration::ZERO && epoch - last != dt {
+                    return Err(Error::EpochSpacing(epoch - last, dt));
+                }
+            },
+            None => {
+                self.header.release_epoch = epoch;
+                self.header.align_week_to_first_epoch(epoch);
+                self.header.align_mjd_to_first_epoch(epoch);
+            },
+        }
+
+        for (sv, entry) in entries.iter() {
+            self.data.insert(SP3Key { epoch, sv: *sv }, *entry);
+
+            if let Err(index) = self.header.satellites.binary_search(sv) {
+                self.header.satellites.insert(index, *sv);
+            }
+        }
+
+        self.header.num_satellites = self.header.satellites.len();
+
+        self.header.num_epochs += 1;
+        Ok(())
+    }
+
+    /// Retains only the satellites that can support an interpolation of this order
+    /// somewhere, that is, satellites having at least one arc of `order +1`
+    /// contiguous epochs (spaced by the [Header] sampling period).
+    /// Use this prior interpolation-heavy processing. The [Header] is rebuilt afterwards
+    /// (see [SP3::canonicalize_mut]).
+    pub fn retain_interpolatable_mut(&mut self, order: usize) {
+        let dt = self.header.sampling_period;
+
+        let mut longest_arcs = BTreeMap::<SV, usize>::new();
+        let mut past = Option::<&SP3Key>::None;
+        let mut arc_len = 0;
+
+        // record is sorted by satellite, then by epoch
+        for key in self.data.keys() {
+            match past {
+                Some(past) if past.sv == key.sv && key.epoch - past.epoch == dt => {
+                    arc_len += 1;
+                },
+                _ => {
+                    arc_len = 1;
+                },
+            }
+
+            let longest = longest_arcs.entry(key.sv).or_insert(0);
+            *longest = (*longest).max(arc_len);
+
+            past = Some(key);
+        }
+
+        self.data
+            .retain(|k, _| longest_arcs.get(&k.sv).copied().unwrap_or(0) > order);
+
+        self.canonicalize_mut();
+    }
+
+    /// Applies the transformation `f` to every [SP3Entry] and returns a new [SP3].
+    /// This is the simplest way to apply a correction uniformly,
+    /// for example a frame rotation or a bias. The [Header] is
+    /// canonicalized after the transformation (see [SP3::canonicalize_mut]).
+    /// ```
+    /// use sp3::prelude::*;
+    ///
+    /// let sp3 = SP3::from_file("data/SP3/C/co108870.sp3")
+    ///     .unwrap();
+    ///
+    /// // +1 km shift on the X axis
+    /// let shifted = sp3.map_entries(|_, entry| {
+    ///     let (x_km, y_km, z_km) = entry.position_km;
+    ///     entry.with_position_km((x_km + 1.0, y_km, z_km))
+    /// });
+    /// ```
+    pub fn map_entries(&self, f: impl Fn(&SP3Key, &SP3Entry) -> SP3Entry) -> Self {
+        let mut s = Self {
+            header: self.header.clone(),
+            comments: self.comments.clone(),
+            prod_attributes: self.prod_attributes.clone(),
+            data: self
+                .data
+                .iter()
+                .map(|(k, v)| (k.clone(), f(k, v)))
+                .collect(),
+        };
+
+        s.canonicalize_mut();
+        s
+    }
+
+    /// Splits this [SP3] into single-satellite [SP3]s, one per satellite,
+    /// which is convenient to generate one file per satellite.
+    /// Each [Header] is narrowed to the satellite constellation
+    /// and canonicalized (see [SP3::canonicalize_mut]).
+    pub fn split_by_satellite(&self) -> BTreeMap<SV, Self> {
+        let mut split = BTreeMap::new();
+
+        for sv in self.satellites_iter() {
+            let mut s = Self {
+                header: self.header.clone(),
+                comments: self.comments.clone(),
+                prod_attributes: self.prod_attributes.clone(),
+                data: self
+                    .data
+                    .iter()
+                    .filter_map(|(k, v)| {
+                        if k.sv == sv {
+                            Some((k.clone(), *v))
+                        } else {
+                            None
+                        }
+                    })
+                    .collect(),
+            };
+
+            s.header.constellation = sv.constellation;
+            s.canonicalize_mut();
+
+            split.insert(sv, s);
+        }
+
+        split
+    }
+
+    /// Splits this [SP3] into single-system [SP3]s, one per [Constellation],
+    /// which is convenient to feed system-specific pipelines.
+    /// Each [Header] constellation is set to this system (never [Constellation::Mixed])
+    /// and canonicalized (see [SP3::canonicalize_mut]).
+    pub fn split_by_constellation(&self) -> BTreeMap<Constellation, Self> {
+        let mut split = BTreeMap::new();
+
+        for constellation in self.constellations_iter() {
+            let mut s = Self {
+                header: self.header.clone(),
+                comments: self.comments.clone(),
+                prod_attributes: self.prod_attributes.clone(),
+                data: self
+                    .data
+                    .iter()
+                    .filter_map(|(k, v)| {
+                        if k.sv.constellation == constellation {
+                            Some((k.clone(), *v))
+                        } else {
+                            None
+                        }
+                    })
+                    .collect(),
+            };
+
+            s.header.constellation = constellation;
+            s.canonicalize_mut();
+
+            split.insert(constellation, s);
+        }
+
+        split
+    }
+
     /// Propose a file name that would follow the IGS file naming conventions.
     /// This is particularly useful in the context of sP3 data synthesis
     /// and production. It may also be used to generate a file name
-    /// that would follow the conventions, while parsed from a file that did not.
+    /// that would follow the conventions, while parsed from a file that did not:
+    /// in that case, the [ProductionAttributes] are inferred from the [Header]
+    /// (see [SP3::infer_production_attributes]).
     pub fn standardized_filename(&self) -> String {
-        let mut batch_id = 0;
-        let mut campaign = Campaign::default();
-        let mut avail = Availability::default();
-        let mut release_period = ReleasePeriod::default();
-        let mut agency = self.header.agency[..3].to_string();
-
-        let mut extension = "";
-
-        if let Some(attributes) = &self.prod_attributes {
-            batch_id = attributes.batch_id;
-            avail = attributes.availability;
-            campaign = attributes.campaign;
-            release_period = attributes.release_period;
-            agency = attributes.agency.clone();
-            extension = ".gz";
-        }
+        let extension = if self.prod_attributes.is_some() {
+            ".gz"
+        } else {
+            ""
+        };
+
+        let attributes = match &self.prod_attributes {
+            Some(attributes) => attributes.clone(),
+            None => self.infer_production_attributes(),
+        };
+
+        let ProductionAttributes {
+            agency,
+            batch_id,
+            campaign,
+            availability: avail,
+            release_period,
+            ..
+        } = attributes;
 
         let (year, doy) = (
             self.header.release_epoch.year(),
@@ -294,7 +755,7 @@ impl SP3 {
 
         format!(
             "{}{}{}{}_{}{:03}{}_{}_{:02}M_ORB.SP3{}",
-            agency,
+            production::agency_code(&agency),
             batch_id,
             campaign,
             avail,
@@ -312,6 +773,31 @@ impl SP3 {
         self.epochs_iter().count()
     }
 
+    /// Returns the number of records we expect at each [Epoch],
+    /// which is the number of satellites declared in the [Header].
+    pub fn expected_records_per_epoch(&self) -> usize {
+        self.header.num_satellites
+    }
+
+    /// Returns the number of records (one per satellite) at this [Epoch].
+    /// Compare to [SP3::expected_records_per_epoch] to identify
+    /// epochs that are missing some satellites.
+    pub fn records_at(&self, epoch: Epoch) -> usize {
+        self.data.keys().filter(|k| k.epoch == epoch).count()
+    }
+
+    /// Returns the number of satellites at each [Epoch], in chronological order.
+    /// This reveals coverage dips, without querying each [Epoch] (see [SP3::records_at]).
+    pub fn satellite_count_iter(&self) -> impl Iterator<Item = (Epoch, usize)> + '_ {
+        let mut counts = BTreeMap::<Epoch, usize>::new();
+
+        for k in self.data.keys() {
+            *counts.entry(k.epoch).or_insert(0) += 1;
+        }
+
+        counts.into_iter()
+    }
+
     /// Returns [Epoch] [Iterator]
     pub fn epochs_iter(&self) -> impl Iterator<Item = Epoch> + '_ {
         self.data.keys().map(|k| k.epoch).unique()
@@ -491,6 +977,85 @@ impl SP3 {
         })
     }
 
+    /// [SV] clock offset (in seconds) and drift (in s.s⁻¹) [Iterator], in a single pass.
+    /// Entries that have neither are not exposed.
+    /// See [Self::satellites_clock_offset_sec_iter] and [Self::satellites_clock_drift_sec_sec_iter].
+    ///
+    /// ## Output
+    /// - [Epoch] : sampling epoch
+    /// - [SV] : satellite identity
+    /// - Option<f64> : clock offset (in seconds), if known
+    /// - Option<f64> : clock drift (in s.s⁻¹), if known
+    pub fn satellites_clock_iter(
+        &self,
+    ) -> impl Iterator<Item = (Epoch, SV, Option<f64>, Option<f64>)> + '_ {
+        self.data.iter().filter_map(|(k, v)| {
+            if v.clock_us.is_none() && v.clock_drift_ns.is_none() {
+                return None;
+            }
+
+            let offset_s = v.clock_us.map(|clock_us| clock_us * 1.0E-6);
+            let drift_s_s = v.clock_drift_ns.map(|drift_ns| drift_ns * 1.0E-9);
+
+            Some((k.epoch, k.sv, offset_s, drift_s_s))
+        })
+    }
+
+    /// [SV] clock offset [Iterator], in microseconds, that only contains
+    /// predicted clock states. Clock prediction is independent of orbit prediction:
+    /// some products predict clock states while the orbit is fitted (or vice versa),
+    /// see [Self::satellites_stable_predicted_position_km_iter] for predicted orbits.
+    ///
+    /// ## Output
+    /// - [Epoch] : sampling epoch
+    /// - [SV] : satellite identity
+    /// - f64 : predicted clock offset (in microseconds)
+    pub fn satellites_predicted_clock_iter(
+        &self,
+    ) -> Box<dyn Iterator<Item = (Epoch, SV, f64)> + '_> {
+        Box::new(self.data.iter().filter_map(|(k, v)| {
+            if v.predicted_clock {
+                let clock_us = v.clock_us?;
+                Some((k.epoch, k.sv, clock_us))
+            } else {
+                None
+            }
+        }))
+    }
+
+    /// Summarizes the prediction regime of each [Epoch], in chronological order:
+    /// whether the majority of satellites are predicted in orbit, and in clock.
+    /// This is typically used on ultra rapid (ULT) products,
+    /// to locate the transition from fitted to predicted states.
+    ///
+    /// ## Output
+    /// - [Epoch] : sampling epoch
+    /// - bool : true when the majority of orbital states are predicted
+    /// - bool : true when the majority of clock states are predicted
+    pub fn prediction_regimes(&self) -> Vec<(Epoch, bool, bool)> {
+        // (total, predicted orbits, predicted clocks), per epoch
+        let mut counts = BTreeMap::<Epoch, (usize, usize, usize)>::new();
+
+        for (k, v) in self.data.iter() {
+            let (total, orbits, clocks) = counts.entry(k.epoch).or_default();
+
+            *total += 1;
+
+            if v.predicted_orbit {
+                *orbits += 1;
+            }
+
+            if v.predicted_clock {
+                *clocks += 1;
+            }
+        }
+
+        counts
+            .into_iter()
+            .map(|(epoch, (total, orbits, clocks))| (epoch, 2 * orbits > total, 2 * clocks > total))
+            .collect()
+    }
+
     /// Designs an evenly spaced (in time) grouping of (x_km, y_km, z_km) coordinates
     /// for you to apply your own interpolation method (as a function pointer).
     /// NB:
@@ -564,6 +1129,12 @@ impl SP3 {
                     if (t_i - t).abs() < smallest_dt {
                         tx_perfect_match = true;
                     }
+
+                    // 1st order: window is already gathered
+                    if target_len_2_1 == 0 {
+                        w1_len = target_len_2;
+                        break;
+                    }
                 }
             } else {
                 // stop when window has been gathered
@@ -614,6 +1185,39 @@ impl SP3 {
         self.satellite_position_interpolate(sv, t, order, lagrange_interpolation)
     }
 
+    /// Applies the Lagrangian interpolation method at desired [Epoch] `t`, using desired
+    /// interpolation order, and estimates the interpolation error (per axis, in km).
+    /// The error is estimated by comparing this result with the result of order `order -2`,
+    /// on the same centered time frame. This is a heuristic, not a rigorous bound: it is only
+    /// meaningful on smooth arcs, where increasing the order improves the interpolation.
+    /// Returns (position_km, error_km).
+    /// NB:
+    /// - this will panic on even interpolation orders
+    /// - this will not interpolate (returns None) if `order` is lower than 3, or
+    /// [Epoch] is either too early or too late with respect to interpolation order.
+    pub fn satellite_position_lagrangian_interpolation_with_error(
+        &self,
+        sv: SV,
+        t: Epoch,
+        order: usize,
+    ) -> Option<(Vector3D, Vector3D)> {
+        let position_km = self.satellite_position_lagrangian_interpolation(sv, t, order)?;
+
+        if order < 3 {
+            return None;
+        }
+
+        let lower_km = self.satellite_position_lagrangian_interpolation(sv, t, order - 2)?;
+
+        let error_km = (
+            (position_km.0 - lower_km.0).abs(),
+            (position_km.1 - lower_km.1).abs(),
+            (position_km.2 - lower_km.2).abs(),
+        );
+
+        Some((position_km, error_km))
+    }
+
     /// Applies 9th order Lagrangian interpolation method, which is compatible with high precision geodesy.
     /// See [Self::satellite_position_lagrangian_interpolation].
     pub fn satellite_position_lagrangian_9_interpolation(
@@ -643,4 +1247,242 @@ impl SP3 {
     ) -> Option<Vector3D> {
         self.satellite_position_lagrangian_interpolation(sv, t, 17)
     }
+
+    /// Interpolates the position of this [SV] at desired [Epoch] `t`,
+    /// using the Hermite method, which consumes both the position and velocity
+    /// vectors at each node. This is much more accurate than the Lagrangian method
+    /// (at equal order) and is only feasible on [DataType::Velocity] files
+    /// (see [Self::resolve_velocities_mut] otherwise).
+    /// The time frame follows [Self::satellite_position_lagrangian_interpolation]:
+    /// - `order + 1` data points, perfectly centered on `t`
+    /// - only odd orders are supported, this will panic on even interpolation orders.
+    ///
+    /// Returns None if `t` cannot be centered within the time frame, or the
+    /// velocity vector is missing at any point of the time frame.
+    pub fn satellite_position_hermite_interpolation(
+        &self,
+        sv: SV,
+        t: Epoch,
+        order: usize,
+    ) -> Option<Vector3D> {
+        let odd_order = order % 2 > 0;
+        if !odd_order {
+            panic!("even interpolation order is not supported");
+        }
+
+        let target_len = order + 1;
+        let target_len_2 = target_len / 2;
+
+        // stable states, possibly missing velocity
+        let states = self
+            .data
+            .iter()
+            .filter_map(|(k, v)| {
+                if k.sv == sv && !v.maneuver {
+                    Some((k.epoch, v.position_km, v.velocity_km_s))
+                } else {
+                    None
+                }
+            })
+            .collect::<Vec<_>>();
+
+        // central point: first state not earlier than t
+        let t_x = states.partition_point(|(t_i, _, _)| *t_i < t);
+
+        // window must be correctly centered on central point
+        if t_x < target_len_2 || t_x + target_len_2 > states.len() {
+            return None;
+        }
+
+        let window = states[t_x - target_len_2..t_x + target_len_2]
+            .iter()
+            .map(|(t_i, position_km, velocity_km_s)| Some((*t_i, *position_km, (*velocity_km_s)?)))
+            .collect::<Option<Vec<_>>>()?;
+
+        Some(hermite_interpolation(t, &window))
+    }
+
+    /// Interpolates the position of every satellite at desired [Epoch] `t`,
+    /// using the Lagrangian method and desired interpolation order.
+    /// This is the per-epoch snapshot that precise positioning requires.
+    /// Satellites for which `t` cannot be centered within the interpolation
+    /// window are simply skipped (see [Self::satellite_position_lagrangian_interpolation]).
+    /// The record is scanned only once (see [Self::interpolation_context]).
+    /// This will panic on even interpolation orders.
+    pub fn interpolate_all_positions(&self, t: Epoch, order: usize) -> BTreeMap<SV, Vector3D> {
+        let context = self.interpolation_context(t, order);
+
+        context
+            .satellites_iter()
+            .filter_map(|sv| {
+                let position_km = context.position(sv)?;
+                Some((sv, position_km))
+            })
+            .collect()
+    }
+
+    /// Returns the time window over which each satellite can actually be interpolated,
+    /// at this interpolation order. That is, the time frame of each satellite,
+    /// excluding its first and last `(order +1)/2` epochs, where the interpolation window
+    /// cannot be centered. Satellites for which no such window exists are not reported.
+    /// See [Self::satellite_position_lagrangian_interpolation].
+    pub fn interpolation_coverage(&self, order: usize) -> BTreeMap<SV, (Epoch, Epoch)> {
+        self.satellites_iter()
+            .filter_map(|sv| {
+                let range = self.interpolation_feasible_range(sv, order)?;
+                Some((sv, range))
+            })
+            .collect()
+    }
+
+    /// Returns the earliest and latest [Epoch]s at which this [SV] can be interpolated,
+    /// at this interpolation order: that is where a perfectly centered window exists.
+    /// This allows clipping a query range, because [Self::satellite_position_interpolate]
+    /// simply returns None when too close to either edge.
+    /// Returns None if this [SV] is not described by enough epochs for this order.
+    pub fn interpolation_feasible_range(&self, sv: SV, order: usize) -> Option<(Epoch, Epoch)> {
+        let half_len = order.div_ceil(2);
+        let epochs = self.satellite_epochs_iter(sv).collect::<Vec<_>>();
+
+        if epochs.len() <= 2 * half_len {
+            return None;
+        }
+
+        Some((epochs[half_len], epochs[epochs.len() - 1 - half_len]))
+    }
+
+    /// Resamples this [SP3] to the desired sampling period, using the Lagrangian
+    /// interpolation method and desired interpolation order (see [Self::satellite_position_lagrangian_interpolation]).
+    /// The new epoch grid starts at the earliest [Epoch] of this record. Each satellite is
+    /// only described within its interpolation window (see [Self::interpolation_coverage]):
+    /// existing states are copied, others are interpolated (positions and clock offsets).
+    /// The [Header] is updated consistently: sampling period, week counter and MJD.
+    /// Unlike [Self::resample], interpolated states are not flagged as predicted.
+    ///
+    /// ## Errors
+    /// - [Error::InterpolationOrder] on even interpolation orders
+    /// - [Error::SamplingPeriod] on null or negative sampling period
+    pub fn with_sampling_period(&self, dt: Duration, order: usize) -> Result<Self, Error> {
+        self.resampled(dt, order, false)
+    }
+
+    /// Resamples this [SP3] onto a uniform grid of period `dt`, using the Lagrangian
+    /// interpolation method and desired interpolation order (see [Self::satellite_position_lagrangian_interpolation]).
+    /// The new epoch grid starts at the earliest [Epoch] of this record, and the [Header] is
+    /// updated consistently. Interpolated states are flagged as predicted (orbit and clock),
+    /// as they were not produced by the agency. Existing states are copied as is.
+    /// This is typically used to align several products onto a common grid.
+    /// Points outside the interpolation window of each satellite are omitted.
+    ///
+    /// ## Errors
+    /// - [Error::InterpolationOrder] on even interpolation orders
+    /// - [Error::SamplingPeriod] on null or negative sampling period
+    pub fn resample(&self, dt: Duration, order: usize) -> Result<Self, Error> {
+        self.resampled(dt, order, true)
+    }
+
+    /// [Self::with_sampling_period] and [Self::resample] implementation,
+    /// `flag_interpolated` flags the interpolated states as predicted.
+    fn resampled(
+        &self,
+        dt: Duration,
+        order: usize,
+        flag_interpolated: bool,
+    ) -> Result<Self, Error> {
+        if order % 2 == 0 {
+            return Err(Error::InterpolationOrder(order));
+        }
+
+        if dt <= Duration::ZERO {
+            return Err(Error::SamplingPeriod(dt));
+        }
+
+        let mut s = self.clone();
+        s.data.clear();
+
+        // the record is sorted by satellite first
+        let t0 = match self.data.keys().map(|k| k.epoch).min() {
+            Some(t0) => t0,
+            None => return Ok(s),
+        };
+
+        let coverage = self.interpolation_coverage(order);
+
+        let (start, end) = match (
+            coverage.values().map(|(start, _)| *start).min(),
+            coverage.values().map(|(_, end)| *end).max(),
+        ) {
+            (Some(start), Some(end)) => (start, end),
+            _ => {
+                s.canonicalize_mut();
+                return Ok(s);
+            },
+        };
+
+        // clock offset time series, per satellite
+        let mut clocks = BTreeMap::<SV, Vec<(Epoch, f64)>>::new();
+
+        for (k, v) in self.data.iter() {
+            if let Some(clock_us) = v.clock_us {
+                clocks.entry(k.sv).or_default().push((k.epoch, clock_us));
+            }
+        }
+
+        // align on the common grid
+        let mut t = t0 + ((start - t0).to_seconds() / dt.to_seconds()).ceil() * dt;
+
+        while t <= end {
+            // formed once per grid epoch, only when interpolation is needed
+            let mut context = Option::<InterpolationContext>::None;
+
+            for (sv, (sv_start, sv_end)) in coverage.iter() {
+                if t < *sv_start || t > *sv_end {
+                    continue;
+                }
+
+                let entry = match self.get(*sv, t) {
+                    Some(entry) => Some(*entry),
+                    None => context
+                        .get_or_insert_with(|| self.interpolation_context(t, order))
+                        .position(*sv)
+                        .map(|position_km| {
+                            let entry = if flag_interpolated {
+                                SP3Entry::from_predicted_position_km(position_km)
+                            } else {
+                                SP3Entry::from_position_km(position_km)
+                            };
+
+                            let clock_us = clocks
+                                .get(sv)
+                                .and_then(|series| centered_scalar_interpolation(order, t, series));
+
+                            match clock_us {
+                                Some(clock_us) if flag_interpolated => {
+                                    entry.with_predicted_clock_offset_us(clock_us)
+                                },
+                                Some(clock_us) => entry.with_clock_offset_us(clock_us),
+                                None => entry,
+                            }
+                        }),
+                };
+
+                if let Some(entry) = entry {
+                    s.data.insert(SP3Key { epoch: t, sv: *sv }, entry);
+                }
+            }
+
+            t += dt;
+        }
+
+        // declared, unless the new grid tells otherwise
+        s.header.sampling_period = dt;
+        s.canonicalize_mut();
+
+        if let Some(first) = s.data.keys().map(|k| k.epoch).min() {
+            s.header.release_epoch = first;
+            s.header.align_mjd_to_first_epoch(first);
+        }
+
+        Ok(s)
+    }
 }
@@ -25,7 +25,7 @@ use anise::{
     prelude::{Almanac, Frame},
 };
 
-use std::sync::Arc;
+use std::{collections::BTreeMap, sync::Arc};
 
 use hifitime::{TimeSeries, Unit};
 
@@ -393,7 +393,7 @@ impl SP3 {
                             pos_vel_km[1],
                             pos_vel_km[2],
                         ))
-                        .with_velocity_km_s((
+                        .with_predicted_velocity_km_s((
                             pos_vel_km[3],
                             pos_vel_km[4],
                             pos_vel_km[5],
@@ -417,6 +417,49 @@ impl SP3 {
 
         Ok(())
     }
+
+    /// Characterizes how the predicted states of this [SP3] degrade over time,
+    /// by comparison to the `truth` [SP3] (for example, the final product
+    /// covering the predicted time frame).
+    /// Predicted states are typically obtained with [SP3::spatial_prediction],
+    /// or published by ultra-rapid (ULT) products.
+    ///
+    /// ## Output
+    /// - Mean 3D position error (in kilometers), binned by time elapsed since
+    ///   the initial [Epoch] of this [SP3], sorted by increasing horizon.
+    ///   States that are not described by `truth` are ignored.
+    pub fn prediction_error_profile(&self, truth: &SP3) -> Vec<(Duration, f64)> {
+        let mut bins = BTreeMap::<Duration, (f64, usize)>::new();
+
+        // the record is sorted by satellite first
+        let t0 = match self.data.keys().map(|k| k.epoch).min() {
+            Some(t0) => t0,
+            None => return Vec::new(),
+        };
+
+        for (k, v) in self.data.iter() {
+            let truth = match truth.data.get(k) {
+                Some(truth) => truth,
+                None => continue,
+            };
+
+            let (x_km, y_km, z_km) = v.position_km;
+            let (truth_x_km, truth_y_km, truth_z_km) = truth.position_km;
+
+            let err_km = ((x_km - truth_x_km).powi(2)
+                + (y_km - truth_y_km).powi(2)
+                + (z_km - truth_z_km).powi(2))
+            .sqrt();
+
+            let bin = bins.entry(k.epoch - t0).or_insert((0.0, 0));
+            bin.0 += err_km;
+            bin.1 += 1;
+        }
+
+        bins.into_iter()
+            .map(|(horizon, (sum_km, count))| (horizon, sum_km / count as f64))
+            .collect()
+    }
 }
 
 #[cfg(test)]
@@ -432,6 +475,36 @@ mod test {
     use std::str::FromStr;
     use std::sync::Arc;
 
+    #[test]
+    fn position_only_spacecraft_models() {
+        let almanac = Almanac::until_2035().unwrap();
+        let earth_cef = almanac.frame_from_uid(EARTH_J2000).unwrap();
+
+        let mut parsed =
+            SP3::from_gzip_file("data/SP3/C/GRG0MGXFIN_20201770000_01D_15M_ORB.SP3.gz").unwrap();
+
+        let t1 = Epoch::from_str("2020-06-25T00:15:00 GPST").unwrap();
+
+        for state in parsed.satellites_orbit_iter(earth_cef) {
+            assert!(state.is_position_only());
+        }
+
+        assert_eq!(
+            parsed.spacecraft_model_iter(t1, earth_cef).count(),
+            0,
+            "position only file should not yield any spacecraft model"
+        );
+
+        parsed.resolve_velocities_mut();
+
+        let num_models = parsed.spacecraft_model_iter(t1, earth_cef).count();
+
+        assert!(
+            num_models > 0 && num_models <= parsed.satellites_iter().count(),
+            "resolved velocities should yield one model per satellite"
+        );
+    }
+
     #[test]
     fn forward_spatial_propagation_12h() {
         init_logger();
@@ -471,25 +544,36 @@ mod test {
             "forward prediction did not extend correctly",
         );
 
-        // obtain residuals
-        let residuals = predicted.substract(&parsed);
+        // error growth
+        let profile = predicted.prediction_error_profile(&parsed);
 
-        // Run testbench
-        for (k, v) in residuals.data.iter() {
-            let (x_err_m, y_err_m, z_err_m) = (
-                v.position_km.0.abs() * 1.0E3,
-                v.position_km.1.abs() * 1.0E3,
-                v.position_km.2.abs() * 1.0E3,
-            );
+        assert!(!profile.is_empty(), "empty prediction error profile");
 
-            info!(
-                "{}({}) - x_err={:.3}m y_err={:.3}m z_err={:.3}m",
-                k.epoch, k.sv, x_err_m, y_err_m, z_err_m
+        assert_eq!(
+            profile[0].0,
+            Duration::ZERO,
+            "profile should start on initial epoch"
+        );
+
+        for (horizon, err_km) in profile.iter() {
+            info!("horizon={} - mean_err={:.3}m", horizon, err_km * 1.0E3);
+
+            assert!(err_km.is_finite(), "{} - invalid error", horizon);
+            assert!(*horizon >= Duration::ZERO, "{} - invalid horizon", horizon);
+            assert!(
+                *horizon <= last_epoch - midnight,
+                "{} - horizon exceeds prediction",
+                horizon
             );
 
-            // assert!(x_err_m < 5.0, "{}({}) - x_err={} too large", k.epoch, k.sv, x_err_m);
-            // assert!(y_err_m < 5.0, "{}({}) - y_err={} too large", k.epoch, k.sv, y_err_m);
-            // assert!(z_err_m < 5.0, "{}({}) - z_err={} too large", k.epoch, k.sv, z_err_m);
+            // morning states were not predicted
+            if *horizon < noon - midnight {
+                assert!(*err_km < 1.0E-6, "{} - fitted state altered", horizon);
+            }
+        }
+
+        for pair in profile.windows(2) {
+            assert!(pair[0].0 < pair[1].0, "profile should be sorted");
         }
     }
 
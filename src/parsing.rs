@@ -1,31 +1,83 @@
 use std::{
     collections::BTreeMap,
     fs::File,
-    io::{BufRead, BufReader, Read},
+    io::{BufRead, BufReader, Lines, Read},
     path::Path,
     str::FromStr,
 };
 
+use itertools::Itertools;
+
 #[cfg(feature = "flate2")]
 use flate2::read::GzDecoder;
 
+#[cfg(feature = "log")]
+use log::warn;
+
 use crate::{
     header::{
+        descriptor::descriptor_fields,
         line1::{is_header_line1, Line1},
         line2::{is_header_line2, Line2},
+        satellites::{
+            is_header_accuracy_line, is_header_satellites_line, parse_accuracy_line,
+            parse_satellites_line,
+        },
     },
     position::{position_entry, PositionEntry},
     prelude::{
         Constellation, Epoch, Error, Header, ParsingError, ProductionAttributes, SP3Entry, SP3Key,
         TimeScale, Version, SP3, SV,
     },
-    velocity::{velocity_entry, VelocityEntry},
+    velocity::{
+        field_to_clock_drift_ns, sp3_velocity_field_to_km_s, velocity_entry, VelocityEntry,
+    },
+    Vector3D,
 };
 
 fn file_descriptor(content: &str) -> bool {
     content.starts_with("%c")
 }
 
+fn float_parameters(content: &str) -> bool {
+    content.starts_with("%f")
+}
+
+fn integer_parameters(content: &str) -> bool {
+    content.starts_with("%i")
+}
+
+/// Parses the values of a `%f` or `%i` line
+fn parse_parameters<T: FromStr>(content: &str) -> Result<Vec<T>, ParsingError> {
+    content[2..]
+        .split_ascii_whitespace()
+        .map(|item| {
+            T::from_str(item).or(Err(ParsingError::AdditionalParameters(content.to_string())))
+        })
+        .collect()
+}
+
+/// Parses the values of a `%f` or `%i` line, according to [ParseOptions]:
+/// a malformed line is only fatal when parsing strictly, otherwise it is ignored.
+fn checked_parameters<T: FromStr>(
+    content: &str,
+    opts: &ParseOptions,
+) -> Result<Option<Vec<T>>, ParsingError> {
+    match parse_parameters::<T>(content) {
+        Ok(values) => Ok(Some(values)),
+        Err(e) => {
+            if opts.strict {
+                return Err(e);
+            }
+
+            #[cfg(feature = "log")]
+            warn!("{}", e);
+
+            Ok(None)
+        },
+    }
+}
+
 fn sp3_comment(content: &str) -> bool {
     content.starts_with("/*")
 }
@@ -38,15 +90,56 @@ fn new_epoch(content: &str) -> bool {
     content.starts_with("*  ")
 }
 
-/// Parses [Epoch] from standard SP3 format
-fn parse_epoch(content: &str, timescale: TimeScale) -> Result<Epoch, ParsingError> {
-    let y = u32::from_str(content[0..4].trim()).or(Err(ParsingError::EpochParsing))?;
-    let m = u32::from_str(content[4..7].trim()).or(Err(ParsingError::EpochParsing))?;
-    let d = u32::from_str(content[7..10].trim()).or(Err(ParsingError::EpochParsing))?;
-    let hh = u32::from_str(content[10..13].trim()).or(Err(ParsingError::EpochParsing))?;
-    let mm = u32::from_str(content[13..16].trim()).or(Err(ParsingError::EpochParsing))?;
-    let ss = u32::from_str(content[16..19].trim()).or(Err(ParsingError::EpochParsing))?;
-    let _ss_fract = f64::from_str(content[20..27].trim()).or(Err(ParsingError::EpochParsing))?;
+/// [ParseOptions] let you select which (optional) fields should be retained
+/// when parsing. Skipping fields you do not need keeps the memory footprint low,
+/// which is interesting when parsing high-rate files. Everything is parsed by default.
+/// EP/EV (correlation) records are not stored by this library, so they are always skipped.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ParseOptions {
+    /// Retain the position and clock standard deviations.
+    pub read_stddev: bool,
+
+    /// Retain the clock offsets, drifts and related flags.
+    pub read_clocks: bool,
+
+    /// Strict parsing: returns [ParsingError::DuplicateEntry] when a satellite
+    /// is described twice within one epoch, with differing coordinates or clock offset,
+    /// and [ParsingError::AdditionalParameters] on malformed `%f` or `%i` lines.
+    /// When lenient (default), the last description prevails, malformed `%f` and `%i`
+    /// lines are ignored, and a warning is logged (requires the `log` feature).
+    pub strict: bool,
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        Self {
+            read_stddev: true,
+            read_clocks: true,
+            strict: false,
+        }
+    }
+}
+
+/// Returns the (trimmed) field of this line, that may be shortened or truncated.
+fn field(line: &str, start: usize, end: usize) -> &str {
+    line.get(start..end.min(line.len()))
+        .unwrap_or_default()
+        .trim()
+}
+
+/// Parses [Epoch] from standard SP3 epoch line.
+/// Some (old) files omit the seconds, or shorten the seconds field: we default to 0 in that case.
+fn parse_epoch(line: &str, timescale: TimeScale) -> Result<Epoch, ParsingError> {
+    let y = u32::from_str(field(line, 3, 7)).or(Err(ParsingError::EpochParsing))?;
+    let m = u32::from_str(field(line, 7, 10)).or(Err(ParsingError::EpochParsing))?;
+    let d = u32::from_str(field(line, 10, 13)).or(Err(ParsingError::EpochParsing))?;
+    let hh = u32::from_str(field(line, 13, 16)).or(Err(ParsingError::EpochParsing))?;
+    let mm = u32::from_str(field(line, 16, 19)).or(Err(ParsingError::EpochParsing))?;
+
+    let ss = match field(line, 19, 22) {
+        "" => 0,
+        ss => u32::from_str(ss).or(Err(ParsingError::EpochParsing))?,
+    };
 
     Epoch::from_str(&format!(
         "{:04}-{:02}-{:02}T{:02}:{:02}:{:02} {}",
@@ -90,19 +183,616 @@ impl SP3 {
         Ok(sp3)
     }
 
+    #[cfg(feature = "mmap")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "mmap")))]
+    /// Parse [SP3] data from local file, using a memory mapping.
+    /// This is well suited for very large files (multi-GB concatenated archives),
+    /// because the file content is not buffered: lines are parsed over the mapped slice.
+    ///
+    /// ## Safety
+    /// The caller must guarantee that the file is not modified (or truncated)
+    /// by this or another process while being parsed, otherwise the behavior is undefined.
+    pub unsafe fn from_mmap(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let fd = File::open(&path)?;
+
+        // SAFETY: the mapping only lives during the parsing process,
+        // and the caller guarantees the file is not modified meanwhile.
+        let mmap = unsafe { memmap2::Mmap::map(&fd)? };
+
+        let mut reader = BufReader::new(&mmap[..]);
+        let mut sp3 = Self::from_reader(&mut reader)?;
+
+        if let Some(filename) = path.as_ref().file_name() {
+            if let Ok(attributes) = ProductionAttributes::from_str(&filename.to_string_lossy()) {
+                sp3.prod_attributes = Some(attributes);
+            }
+        }
+
+        Ok(sp3)
+    }
+
+    #[cfg(feature = "async")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+    /// Parse [SP3] data from [tokio::io::AsyncRead]able I/O, without blocking the executor
+    /// while the content is being received (for example, streamed from object storage).
+    /// Lines are parsed as they are received, so the content is never buffered as a whole.
+    pub async fn from_async_reader<R: tokio::io::AsyncRead + Unpin>(
+        reader: R,
+    ) -> Result<Self, Error> {
+        use tokio::io::AsyncBufReadExt;
+
+        let mut parser = RecordParser::new(ParseOptions::default());
+        let mut lines = tokio::io::BufReader::new(reader).lines();
+
+        while let Some(line) = lines.next_line().await? {
+            if !parser.parse_line(&line)? {
+                break;
+            }
+        }
+
+        parser.finalize()
+    }
+
+    /// Parse [SP3] data from [Read]able I/O, expecting a specific [Version].
+    /// This is useful for strict pipelines that only support a given revision:
+    /// returns [ParsingError::UnexpectedRevision] if the parsed revision differs.
+    pub fn from_reader_expecting<R: Read>(
+        reader: &mut BufReader<R>,
+        version: Version,
+    ) -> Result<Self, Error> {
+        let sp3 = Self::from_reader(reader)?;
+
+        if sp3.header.version != version {
+            return Err(Error::ParsingError(ParsingError::UnexpectedRevision(
+                version,
+                sp3.header.version,
+            )));
+        }
+
+        Ok(sp3)
+    }
+
     /// Parse [SP3] data from [Read]able I/O.
     pub fn from_reader<R: Read>(reader: &mut BufReader<R>) -> Result<Self, Error> {
-        let mut pc_count = 0_u8;
-        let mut header = Header::default();
-        let mut timescale = TimeScale::default();
-
-        let mut vehicles: Vec<SV> = Vec::new();
-        let mut comments = Vec::new();
-        let mut data = BTreeMap::<SP3Key, SP3Entry>::new();
+        Self::from_reader_with_opts(reader, ParseOptions::default())
+    }
 
-        let mut epoch = Epoch::default();
+    /// Parse [SP3] data from [Read]able I/O, only retaining
+    /// the optional fields selected by [ParseOptions].
+    pub fn from_reader_with_opts<R: Read>(
+        reader: &mut BufReader<R>,
+        opts: ParseOptions,
+    ) -> Result<Self, Error> {
+        let mut parser = RecordParser::new(opts);
 
         for line in reader.lines() {
+            if !parser.parse_line(&line?)? {
+                break;
+            }
+        }
+
+        parser.finalize()
+    }
+
+    /// Parses the [Header] of [Read]able SP3 I/O, and returns a [SP3EpochStream]
+    /// that parses the record one epoch at a time. Only the current epoch is held in memory,
+    /// which is well suited for very large (multi-day concatenated) files.
+    /// ```
+    /// use sp3::prelude::*;
+    /// use std::fs::File;
+    ///
+    /// let fd = File::open("data/SP3/D/example.txt")
+    ///     .unwrap();
+    ///
+    /// let stream = SP3::epochs_stream(fd)
+    ///     .unwrap();
+    ///
+    /// assert_eq!(stream.header.version, Version::D);
+    ///
+    /// for block in stream {
+    ///     let (_, entries) = block.unwrap();
+    ///     assert!(!entries.is_empty());
+    /// }
+    /// ```
+    pub fn epochs_stream<R: Read>(reader: R) -> Result<SP3EpochStream<R>, Error> {
+        Self::epochs_stream_with_opts(reader, ParseOptions::default())
+    }
+
+    /// Returns a [SP3EpochStream] like [SP3::epochs_stream], that only retains
+    /// the optional fields selected by [ParseOptions].
+    pub fn epochs_stream_with_opts<R: Read>(
+        reader: R,
+        opts: ParseOptions,
+    ) -> Result<SP3EpochStream<R>, Error> {
+        SP3EpochStream::new(reader, opts)
+    }
+}
+
+/// Header parsing state, shared by [SP3::from_reader_with_opts] and [SP3EpochStream].
+#[derive(Default)]
+struct HeaderParser {
+    header: Header,
+    timescale: TimeScale,
+    pc_count: u8,
+    pf_count: usize,
+    pi_count: usize,
+    satellites_lines: Vec<String>,
+    accuracy_exponents: Vec<u8>,
+    release_epoch_missing: bool,
+}
+
+impl HeaderParser {
+    /// Parses any header line, other lines are ignored.
+    fn parse_line(&mut self, line: &str, opts: &ParseOptions) -> Result<(), Error> {
+        let header = &mut self.header;
+
+        if is_header_line1(line) && !is_header_line2(line) {
+            let l1 = Line1::from_str(line)?;
+
+            header.version = l1.version;
+            header.data_type = l1.data_type;
+            header.coord_system = l1.coord_system;
+            header.orbit_type = l1.orbit_type;
+            header.agency = l1.agency.to_string();
+            header.num_epochs = l1.num_epochs;
+            header.observables = l1.observables.to_string();
+
+            match l1.epoch {
+                Some(epoch) => header.release_epoch = epoch,
+                None => self.release_epoch_missing = true,
+            }
+        }
+
+        if is_header_line2(line) {
+            let l2 = Line2::from_str(line)?;
+            header.week = l2.week;
+            header.week_nanos = l2.week_nanos;
+
+            header.sampling_period = l2.sampling_period;
+
+            header.mjd = l2.mjd_fract.0;
+            header.mjd_fraction = l2.mjd_fract.1;
+        }
+
+        if is_header_satellites_line(line) {
+            // parsed once the constellation is known
+            self.satellites_lines.push(line.to_string());
+        }
+
+        if is_header_accuracy_line(line) {
+            self.accuracy_exponents.extend(parse_accuracy_line(line));
+        }
+
+        if file_descriptor(line) {
+            if line.len() < 60 {
+                return Err(Error::ParsingError(ParsingError::MalformedDescriptor(
+                    line.to_string(),
+                )));
+            }
+
+            let malformed = || ParsingError::MalformedDescriptor(line.to_string());
+
+            // Rev-A is expressed in GPST and usually GPS-Only,
+            // but some files declare another system
+            if header.version == Version::A {
+                if self.pc_count == 0 {
+                    header.constellation = line
+                        .get(3..5)
+                        .and_then(|c| Constellation::from_str(c.trim()).ok())
+                        .unwrap_or(Constellation::GPS);
+                }
+                header.timescale = TimeScale::GPST;
+            } else {
+                // Constellation identification needs to pass
+                if self.pc_count == 0 {
+                    let constellation = line.get(3..5).ok_or_else(malformed)?;
+
+                    header.constellation =
+                        Constellation::from_str(constellation.trim()).or(Err(malformed()))?;
+
+                    let ts = line.get(9..12).ok_or_else(malformed)?;
+
+                    self.timescale = TimeScale::from_str(ts.trim()).or(Err(malformed()))?;
+                    header.timescale = self.timescale;
+                }
+            }
+
+            let fields = descriptor_fields(line).ok_or_else(malformed)?;
+
+            header
+                .file_descriptor
+                .set_reserved_fields(self.pc_count, fields);
+
+            self.pc_count += 1;
+        }
+
+        if float_parameters(line) {
+            let values = checked_parameters::<f64>(line, opts)?.unwrap_or_default();
+
+            for (i, value) in values.into_iter().take(4).enumerate() {
+                match self.pf_count * 4 + i {
+                    0 => header.pos_vel_base.0 = value,
+                    1 => header.pos_vel_base.1 = value,
+                    n => {
+                        if let Some(param) = header.float_params.get_mut(n - 2) {
+                            *param = value;
+                        }
+                    },
+                }
+            }
+
+            self.pf_count += 1;
+        }
+
+        if integer_parameters(line) {
+            let values = checked_parameters::<i32>(line, opts)?.unwrap_or_default();
+
+            for (i, value) in values.into_iter().take(9).enumerate() {
+                if let Some(param) = header.int_params.get_mut(self.pi_count * 9 + i) {
+                    *param = value;
+                }
+            }
+
+            self.pi_count += 1;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the [Header], once all header lines have been parsed.
+    /// The satellites are the ones declared in the `+` lines, sorted.
+    fn finalize(self) -> Result<Header, Error> {
+        let mut header = self.header;
+
+        let mut declared_vehicles = Vec::<SV>::new();
+
+        for line in self.satellites_lines.iter() {
+            declared_vehicles.extend(parse_satellites_line(
+                line,
+                header.version,
+                header.constellation,
+            )?);
+        }
+
+        header.accuracy_exponents = declared_vehicles
+            .iter()
+            .copied()
+            .zip(self.accuracy_exponents)
+            .filter(|(_, exponent)| *exponent > 0)
+            .collect();
+
+        header.num_satellites = declared_vehicles.len();
+
+        header.satellites = declared_vehicles;
+        header.satellites.sort();
+        header.satellites.dedup();
+
+        if self.release_epoch_missing {
+            header.release_epoch = header
+                .release_epoch_from_time_fields()
+                .ok_or(Error::ParsingError(ParsingError::Epoch))?;
+        }
+
+        Ok(header)
+    }
+}
+
+/// Record parsing state of [SP3::from_reader_with_opts] and [SP3::from_async_reader],
+/// fed one line at a time.
+struct RecordParser {
+    opts: ParseOptions,
+    header_parser: HeaderParser,
+    comments: Vec<String>,
+    data: BTreeMap<SP3Key, SP3Entry>,
+    epoch: Epoch,
+}
+
+impl RecordParser {
+    fn new(opts: ParseOptions) -> Self {
+        Self {
+            opts,
+            header_parser: HeaderParser::default(),
+            comments: Vec::new(),
+            data: BTreeMap::new(),
+            epoch: Epoch::default(),
+        }
+    }
+
+    /// Parses any line, returns false once "EOF" is reached.
+    fn parse_line(&mut self, line: &str) -> Result<bool, Error> {
+        let line = line.trim();
+        let opts = &self.opts;
+
+        if sp3_comment(line) {
+            if line.len() > 4 {
+                self.comments.push(line[3..].to_string());
+            }
+            return Ok(true);
+        }
+
+        if end_of_file(line) {
+            return Ok(false);
+        }
+
+        self.header_parser.parse_line(line, opts)?;
+
+        if new_epoch(line) {
+            self.epoch = parse_epoch(line, self.header_parser.timescale)?;
+        }
+
+        let epoch = self.epoch;
+
+        if position_entry(line) {
+            if let Some(entry) = parse_position(line, &self.header_parser.header, opts)? {
+                let key = SP3Key {
+                    epoch,
+                    sv: entry.sv,
+                };
+
+                check_duplicate(&entry, epoch, self.data.get(&key), opts)?;
+
+                if let Some(value) = position_record(&entry, self.data.get_mut(&key)) {
+                    self.data.insert(key, value);
+                }
+            }
+        }
+
+        if velocity_entry(line) {
+            if let Some((sv, velocity_dm_s, clk_sub_ns)) =
+                parse_velocity(line, &self.header_parser.header, opts)?
+            {
+                let key = SP3Key { epoch, sv };
+
+                if let Some(value) =
+                    velocity_record(velocity_dm_s, clk_sub_ns, self.data.get_mut(&key))
+                {
+                    self.data.insert(key, value);
+                }
+            }
+        }
+
+        Ok(true)
+    }
+
+    /// Returns the [SP3], once all lines have been parsed.
+    fn finalize(self) -> Result<SP3, Error> {
+        let mut header = self.header_parser.finalize()?;
+
+        // complete the declared satellites, with the ones actually described
+        let declared = header.num_satellites;
+
+        for sv in self.data.keys().map(|k| k.sv).unique() {
+            if let Err(index) = header.satellites.binary_search(&sv) {
+                header.satellites.insert(index, sv);
+            }
+        }
+
+        // missing or malformed `+` lines: we expect the satellites of the record
+        if declared == 0 {
+            header.num_satellites = header.satellites.len();
+        }
+
+        Ok(SP3 {
+            header,
+            data: self.data,
+            comments: self.comments,
+            prod_attributes: None,
+        })
+    }
+}
+
+/// Parses a position line, only retaining the fields selected by [ParseOptions].
+/// Returns None for malformed position vectors, which we tolerate.
+fn parse_position(
+    line: &str,
+    header: &Header,
+    opts: &ParseOptions,
+) -> Result<Option<PositionEntry>, ParsingError> {
+    if line.len() < 60 {
+        return Ok(None);
+    }
+
+    let mut entry = PositionEntry::parse(line, header.version, header.constellation)?;
+
+    if !opts.read_clocks {
+        entry.clock_us = None;
+        entry.clock_std = None;
+        entry.clock_event = false;
+        entry.clock_prediction = false;
+    }
+
+    if !opts.read_stddev {
+        entry.position_std = None;
+        entry.clock_std = None;
+    }
+
+    Ok(Some(entry))
+}
+
+/// Parses a velocity line, only retaining the fields selected by [ParseOptions].
+/// Returns None for malformed velocity vectors, which we tolerate.
+fn parse_velocity(
+    line: &str,
+    header: &Header,
+    opts: &ParseOptions,
+) -> Result<Option<(SV, Vector3D, Option<f64>)>, ParsingError> {
+    if line.len() < 60 {
+        return Ok(None);
+    }
+
+    let entry = VelocityEntry::parse(line, header.version, header.constellation)?;
+    let (sv, velocity_dm_s, clk_sub_ns) = entry.to_parts();
+
+    Ok(Some((
+        sv,
+        velocity_dm_s,
+        clk_sub_ns.filter(|_| opts.read_clocks),
+    )))
+}
+
+/// Verifies that this [PositionEntry] does not describe a satellite that
+/// was already described (with differing coordinates) within this [Epoch].
+/// This is an error in strict mode only.
+fn check_duplicate(
+    entry: &PositionEntry,
+    epoch: Epoch,
+    existing: Option<&SP3Entry>,
+    opts: &ParseOptions,
+) -> Result<(), ParsingError> {
+    let existing = match existing {
+        Some(existing) => existing,
+        None => return Ok(()),
+    };
+
+    // null position: only the velocity was described so far
+    if existing.position_km == (0.0, 0.0, 0.0) {
+        return Ok(());
+    }
+
+    // strictly identical descriptions
+    if existing.position_km == (entry.x_km, entry.y_km, entry.z_km)
+        && existing.clock_us == entry.clock_us
+    {
+        return Ok(());
+    }
+
+    if opts.strict {
+        return Err(ParsingError::DuplicateEntry {
+            sv: entry.sv,
+            epoch,
+        });
+    }
+
+    #[cfg(feature = "log")]
+    warn!(
+        "{}({}): duplicate entry, overwriting previous content",
+        epoch, entry.sv
+    );
+
+    Ok(())
+}
+
+/// Applies this [PositionEntry] to the record: either updates the `existing` entry,
+/// or returns the new entry to be stored.
+fn position_record(entry: &PositionEntry, existing: Option<&mut SP3Entry>) -> Option<SP3Entry> {
+    // null vectors are bad records, but a single null coordinate is legitimate
+    if entry.x_km == 0.0_f64 && entry.y_km == 0.0_f64 && entry.z_km == 0.0_f64 {
+        return None;
+    }
+
+    // overwrites the position and clock states, the velocity states remain
+    if let Some(e) = existing {
+        e.position_km = (entry.x_km, entry.y_km, entry.z_km);
+        e.maneuver = entry.maneuver;
+        e.predicted_orbit = entry.orbit_prediction;
+        e.clock_us = entry.clock_us;
+        e.clock_event = entry.clock_event;
+        e.predicted_clock = entry.clock_us.is_some() && entry.clock_prediction;
+        e.position_std_mm = entry.position_std;
+        e.clock_std_ps = entry.clock_std;
+        return None;
+    }
+
+    let value = if entry.orbit_prediction {
+        SP3Entry::from_predicted_position_km((entry.x_km, entry.y_km, entry.z_km))
+    } else {
+        SP3Entry::from_position_km((entry.x_km, entry.y_km, entry.z_km))
+    };
+
+    let mut value = match entry.clock_us {
+        Some(clk_us) => {
+            if entry.clock_prediction {
+                value.with_predicted_clock_offset_us(clk_us)
+            } else {
+                value.with_clock_offset_us(clk_us)
+            }
+        },
+        None => value,
+    };
+
+    value.maneuver = entry.maneuver;
+    value.clock_event = entry.clock_event;
+    value.position_std_mm = entry.position_std;
+    value.clock_std_ps = entry.clock_std;
+
+    Some(value)
+}
+
+/// Applies this velocity vector (in dm/s) and clock drift to the record:
+/// either updates the `existing` entry, or returns the new entry to be stored.
+fn velocity_record(
+    velocity_dm_s: Vector3D,
+    clk_sub_ns: Option<f64>,
+    existing: Option<&mut SP3Entry>,
+) -> Option<SP3Entry> {
+    let (vel_x_dm_s, vel_y_dm_s, vel_z_dm_s) = velocity_dm_s;
+
+    // null vectors are bad records, but a single null component is legitimate.
+    // The clock drift of a bad record is dropped as well, like the clock offset
+    // of a null position vector.
+    if vel_x_dm_s == 0.0_f64 && vel_y_dm_s == 0.0_f64 && vel_z_dm_s == 0.0_f64 {
+        return None;
+    }
+
+    let velocity_km_s = (
+        sp3_velocity_field_to_km_s(vel_x_dm_s),
+        sp3_velocity_field_to_km_s(vel_y_dm_s),
+        sp3_velocity_field_to_km_s(vel_z_dm_s),
+    );
+
+    if let Some(e) = existing {
+        *e = e.with_velocity_km_s(velocity_km_s);
+
+        if let Some(clk_sub_ns) = clk_sub_ns {
+            *e = e.with_clock_drift_ns(field_to_clock_drift_ns(clk_sub_ns));
+        }
+
+        return None;
+    }
+
+    // Entry does not exist (velocity prior position)
+    // Should not exist, but we tolerate
+    let value = SP3Entry::from_position_km((0.0, 0.0, 0.0)).with_velocity_km_s(velocity_km_s);
+
+    match clk_sub_ns {
+        Some(clk_sub_ns) => Some(value.with_clock_drift_ns(field_to_clock_drift_ns(clk_sub_ns))),
+        None => Some(value),
+    }
+}
+
+/// [SP3EpochStream] parses the SP3 record one epoch at a time, see [SP3::epochs_stream].
+/// Each item is an [Epoch] and all its [SP3Entry]s, sorted by [SV].
+/// The iteration stops on the first error.
+pub struct SP3EpochStream<R: Read> {
+    /// [Header], parsed prior the record.
+    pub header: Header,
+
+    /// File comments, found prior the record.
+    pub comments: Vec<String>,
+
+    /// Line iterator
+    lines: Lines<BufReader<R>>,
+
+    /// [TimeScale] of the record
+    timescale: TimeScale,
+
+    /// [ParseOptions] applied to each epoch
+    opts: ParseOptions,
+
+    /// Next [Epoch] to be parsed
+    next_epoch: Option<Result<Epoch, ParsingError>>,
+}
+
+impl<R: Read> SP3EpochStream<R> {
+    /// Parses the [Header] until the first [Epoch] of the record is found.
+    fn new(reader: R, opts: ParseOptions) -> Result<Self, Error> {
+        let mut lines = BufReader::new(reader).lines();
+
+        let mut header_parser = HeaderParser::default();
+        let mut comments = Vec::new();
+        let mut next_epoch = None;
+
+        for line in lines.by_ref() {
             let line = line?;
             let line = line.trim();
 
@@ -117,173 +807,107 @@ impl SP3 {
                 break;
             }
 
-            if is_header_line1(line) && !is_header_line2(line) {
-                let l1 = Line1::from_str(line)?;
-
-                header.version = l1.version;
-                header.data_type = l1.data_type;
-                header.coord_system = l1.coord_system;
-                header.orbit_type = l1.orbit_type;
-                header.agency = l1.agency.to_string();
-                header.num_epochs = l1.num_epochs;
-                header.observables = l1.observables.to_string();
-                header.release_epoch = l1.epoch;
+            if new_epoch(line) {
+                next_epoch = Some(parse_epoch(line, header_parser.timescale));
+                break;
             }
 
-            if is_header_line2(line) {
-                let l2 = Line2::from_str(line)?;
-                header.week = l2.week;
-                header.week_nanos = l2.week_nanos;
-
-                header.sampling_period = l2.sampling_period;
+            header_parser.parse_line(line, &opts)?;
+        }
 
-                header.mjd = l2.mjd_fract.0;
-                header.mjd_fraction = l2.mjd_fract.1;
-            }
+        Ok(Self {
+            timescale: header_parser.timescale,
+            header: header_parser.finalize()?,
+            comments,
+            lines,
+            opts,
+            next_epoch,
+        })
+    }
 
-            if file_descriptor(line) {
-                if line.len() < 60 {
-                    return Err(Error::ParsingError(ParsingError::MalformedDescriptor(
-                        line.to_string(),
-                    )));
-                }
+    /// Parses the current epoch block.
+    fn parse_block(&mut self, epoch: Epoch) -> Result<Vec<(SV, SP3Entry)>, Error> {
+        let opts = self.opts;
+        let mut block = BTreeMap::<SV, SP3Entry>::new();
 
-                // no need to parse this line, since Rev-A is limited
-                // to GPS-Only
-                if header.version == Version::A {
-                    header.constellation = Constellation::GPS;
-                    header.timescale = TimeScale::GPST;
-                } else {
-                    // Constellation identification needs to pass
-                    if pc_count == 0 {
-                        header.constellation = Constellation::from_str(line[3..5].trim())?;
-                        timescale = TimeScale::from_str(line[9..12].trim())?;
-                        header.timescale = timescale;
-                    }
-                }
+        for line in self.lines.by_ref() {
+            let line = line?;
+            let line = line.trim();
 
-                pc_count += 1;
+            if end_of_file(line) {
+                break;
             }
 
             if new_epoch(line) {
-                epoch = parse_epoch(&line[3..], timescale)?;
+                // errors are reported on next iteration
+                self.next_epoch = Some(parse_epoch(line, self.timescale));
+                break;
             }
 
             if position_entry(line) {
-                if line.len() < 60 {
-                    // tolerates malformed position vectors
-                    continue;
-                }
-
-                let entry = PositionEntry::parse(line, header.version)?;
-
-                //TODO : move this into %c config frame
-                if !vehicles.contains(&entry.sv) {
-                    vehicles.push(entry.sv);
-                }
+                if let Some(entry) = parse_position(line, &self.header, &opts)? {
+                    check_duplicate(&entry, epoch, block.get(&entry.sv), &opts)?;
 
-                // verify entry validity
-                if entry.x_km != 0.0_f64 && entry.y_km != 0.0_f64 && entry.z_km != 0.0_f64 {
-                    let key = SP3Key {
-                        epoch,
-                        sv: entry.sv,
-                    };
-
-                    if let Some(e) = data.get_mut(&key) {
-                        e.position_km = (entry.x_km, entry.y_km, entry.z_km);
-                        e.maneuver = entry.maneuver;
-                        e.predicted_orbit = entry.orbit_prediction;
-                    } else if let Some(clk_us) = entry.clock_us {
-                        let value = if entry.orbit_prediction {
-                            SP3Entry::from_predicted_position_km((
-                                entry.x_km, entry.y_km, entry.z_km,
-                            ))
-                        } else {
-                            SP3Entry::from_position_km((entry.x_km, entry.y_km, entry.z_km))
-                        };
-
-                        let mut value = if entry.clock_prediction {
-                            value.with_predicted_clock_offset_us(clk_us)
-                        } else {
-                            value.with_clock_offset_us(clk_us)
-                        };
-
-                        value.maneuver = entry.maneuver;
-                        value.clock_event = entry.clock_event;
-
-                        data.insert(key, value);
-                    } else {
-                        let mut value = if entry.orbit_prediction {
-                            SP3Entry::from_predicted_position_km((
-                                entry.x_km, entry.y_km, entry.z_km,
-                            ))
-                        } else {
-                            SP3Entry::from_position_km((entry.x_km, entry.y_km, entry.z_km))
-                        };
-
-                        value.maneuver = entry.maneuver;
-                        value.clock_event = entry.clock_event;
-
-                        data.insert(key, value);
+                    if let Some(value) = position_record(&entry, block.get_mut(&entry.sv)) {
+                        block.insert(entry.sv, value);
                     }
                 }
             }
 
             if velocity_entry(line) {
-                if line.len() < 60 {
-                    // tolerates malformed velocity vectors
-                    continue;
+                if let Some((sv, velocity_dm_s, clk_sub_ns)) =
+                    parse_velocity(line, &self.header, &opts)?
+                {
+                    if let Some(value) =
+                        velocity_record(velocity_dm_s, clk_sub_ns, block.get_mut(&sv))
+                    {
+                        block.insert(sv, value);
+                    }
                 }
+            }
+        }
 
-                let entry = VelocityEntry::parse(line, header.version)?;
-                let (sv, (vel_x_dm_s, vel_y_dm_s, vel_z_dm_s), clk_sub_ns) = entry.to_parts();
+        Ok(block.into_iter().collect())
+    }
+}
 
-                let (vel_x_km_s, vel_y_km_s, vel_z_km_s) = (
-                    vel_y_dm_s * 1.0E-4,
-                    vel_y_dm_s * 1.0E-4,
-                    vel_z_dm_s * 1.0E-4,
-                );
+impl<R: Read> Iterator for SP3EpochStream<R> {
+    type Item = Result<(Epoch, Vec<(SV, SP3Entry)>), Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let epoch = match self.next_epoch.take()? {
+            Ok(epoch) => epoch,
+            Err(e) => return Some(Err(e.into())),
+        };
+
+        match self.parse_block(epoch) {
+            Ok(entries) => Some(Ok((epoch, entries))),
+            Err(e) => {
+                // stop iterating
+                self.next_epoch = None;
+                Some(Err(e))
+            },
+        }
+    }
+}
 
-                //TODO : move this into %c config frame
-                if !vehicles.contains(&sv) {
-                    vehicles.push(sv);
-                }
+impl FromStr for SP3 {
+    type Err = Error;
 
-                // verify entry validity
-                if vel_x_dm_s != 0.0_f64 && vel_y_dm_s != 0.0_f64 && vel_z_dm_s != 0.0_f64 {
-                    let key = SP3Key { epoch, sv };
-                    if let Some(e) = data.get_mut(&key) {
-                        *e = e.with_velocity_km_s((vel_x_km_s, vel_y_km_s, vel_z_km_s));
+    /// Parses [SP3] from readable (uncompressed) text content.
+    /// Use [SP3::from_gzip_file] for compressed files.
+    fn from_str(content: &str) -> Result<Self, Self::Err> {
+        let mut reader = BufReader::new(content.as_bytes());
+        Self::from_reader(&mut reader)
+    }
+}
 
-                        if let Some(clk_sub_ns) = clk_sub_ns {
-                            *e = e.with_clock_drift_ns(clk_sub_ns * 0.1);
-                        }
-                    } else {
-                        // Entry does not exist (velocity prior position)
-                        // Should not exist, but we tolerate
-                        if let Some(clk_sub_ns) = clk_sub_ns {
-                            data.insert(
-                                key,
-                                SP3Entry::from_position_km((0.0, 0.0, 0.0))
-                                    .with_velocity_km_s((vel_x_km_s, vel_y_km_s, vel_z_km_s))
-                                    .with_clock_drift_ns(clk_sub_ns * 0.1),
-                            );
-                        } else {
-                            data.insert(
-                                key,
-                                SP3Entry::from_position_km((0.0, 0.0, 0.0))
-                                    .with_velocity_km_s((vel_x_km_s, vel_y_km_s, vel_z_km_s)),
-                            );
-                        }
-                    }
-                }
-            }
-        }
-        Ok(Self {
-            header,
-            data,
-            comments,
-            prod_attributes: None,
-        })
+impl TryFrom<&str> for SP3 {
+    type Error = Error;
+
+    /// Parses [SP3] from readable (uncompressed) text content.
+    /// Use [SP3::from_gzip_file] for compressed files.
+    fn try_from(content: &str) -> Result<Self, Self::Error> {
+        Self::from_str(content)
     }
 }
@@ -10,12 +10,43 @@ pub fn position_entry(content: &str) -> bool {
     content.starts_with('P')
 }
 
+/// Parses the [SV] of a position or velocity entry.
+/// Old revisions (SP3-a/b) may omit the constellation letter,
+/// in which case we assume the header-declared `constellation`
+/// (GPS in case of mixed files).
+pub(crate) fn parse_sv(
+    line: &str,
+    revision: Version,
+    constellation: Constellation,
+) -> Result<SV, ParsingError> {
+    let omitted = match revision {
+        Version::A | Version::B => line[1..2].trim().is_empty(),
+        _ => false,
+    };
+
+    if omitted {
+        let prn = line[2..4].trim().parse::<u8>().or(Err(ParsingError::SV))?;
+
+        let constellation = match constellation {
+            Constellation::Mixed => Constellation::GPS,
+            constellation => constellation,
+        };
+
+        Ok(SV::new(constellation, prn))
+    } else {
+        // parsing needs to pass
+        SV::from_str(line[1..4].trim()).or(Err(ParsingError::SV))
+    }
+}
+
 pub struct PositionEntry {
     pub sv: SV,
     pub x_km: f64,
     pub y_km: f64,
     pub z_km: f64,
     pub clock_us: Option<f64>,
+    pub position_std: Option<(u16, u16, u16)>,
+    pub clock_std: Option<u32>,
     pub clock_event: bool,
     pub clock_prediction: bool,
     pub maneuver: bool,
@@ -23,7 +54,13 @@ pub struct PositionEntry {
 }
 
 impl PositionEntry {
-    pub fn parse(line: &str, revision: Version) -> Result<Self, ParsingError> {
+    /// Parses [PositionEntry] from this line. `constellation` is the
+    /// header-declared system, assumed when the constellation letter is omitted.
+    pub fn parse(
+        line: &str,
+        revision: Version,
+        constellation: Constellation,
+    ) -> Result<Self, ParsingError> {
         let line_len = line.len();
 
         let mut clock_event = false;
@@ -33,18 +70,7 @@ impl PositionEntry {
 
         let mut clock_us: Option<f64> = None;
 
-        let sv = match revision {
-            Version::A => {
-                // GPS-Only: constellation might be omitted
-                let prn = line[2..4].trim().parse::<u8>().or(Err(ParsingError::SV))?;
-
-                SV::new(Constellation::GPS, prn)
-            },
-            _ => {
-                // parsing needs to pass
-                SV::from_str(line[1..4].trim()).or(Err(ParsingError::SV))?
-            },
-        };
+        let sv = parse_sv(line, revision, constellation)?;
 
         let x = f64::from_str(line[4..18].trim())
             .or(Err(ParsingError::Coordinates(line[4..18].to_string())))?;
@@ -62,6 +88,26 @@ impl PositionEntry {
             clock_us = Some(clk_data);
         }
 
+        // standard deviations (SP3-c/d): blank when unknown
+        let position_std = if line_len > 68 {
+            let x_std = line[61..63].trim().parse::<u16>();
+            let y_std = line[64..66].trim().parse::<u16>();
+            let z_std = line[67..69].trim().parse::<u16>();
+
+            match (x_std, y_std, z_std) {
+                (Ok(x_std), Ok(y_std), Ok(z_std)) => Some((x_std, y_std, z_std)),
+                _ => None,
+            }
+        } else {
+            None
+        };
+
+        let clock_std = if line_len > 72 {
+            line[70..73].trim().parse::<u32>().ok()
+        } else {
+            None
+        };
+
         if line_len > 74 && line[74..75].eq("E") {
             clock_event = true;
         }
@@ -81,6 +127,8 @@ impl PositionEntry {
         Ok(Self {
             sv,
             clock_us,
+            position_std,
+            clock_std,
             clock_event,
             clock_prediction,
             orbit_prediction,
@@ -95,7 +143,7 @@ impl PositionEntry {
 #[cfg(test)]
 mod test {
     use super::PositionEntry;
-    use crate::prelude::{Version, SV};
+    use crate::prelude::{Constellation, Version, SV};
     use std::str::FromStr;
 
     #[test]
@@ -186,7 +234,7 @@ mod test {
             ),
         ] {
             let sv = SV::from_str(sv).unwrap();
-            let entry = PositionEntry::parse(content, Version::C).unwrap();
+            let entry = PositionEntry::parse(content, Version::C, Constellation::Mixed).unwrap();
             assert_eq!(entry.sv, sv);
             assert_eq!(entry.x_km, x_km);
             assert_eq!(entry.y_km, y_km);
@@ -206,9 +254,10 @@ mod test {
         let content =
             "PG01 -22335.782004 -14656.280389  -1218.238499   -176.397152 10  9 11 102 EP  MP";
 
-        let position = PositionEntry::parse(content, Version::C).unwrap_or_else(|e| {
-            panic!("Failed to parse predicted state \"{}\": {}", content, e);
-        });
+        let position = PositionEntry::parse(content, Version::C, Constellation::Mixed)
+            .unwrap_or_else(|e| {
+                panic!("Failed to parse predicted state \"{}\": {}", content, e);
+            });
 
         assert_eq!(position.sv, g01);
         assert_eq!(position.x_km, -22335.782004);
@@ -218,5 +267,27 @@ mod test {
         assert!(position.clock_prediction);
         assert!(position.maneuver);
         assert!(position.orbit_prediction);
+        assert_eq!(position.position_std, Some((10, 9, 11)));
+        assert_eq!(position.clock_std, Some(102));
+    }
+
+    #[test]
+    fn omitted_constellation() {
+        let content = "P  5 -22335.782004 -14656.280389  -1218.238499   -176.397152";
+
+        for (revision, constellation, expected) in [
+            (Version::A, Constellation::GPS, "G05"),
+            (Version::A, Constellation::Glonass, "R05"),
+            (Version::A, Constellation::Mixed, "G05"),
+            (Version::B, Constellation::Glonass, "R05"),
+        ] {
+            let entry = PositionEntry::parse(content, revision, constellation).unwrap();
+            assert_eq!(entry.sv, SV::from_str(expected).unwrap());
+        }
+
+        // explicit constellation always prevails
+        let content = "PR05 -22335.782004 -14656.280389  -1218.238499   -176.397152";
+        let entry = PositionEntry::parse(content, Version::A, Constellation::GPS).unwrap();
+        assert_eq!(entry.sv, SV::from_str("R05").unwrap());
     }
 }
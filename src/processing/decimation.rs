@@ -1,4 +1,5 @@
 use qc_traits::{Decimate, DecimationFilter, DecimationFilterType};
+use std::collections::BTreeSet;
 
 use crate::prelude::{Epoch, Header, SP3};
 
@@ -61,3 +62,219 @@ impl Decimate for SP3 {
         }
     }
 }
+
+impl SP3 {
+    /// Decimates this [SP3] like [Decimate::decimate], except that epochs
+    /// carrying a clock event or a maneuver flag (for any satellite) are always retained,
+    /// whatever the decimation rule. The rule applies to the epochs of the record:
+    /// [DecimationFilterType::Modulo] retains one epoch out of `modulo`,
+    /// [DecimationFilterType::Duration] retains epochs at least `interval` apart.
+    pub fn downsample_preserving_events(&self, f: &DecimationFilter) -> Self {
+        let mut s = self.clone();
+        s.downsample_preserving_events_mut(f);
+        s
+    }
+
+    /// Mutable [SP3::downsample_preserving_events] implementation.
+    /// Targeted filters (with a [DecimationFilter] item) are not supported yet:
+    /// the [SP3] is left untouched in that case.
+    pub fn downsample_preserving_events_mut(&mut self, f: &DecimationFilter) {
+        if f.item.is_some() {
+            return;
+        }
+
+        self.header.decimate_mut(f);
+
+        // chronological epochs (the record is sorted by satellite first)
+        let epochs = self.data.keys().map(|k| k.epoch).collect::<BTreeSet<_>>();
+
+        let mut retained = BTreeSet::<Epoch>::new();
+
+        match f.filter {
+            DecimationFilterType::Modulo(modulo) => {
+                retained.extend(epochs.into_iter().step_by(modulo.max(1) as usize));
+            },
+            DecimationFilterType::Duration(interval) => {
+                let mut last_retained = Option::<Epoch>::None;
+
+                for epoch in epochs {
+                    if let Some(last) = last_retained {
+                        if epoch - last < interval {
+                            continue;
+                        }
+                    }
+
+                    last_retained = Some(epoch);
+                    retained.insert(epoch);
+                }
+            },
+        }
+
+        for (k, v) in self.data.iter() {
+            if v.clock_event || v.maneuver {
+                retained.insert(k.epoch);
+            }
+        }
+
+        self.data.retain(|k, _| retained.contains(&k.epoch));
+    }
+
+    /// Decimates this [SP3] by an integer ratio, keeping one epoch every `factor`
+    /// epochs, starting on the first [Epoch]. Unlike [Decimate::decimate], the ratio applies
+    /// to the epochs (not the entries), and the [Header] remains honest:
+    /// the sampling period is multiplied by `factor` and the number of epochs is updated.
+    /// A steady [SP3] therefore remains steady (see [SP3::has_steady_sampling]).
+    /// This is a no-op for `factor` lower than 2.
+    pub fn decimate_by_ratio(&self, factor: u32) -> Self {
+        let mut s = self.clone();
+        s.decimate_by_ratio_mut(factor);
+        s
+    }
+
+    /// Mutable [SP3::decimate_by_ratio] implementation.
+    pub fn decimate_by_ratio_mut(&mut self, factor: u32) {
+        if factor < 2 {
+            return;
+        }
+
+        let epochs = self.epochs_iter().collect::<BTreeSet<_>>();
+
+        let retained = epochs
+            .into_iter()
+            .step_by(factor as usize)
+            .collect::<BTreeSet<_>>();
+
+        self.data.retain(|k, _| retained.contains(&k.epoch));
+
+        self.header.sampling_period = self.header.sampling_period * factor as f64;
+        self.header.num_epochs = retained.len() as u64;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::prelude::{DataType, Epoch, Version, SP3};
+    use crate::tests::fixtures::{sp3_d_header, sp3_header, t0};
+    use hifitime::Unit;
+    use qc_traits::{Decimate, DecimationFilter};
+    use std::collections::BTreeSet;
+    use std::str::FromStr;
+
+    #[test]
+    fn downsample_preserving_events() {
+        let content = sp3_d_header(4)
+            + "*  2023  8 27  0  0  0.00000000
+PG01 -22335.782004 -14656.280389  -1218.238499   -176.397152
+*  2023  8 27  0 15  0.00000000
+PG01 -22335.782004 -14656.280389  -1218.238499   -176.397152 10  9 11 102 E
+*  2023  8 27  0 30  0.00000000
+PG01 -22335.782004 -14656.280389  -1218.238499   -176.397152
+*  2023  8 27  0 45  0.00000000
+PG01 -22335.782004 -14656.280389  -1218.238499   -176.397152
+EOF";
+
+        let sp3 = SP3::from_str(&content).unwrap();
+        assert_eq!(sp3.total_epochs(), 4);
+
+        let event = Epoch::from_str("2023-08-27T00:15:00 GPST").unwrap();
+
+        // plain decimation drops the event
+        let decimated = sp3.decimate(&DecimationFilter::modulo(2));
+        assert!(decimated.epochs_iter().all(|t| t != event));
+
+        let decimated = sp3.downsample_preserving_events(&DecimationFilter::modulo(2));
+
+        let epochs = decimated.epochs_iter().collect::<Vec<_>>();
+
+        assert_eq!(
+            epochs,
+            vec![
+                t0(),
+                event,
+                Epoch::from_str("2023-08-27T00:30:00 GPST").unwrap(),
+            ]
+        );
+
+        assert!(decimated.has_satellite_clock_event());
+    }
+
+    #[test]
+    fn downsample_preserving_events_chronological() {
+        // G01 starts after G02: satellite-major iteration is not chronological
+        let content = sp3_header(Version::D, DataType::Position, &["G01", "G02"], 4)
+            + "*  2023  8 27  0  0  0.00000000
+PG02 -22335.782004 -14656.280389  -1218.238499   -176.397152
+*  2023  8 27  0 15  0.00000000
+PG02 -22335.782004 -14656.280389  -1218.238499   -176.397152
+*  2023  8 27  0 30  0.00000000
+PG01 -22335.782004 -14656.280389  -1218.238499   -176.397152
+PG02 -22335.782004 -14656.280389  -1218.238499   -176.397152
+*  2023  8 27  0 45  0.00000000
+PG01 -22335.782004 -14656.280389  -1218.238499   -176.397152
+PG02 -22335.782004 -14656.280389  -1218.238499   -176.397152
+EOF";
+
+        let sp3 = SP3::from_str(&content).unwrap();
+
+        let decimated = sp3.downsample_preserving_events(&DecimationFilter::modulo(2));
+
+        let epochs = decimated
+            .data
+            .keys()
+            .map(|k| k.epoch)
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            epochs,
+            vec![t0(), Epoch::from_str("2023-08-27T00:30:00 GPST").unwrap(),]
+        );
+
+        let decimated =
+            sp3.downsample_preserving_events(&DecimationFilter::duration(30.0 * Unit::Minute));
+
+        let epochs = decimated
+            .data
+            .keys()
+            .map(|k| k.epoch)
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            epochs,
+            vec![t0(), Epoch::from_str("2023-08-27T00:30:00 GPST").unwrap(),]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "flate2")]
+    fn decimate_by_ratio() {
+        let sp3 =
+            SP3::from_gzip_file("data/SP3/C/GRG0MGXFIN_20201760000_01D_15M_ORB.SP3.gz").unwrap();
+
+        assert_eq!(sp3.header.sampling_period, 15.0 * Unit::Minute);
+        assert!(sp3.has_steady_sampling());
+
+        let total_epochs = sp3.total_epochs();
+
+        let decimated = sp3.decimate_by_ratio(2);
+
+        assert_eq!(decimated.header.sampling_period, 30.0 * Unit::Minute);
+        assert!(decimated.has_steady_sampling());
+
+        assert_eq!(decimated.first_epoch(), sp3.first_epoch());
+        assert_eq!(decimated.total_epochs(), total_epochs.div_ceil(2));
+        assert_eq!(decimated.header.num_epochs, decimated.total_epochs() as u64);
+
+        // all satellites are preserved, at each retained epoch
+        for (k, v) in decimated.data.iter() {
+            assert_eq!(sp3.data.get(k), Some(v));
+        }
+
+        // no-op
+        assert_eq!(sp3.decimate_by_ratio(1), sp3);
+        assert_eq!(sp3.decimate_by_ratio(0), sp3);
+    }
+}
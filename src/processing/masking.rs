@@ -104,6 +104,8 @@ impl Masking for Header {
                 _ => {},
             },
         }
+
+        self.num_satellites = self.satellites.len();
     }
     fn mask(&self, mask: &MaskFilter) -> Self {
         let mut s = self.clone();
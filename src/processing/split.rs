@@ -1,6 +1,20 @@
 use crate::prelude::{Duration, Epoch, Header, SP3};
 use qc_traits::Split;
 
+/// Returns the contiguous `[start, end)` windows, each spanning `dt`, anchored on `first`
+/// and covering `[first, last]`. This is the boundary computation shared by the
+/// [Header] and [SP3] implementations of [Split::split_even_dt], `dt` must be positive.
+fn even_dt_windows(first: Epoch, last: Epoch, dt: Duration) -> Vec<(Epoch, Epoch)> {
+    let num_windows = (last - first).total_nanoseconds() / dt.total_nanoseconds() + 1;
+
+    (0..num_windows)
+        .map(|i| {
+            let start = first + i as f64 * dt;
+            (start, start + dt)
+        })
+        .collect()
+}
+
 impl Split for Header {
     fn split(&self, epoch: Epoch) -> (Self, Self)
     where
@@ -15,11 +29,60 @@ impl Split for Header {
         self.clone()
     }
 
-    fn split_even_dt(&self, _: Duration) -> Vec<Self>
+    /// Splits the time frame described by this [Header] into contiguous pieces,
+    /// each spanning `dt`, starting on the publication [Epoch].
+    /// Each piece describes its own epochs and time references.
+    fn split_even_dt(&self, dt: Duration) -> Vec<Self>
     where
         Self: Sized,
     {
-        Default::default()
+        if self.num_epochs == 0 {
+            return Vec::new();
+        }
+
+        if dt <= Duration::ZERO || self.sampling_period <= Duration::ZERO {
+            return vec![self.clone()];
+        }
+
+        let first = self.release_epoch;
+
+        let epochs = (0..self.num_epochs)
+            .map(|i| first + i as f64 * self.sampling_period)
+            .collect::<Vec<_>>();
+
+        let last = epochs[epochs.len() - 1];
+
+        even_dt_windows(first, last, dt)
+            .into_iter()
+            .filter_map(|(start, end)| {
+                let mut window = epochs.iter().filter(|t| **t >= start && **t < end);
+
+                let first = *window.next()?;
+
+                let mut piece = self.clone();
+                piece.num_epochs = window.count() as u64 + 1;
+                piece.release_epoch = first;
+                piece.align_week_to_first_epoch(first);
+                piece.align_mjd_to_first_epoch(first);
+
+                Some(piece)
+            })
+            .collect()
+    }
+}
+
+impl SP3 {
+    /// Rebuilds the [Header] of a split piece (see [SP3::canonicalize_mut]) and aligns its
+    /// time references (publication [Epoch], week counter and MJD) to its earliest [Epoch],
+    /// so the piece is self-consistent once dumped.
+    fn align_split_header(&mut self) {
+        self.canonicalize_mut();
+
+        // the record is sorted by satellite first
+        if let Some(first) = self.data.keys().map(|k| k.epoch).min() {
+            self.header.release_epoch = first;
+            self.header.align_mjd_to_first_epoch(first);
+        }
     }
 }
 
@@ -39,16 +102,55 @@ impl Split for SP3 {
 
         rhs.data.retain(|k, _| k.epoch > epoch);
         rhs.header = rhs_header; // overwrite header
+        rhs.align_split_header();
 
         self.data.retain(|k, _| k.epoch <= epoch);
+        self.align_split_header();
 
         rhs
     }
 
-    fn split_even_dt(&self, _dt: Duration) -> Vec<Self>
+    /// Splits this [SP3] into contiguous pieces, each spanning `dt`,
+    /// starting on the first [Epoch] of the record. Empty pieces are not reported.
+    /// The [Header] of each piece is rebuilt and aligned to its first [Epoch].
+    fn split_even_dt(&self, dt: Duration) -> Vec<Self>
     where
         Self: Sized,
     {
-        Default::default()
+        // the record is sorted by satellite first
+        let t0 = match self.data.keys().map(|k| k.epoch).min() {
+            Some(t0) => t0,
+            None => return Vec::new(),
+        };
+
+        if dt <= Duration::ZERO {
+            return vec![self.clone()];
+        }
+
+        let last = self.data.keys().map(|k| k.epoch).max().unwrap_or(t0);
+
+        let mut empty = self.clone();
+        empty.data.clear();
+
+        even_dt_windows(t0, last, dt)
+            .into_iter()
+            .filter_map(|(start, end)| {
+                let mut piece = empty.clone();
+
+                piece.data = self
+                    .data
+                    .iter()
+                    .filter(|(k, _)| k.epoch >= start && k.epoch < end)
+                    .map(|(k, v)| (k.clone(), *v))
+                    .collect();
+
+                if piece.data.is_empty() {
+                    return None;
+                }
+
+                piece.align_split_header();
+                Some(piece)
+            })
+            .collect()
     }
 }
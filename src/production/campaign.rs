@@ -16,7 +16,9 @@ pub enum Campaign {
     #[default]
     OPS,
 
-    /// Reprocessing campaign (with number of campaign iteration)
+    /// Reprocessing campaign (with number of campaign iteration).
+    /// The iteration number is limited to two digits (00 to 99)
+    /// as per the IGS naming conventions.
     Reprocessing(u8),
 
     /// Tide Gauche Benchmark Monitoring (TIGA)
@@ -37,8 +39,14 @@ impl std::str::FromStr for Campaign {
             "OPS" => Ok(Self::OPS),
             "MGX" => Ok(Self::MGX),
             value => {
-                if value.starts_with('R') {
-                    let rnn = value[1..]
+                if let Some(digits) = value.strip_prefix('R') {
+                    // exactly two digits (00..99): anything else
+                    // would not format back to the same name
+                    if digits.len() != 2 || !digits.chars().all(|c| c.is_ascii_digit()) {
+                        return Err(ParsingError::InvalidCampaignName);
+                    }
+
+                    let rnn = digits
                         .parse::<u8>()
                         .or(Err(ParsingError::InvalidCampaignName))?;
 
@@ -63,3 +71,30 @@ impl std::fmt::Display for Campaign {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn reprocessing_campaigns() {
+        for (value, expected) in [
+            ("R00", Campaign::Reprocessing(0)),
+            ("R01", Campaign::Reprocessing(1)),
+            ("R99", Campaign::Reprocessing(99)),
+        ] {
+            let campaign = Campaign::from_str(value).unwrap();
+            assert_eq!(campaign, expected);
+            assert_eq!(campaign.to_string(), value, "reciprocal failed");
+        }
+
+        for value in ["R100", "R1", "R", "R-1", "R+1", "RAB"] {
+            assert!(
+                Campaign::from_str(value).is_err(),
+                "should have rejected \"{}\"",
+                value
+            );
+        }
+    }
+}
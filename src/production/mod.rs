@@ -1,6 +1,5 @@
 use crate::{prelude::Duration, ParsingError};
 
-#[cfg(doc)]
 use crate::prelude::SP3;
 
 mod campaign;
@@ -15,6 +14,9 @@ pub use date::*;
 mod period;
 pub use period::*;
 
+mod summary;
+pub use summary::*;
+
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
@@ -51,6 +53,20 @@ pub struct ProductionAttributes {
     pub gzip_compressed: bool,
 }
 
+/// Converts any agency description to a 3-letter code compatible with the
+/// file naming conventions: longer strings are truncated,
+/// shorter strings are padded and non ASCII alphanumeric characters are replaced, with `X`.
+pub(crate) fn agency_code(agency: &str) -> String {
+    let mut chars = agency.chars();
+
+    (0..3)
+        .map(|_| match chars.next() {
+            Some(c) if c.is_ascii_alphanumeric() => c,
+            _ => 'X',
+        })
+        .collect()
+}
+
 impl std::fmt::Display for ProductionAttributes {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let sampling_interval_mins = (self.sampling_period.to_seconds() / 60.0).round() as u16;
@@ -58,7 +74,7 @@ impl std::fmt::Display for ProductionAttributes {
         write!(
             f,
             "{}{}{}{}_{:04}{:03}0000_{}_{:02}M_ORB.SP3",
-            &self.agency[..3],
+            agency_code(&self.agency),
             self.batch_id,
             self.campaign,
             self.availability,
@@ -141,6 +157,49 @@ impl std::str::FromStr for ProductionAttributes {
     }
 }
 
+impl SP3 {
+    /// Infers [ProductionAttributes] from the [Header] fields, which is
+    /// useful for files that do not follow the standard naming conventions
+    /// (legacy or short file names). Agency, [ReleaseDate] and sampling period
+    /// are deduced from the [Header], while all other fields use their default value
+    /// (batch #0, [Campaign::OPS], [Availability::Rapid], [ReleasePeriod::Daily]).
+    pub fn infer_production_attributes(&self) -> ProductionAttributes {
+        ProductionAttributes {
+            agency: agency_code(&self.header.agency),
+            batch_id: 0,
+            release_date: ReleaseDate::from(self.header.release_epoch),
+            release_period: ReleasePeriod::default(),
+            campaign: Campaign::default(),
+            availability: Availability::default(),
+            sampling_period: self.header.sampling_period,
+            #[cfg(feature = "flate2")]
+            gzip_compressed: false,
+        }
+    }
+
+    /// Verifies that the sampling interval encoded in the standardized file name
+    /// (for example `15M`) matches the [Header] sampling period, because
+    /// producers occasionally mislabel their files.
+    /// Returns None when the file name did not follow the standard naming conventions
+    /// (no [ProductionAttributes]).
+    pub fn filename_sampling_matches(&self) -> Option<bool> {
+        let attributes = self.prod_attributes.as_ref()?;
+        Some(attributes.sampling_period == self.header.sampling_period)
+    }
+}
+
+#[cfg(feature = "serde")]
+#[cfg_attr(docsrs, doc(cfg(feature = "serde")))]
+impl SP3 {
+    /// Serializes the [ProductionAttributes] of this [SP3] as JSON structured metadata,
+    /// which is typically what archive cataloguing tools want to ingest.
+    /// Returns None when the file did not follow the standard naming conventions.
+    pub fn production_metadata_json(&self) -> Option<String> {
+        let attributes = self.prod_attributes.as_ref()?;
+        serde_json::to_string(attributes).ok()
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
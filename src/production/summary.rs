@@ -0,0 +1,60 @@
+use crate::prelude::{Availability, TimeScale, SP3};
+
+use super::Campaign;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// [ProductSummary] gathers the provenance of an [SP3] product,
+/// in a single structure (one per file).
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ProductSummary {
+    /// Publishing agency
+    pub agency: String,
+
+    /// Production [Campaign]
+    pub campaign: Campaign,
+
+    /// Product [Availability]
+    pub availability: Availability,
+
+    /// Coordinates system (reference frame) as described in the header
+    pub frame: String,
+
+    /// [TimeScale] in which the product is expressed
+    pub timescale: TimeScale,
+
+    /// True when most of the orbital states are predicted (not fitted)
+    pub is_prediction_heavy: bool,
+}
+
+impl SP3 {
+    /// Returns the [ProductSummary] of this [SP3] product.
+    /// When the file did not follow the standard naming conventions,
+    /// the production attributes are inferred from the [Header](crate::prelude::Header)
+    /// (see [SP3::infer_production_attributes]).
+    pub fn product_summary(&self) -> ProductSummary {
+        let attributes = match &self.prod_attributes {
+            Some(attributes) => attributes.clone(),
+            None => self.infer_production_attributes(),
+        };
+
+        let total = self.data.len();
+
+        let predicted = self
+            .data
+            .values()
+            .filter(|entry| entry.predicted_orbit)
+            .count();
+
+        ProductSummary {
+            agency: attributes.agency,
+            campaign: attributes.campaign,
+            availability: attributes.availability,
+            frame: self.header.coord_system.clone(),
+            timescale: self.header.timescale,
+            is_prediction_heavy: total > 0 && 2 * predicted > total,
+        }
+    }
+}
@@ -1,6 +1,7 @@
-use crate::prelude::{Constellation, Header, SP3};
+use crate::prelude::{Constellation, Duration, Epoch, Header, OrbitType, SP3, SV};
 
 use qc_traits::{Merge, MergeError};
+use thiserror::Error;
 
 #[cfg(doc)]
 use qc_traits::Timeshift;
@@ -8,6 +9,174 @@ use qc_traits::Timeshift;
 #[cfg(doc)]
 use crate::prelude::TimeScale;
 
+/// [MergeWarning]s do not prevent the merging operation,
+/// but describe differences that are physically questionable.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MergeWarning {
+    /// Merged files were not fitted with the same [OrbitType]
+    OrbitTypeMismatch(OrbitType, OrbitType),
+
+    /// Merged files do not share the same sampling period
+    SamplingPeriodMismatch(Duration, Duration),
+}
+
+/// Collection of [MergeWarning]s, reported by a merging operation.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MergeWarnings(Vec<MergeWarning>);
+
+impl MergeWarnings {
+    /// Returns true if no warning was reported
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns the number of reported warnings
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns true if this [MergeWarning] was reported
+    pub fn contains(&self, warning: &MergeWarning) -> bool {
+        self.0.contains(warning)
+    }
+
+    /// [MergeWarning]s [Iterator]
+    pub fn iter(&self) -> impl Iterator<Item = &MergeWarning> + '_ {
+        self.0.iter()
+    }
+}
+
+/// [MergeConflictPolicy] describes how a merging operation handles entries
+/// that both files describe (same satellite and same [Epoch]), with differing positions.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MergeConflictPolicy {
+    /// Keep the position of the left-hand side. This is the default behavior,
+    /// and the behavior of [Merge::merge].
+    #[default]
+    KeepLhs,
+
+    /// Reject the merging operation: [MergeConflictError::PositionConflict].
+    Reject,
+
+    /// Average the two positions (and clock offsets, when both are known).
+    Average,
+}
+
+/// Errors that may rise when merging with a [MergeConflictPolicy].
+#[derive(Debug, Error)]
+pub enum MergeConflictError {
+    #[error("merge error: {0}")]
+    Merge(#[from] MergeError),
+
+    #[error("conflicting positions for {sv} at {epoch}")]
+    PositionConflict { sv: SV, epoch: Epoch },
+}
+
+impl Header {
+    /// Returns the [MergeWarnings] that merging `rhs` into self would report.
+    /// Refer to [Header::merge_mut] for the verifications that
+    /// actually prevent the merging operation.
+    pub fn merge_warnings(&self, rhs: &Self) -> MergeWarnings {
+        let mut warnings = MergeWarnings::default();
+
+        if self.orbit_type != rhs.orbit_type {
+            warnings.0.push(MergeWarning::OrbitTypeMismatch(
+                self.orbit_type,
+                rhs.orbit_type,
+            ));
+        }
+
+        if self.sampling_period != rhs.sampling_period {
+            warnings.0.push(MergeWarning::SamplingPeriodMismatch(
+                self.sampling_period,
+                rhs.sampling_period,
+            ));
+        }
+
+        warnings
+    }
+}
+
+impl SP3 {
+    /// Merge `rhs` [SP3] into self, like [SP3::merge_mut], but also reports
+    /// the [MergeWarnings] (physically questionable differences) that did not
+    /// prevent the operation, like merging different [OrbitType]s.
+    pub fn merge_mut_with_warnings(&mut self, rhs: &Self) -> Result<MergeWarnings, MergeError> {
+        let warnings = self.header.merge_warnings(&rhs.header);
+        self.merge_mut(rhs)?;
+        Ok(warnings)
+    }
+
+    /// Merge `rhs` [SP3] into self, like [SP3::merge_mut], but handles
+    /// conflicting positions (same satellite and [Epoch]) with desired [MergeConflictPolicy].
+    /// With [MergeConflictPolicy::Reject], self is not modified when a conflict is found.
+    pub fn merge_mut_with_policy(
+        &mut self,
+        rhs: &Self,
+        policy: MergeConflictPolicy,
+    ) -> Result<(), MergeConflictError> {
+        let conflicts = rhs
+            .data
+            .iter()
+            .filter_map(|(key, entry)| {
+                let lhs_entry = self.data.get(key)?;
+                if lhs_entry.position_km != entry.position_km {
+                    Some((key.clone(), *lhs_entry))
+                } else {
+                    None
+                }
+            })
+            .collect::<Vec<_>>();
+
+        if policy == MergeConflictPolicy::Reject {
+            if let Some((key, _)) = conflicts.first() {
+                return Err(MergeConflictError::PositionConflict {
+                    sv: key.sv,
+                    epoch: key.epoch,
+                });
+            }
+        }
+
+        self.merge_mut(rhs)?;
+
+        if policy == MergeConflictPolicy::Average {
+            for (key, lhs_entry) in conflicts {
+                let (Some(entry), Some(rhs_entry)) = (self.data.get_mut(&key), rhs.data.get(&key))
+                else {
+                    continue;
+                };
+
+                entry.position_km = (
+                    (lhs_entry.position_km.0 + rhs_entry.position_km.0) / 2.0,
+                    (lhs_entry.position_km.1 + rhs_entry.position_km.1) / 2.0,
+                    (lhs_entry.position_km.2 + rhs_entry.position_km.2) / 2.0,
+                );
+
+                if let (Some(lhs_clock_us), Some(rhs_clock_us)) =
+                    (lhs_entry.clock_us, rhs_entry.clock_us)
+                {
+                    entry.clock_us = Some((lhs_clock_us + rhs_clock_us) / 2.0);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Merge `rhs` [SP3] into self, creating a new combined [SP3],
+    /// while handling conflicting positions with desired [MergeConflictPolicy].
+    /// See [SP3::merge_mut_with_policy].
+    pub fn merge_with_policy(
+        &self,
+        rhs: &Self,
+        policy: MergeConflictPolicy,
+    ) -> Result<Self, MergeConflictError> {
+        let mut s = self.clone();
+        s.merge_mut_with_policy(rhs, policy)?;
+        Ok(s)
+    }
+}
+
 impl Merge for Header {
     /// Merge `rhs` [SP3] into self, creating a new combined [SP3].
     /// For this operation to work:
@@ -64,6 +233,14 @@ impl Merge for Header {
             }
         }
 
+        self.num_satellites = self.satellites.len();
+
+        for (satellite, exponent) in rhs.accuracy_exponents.iter() {
+            self.accuracy_exponents
+                .entry(*satellite)
+                .or_insert(*exponent);
+        }
+
         // update sampling
         self.sampling_period = std::cmp::max(self.sampling_period, rhs.sampling_period);
 
@@ -97,6 +274,11 @@ impl Merge for SP3 {
                 self.data.insert(key.clone(), entry.clone()); // new entry
             }
         }
+
+        // header counters depend on the merged content
+        self.header.num_epochs = self.total_epochs() as u64;
+        self.header.satellites.sort();
+
         Ok(())
     }
 }
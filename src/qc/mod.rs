@@ -1 +1,3 @@
 mod merge;
+
+pub use merge::{MergeConflictError, MergeConflictPolicy, MergeWarning, MergeWarnings};
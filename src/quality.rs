@@ -0,0 +1,153 @@
+//! Orbit quality metrics
+use crate::{
+    lagrange_interpolation,
+    prelude::{Duration, Epoch, SP3, SV},
+    Vector3D,
+};
+
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Interpolation order used when evaluating products in overlap regions
+const OVERLAP_INTERPOLATION_ORDER: usize = 9;
+
+// Evaluates the Lagrangian fit of the (order +1) data points closest to `t`.
+// Unlike our regular interpolation, the window is not required to be centered,
+// so this will extrapolate close to the edges of the dataset.
+fn closest_fit(order: usize, t: Epoch, points: &[(Epoch, Vector3D)]) -> Option<Vector3D> {
+    let window_len = order + 1;
+
+    if points.len() < window_len {
+        return None;
+    }
+
+    let closest = points.partition_point(|(t_i, _)| *t_i < t);
+
+    let start = closest
+        .saturating_sub(window_len / 2)
+        .min(points.len() - window_len);
+
+    lagrange_interpolation(order, t, points[start..start + window_len].to_vec())
+}
+
+impl SP3 {
+    // Stable (not maneuvered) positions of this satellite, sorted in time
+    fn stable_positions_km(&self, sv: SV) -> Vec<(Epoch, Vector3D)> {
+        self.satellites_stable_position_km_iter()
+            .filter_map(|(t, sv_i, _, position_km)| {
+                if sv_i == sv {
+                    Some((t, position_km))
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+
+    /// Computes the orbit overlap RMS at the boundary between this [SP3]
+    /// and the `next_day` [SP3], which is a standard quality metric of consecutive products.
+    /// The overlap window has the desired `overlap` duration and is centered on the boundary
+    /// (midway between our last [Epoch] and the first [Epoch] of `next_day`).
+    /// Both products are evaluated over this window, with our sampling period,
+    /// using a 9th order Lagrangian fit of their closest data points,
+    /// which extrapolates the products that do not cover the entire window.
+    /// Extrapolation quickly degrades, so we recommend short overlaps (under one hour).
+    ///
+    /// ## Output
+    /// - RMS of the 3D position difference, in kilometers, for each satellite
+    ///   described by both products.
+    pub fn overlap_rms(&self, next_day: &SP3, overlap: Duration) -> BTreeMap<SV, f64> {
+        let mut rms = BTreeMap::new();
+
+        // records are sorted by satellite first
+        let (last, next_first) = match (
+            self.data.keys().map(|k| k.epoch).max(),
+            next_day.data.keys().map(|k| k.epoch).min(),
+        ) {
+            (Some(last), Some(next_first)) => (last, next_first),
+            _ => return rms,
+        };
+
+        let dt = self.header.sampling_period;
+
+        if dt <= Duration::ZERO {
+            return rms;
+        }
+
+        let boundary = last + (next_first - last) * 0.5;
+        let (start, end) = (boundary - overlap * 0.5, boundary + overlap * 0.5);
+
+        for sv in self.satellites_iter() {
+            let points = self.stable_positions_km(sv);
+            let next_points = next_day.stable_positions_km(sv);
+
+            let (mut sum, mut count) = (0.0_f64, 0);
+            let mut t = start;
+
+            while t <= end {
+                let fits = (
+                    closest_fit(OVERLAP_INTERPOLATION_ORDER, t, &points),
+                    closest_fit(OVERLAP_INTERPOLATION_ORDER, t, &next_points),
+                );
+
+                if let (Some((x_km, y_km, z_km)), Some((next_x_km, next_y_km, next_z_km))) = fits {
+                    sum += (x_km - next_x_km).powi(2)
+                        + (y_km - next_y_km).powi(2)
+                        + (z_km - next_z_km).powi(2);
+
+                    count += 1;
+                }
+
+                t += dt;
+            }
+
+            if count > 0 {
+                rms.insert(sv, (sum / count as f64).sqrt());
+            }
+        }
+
+        rms
+    }
+
+    /// Measures the accuracy of our interpolation method, against a `truth` [SP3]
+    /// that is sampled more densely (for example, the product we were decimated from).
+    /// Each `truth` position, at an [Epoch] that is not part of our record,
+    /// is compared to our Lagrangian interpolation of desired `order`
+    /// (see [SP3::satellite_position_lagrangian_interpolation]).
+    /// This will panic on even interpolation orders.
+    ///
+    /// ## Output
+    /// - RMS of the 3D position error, in kilometers, for each satellite
+    ///   that could be interpolated at least once.
+    pub fn interpolation_accuracy(&self, truth: &SP3, order: usize) -> BTreeMap<SV, f64> {
+        let grid = self.epochs_iter().collect::<BTreeSet<_>>();
+
+        let mut errors = BTreeMap::<SV, (f64, usize)>::new();
+
+        for (k, v) in truth.data.iter() {
+            if grid.contains(&k.epoch) || v.position_km == (0.0, 0.0, 0.0) {
+                continue;
+            }
+
+            let (x_km, y_km, z_km) =
+                match self.satellite_position_lagrangian_interpolation(k.sv, k.epoch, order) {
+                    Some(position_km) => position_km,
+                    None => continue,
+                };
+
+            let (truth_x_km, truth_y_km, truth_z_km) = v.position_km;
+
+            let (sum, count) = errors.entry(k.sv).or_insert((0.0, 0));
+
+            *sum += (x_km - truth_x_km).powi(2)
+                + (y_km - truth_y_km).powi(2)
+                + (z_km - truth_z_km).powi(2);
+
+            *count += 1;
+        }
+
+        errors
+            .into_iter()
+            .map(|(sv, (sum, count))| (sv, (sum / count as f64).sqrt()))
+            .collect()
+    }
+}
@@ -0,0 +1,82 @@
+//! Async parsing tests
+#[cfg(test)]
+mod test {
+    use crate::prelude::*;
+    use crate::tests::fixtures::{sp3_d_header, sp3_header};
+    use std::{io::Cursor, path::PathBuf, str::FromStr};
+
+    #[tokio::test]
+    async fn async_reader() {
+        let path = PathBuf::new()
+            .join(env!("CARGO_MANIFEST_DIR"))
+            .join("data/SP3")
+            .join("D")
+            .join("example.txt");
+
+        let content = std::fs::read(&path).unwrap();
+
+        let sp3 = SP3::from_async_reader(Cursor::new(content))
+            .await
+            .unwrap_or_else(|e| panic!("failed to parse {}: {}", path.display(), e));
+
+        let expected = SP3::from_file(&path).unwrap();
+
+        assert_eq!(sp3.header.version, Version::D);
+        assert_eq!(sp3.header, expected.header);
+        assert_eq!(sp3.comments, expected.comments);
+        assert_eq!(sp3.data, expected.data);
+    }
+
+    #[tokio::test]
+    async fn async_cursor() {
+        let content = sp3_header(Version::D, DataType::Velocity, &["G01", "E05"], 2)
+            .replace("++         0  0", "++         5  7")
+            + "/* async cursor
+*  2023  8 27  0  0  0.00000000
+PG01 -22335.782004 -14656.280389  -1218.238499   -176.397152
+VG01  -1436.471028  -2195.735237  -3181.326745     -0.004031
+PE05  13001.000000 -19000.000000  15000.123456     -9.500000
+*  2023  8 27  0 15  0.00000000
+PG01 -22335.000000 -14656.000000  -1218.000000   -176.400000
+VG01  -1436.000000  -2195.000000  -3181.000000     -0.004000
+PE05  13002.000000 -19001.000000  15001.000000     -9.600000
+EOF";
+
+        let sp3 = SP3::from_async_reader(Cursor::new(content.as_bytes()))
+            .await
+            .unwrap();
+
+        let expected = SP3::from_str(&content).unwrap();
+
+        assert_eq!(sp3.header.data_type, DataType::Velocity);
+        assert_eq!(sp3.header.num_epochs, 2);
+        assert_eq!(sp3.comments, vec!["async cursor".to_string()]);
+        assert_eq!(sp3.data.len(), 4);
+
+        assert_eq!(sp3.header, expected.header);
+        assert_eq!(sp3.data, expected.data);
+    }
+
+    #[tokio::test]
+    async fn async_stream() {
+        use tokio::io::AsyncWriteExt;
+
+        let content = sp3_d_header(1)
+            + "*  2023  8 27  0  0  0.00000000
+PG01 -22335.782004 -14656.280389  -1218.238499   -176.397152
+EOF
+";
+
+        // the writer is kept open: parsing completes on "EOF",
+        // without waiting for the end of the stream
+        let (mut writer, reader) = tokio::io::duplex(4096);
+        writer.write_all(content.as_bytes()).await.unwrap();
+
+        let sp3 = SP3::from_async_reader(reader).await.unwrap();
+
+        assert_eq!(sp3.header.num_epochs, 1);
+        assert_eq!(sp3.data.len(), 1);
+
+        drop(writer);
+    }
+}
@@ -0,0 +1,204 @@
+//! SP3 synthesis tests
+#[cfg(test)]
+mod test {
+    use crate::prelude::*;
+    use crate::tests::fixtures::{dummy_entry, t0};
+    use hifitime::Unit;
+    use std::io::{BufReader, BufWriter};
+    use std::str::FromStr;
+
+    #[test]
+    fn build_two_epochs() {
+        let g01 = SV::from_str("G01").unwrap();
+        let g02 = SV::from_str("G02").unwrap();
+
+        let t0 = t0();
+        let dt = 15.0 * Unit::Minute;
+
+        let mut builder = SP3Builder::default()
+            .with_agency("TEST")
+            .with_constellation(Constellation::GPS)
+            .with_timescale(TimeScale::GPST)
+            .with_sampling_period(dt)
+            .with_coord_system("IGS20")
+            .with_comment("synthesized");
+
+        for i in 0..2 {
+            let epoch = t0 + i as f64 * dt;
+
+            for (sv, x_km) in [(g02, 2000.0), (g01, 1000.0)] {
+                builder.push_entry(
+                    epoch,
+                    sv,
+                    SP3Entry::from_position_km((x_km + i as f64, 20000.0, 15000.0))
+                        .with_clock_offset_us(-10.5),
+                );
+            }
+        }
+
+        let sp3 = builder.build().unwrap();
+
+        assert_eq!(sp3.header.agency, "TEST");
+        assert_eq!(sp3.header.constellation, Constellation::GPS);
+        assert_eq!(sp3.header.timescale, TimeScale::GPST);
+        assert_eq!(sp3.header.coord_system, "IGS20");
+        assert_eq!(sp3.header.data_type, DataType::Position);
+        assert_eq!(sp3.header.sampling_period, dt);
+        assert_eq!(sp3.header.num_epochs, 2);
+        assert_eq!(sp3.header.satellites, vec![g01, g02]);
+        assert_eq!(sp3.header.week, 2277);
+        assert_eq!(sp3.header.week_nanos, 0);
+        assert_eq!(sp3.header.mjd, 60183);
+        assert_eq!(sp3.header.mjd_fraction, 0.0);
+        assert_eq!(sp3.comments, vec!["synthesized".to_string()]);
+        assert_eq!(sp3.data.len(), 4);
+
+        // dump and parse back
+        let mut writer = BufWriter::new(Vec::new());
+        sp3.format(&mut writer).unwrap();
+
+        let content = writer.into_inner().unwrap();
+        let mut reader = BufReader::new(content.as_slice());
+
+        let parsed = SP3::from_reader(&mut reader).unwrap();
+
+        assert_eq!(parsed.header.agency, sp3.header.agency);
+        assert_eq!(parsed.header.coord_system, sp3.header.coord_system);
+        assert_eq!(parsed.header.sampling_period, sp3.header.sampling_period);
+        assert_eq!(parsed.header.num_epochs, sp3.header.num_epochs);
+        assert_eq!(parsed.header.satellites, sp3.header.satellites);
+        assert_eq!(parsed.comments, sp3.comments);
+        assert_eq!(parsed.data.len(), sp3.data.len());
+
+        for (entry, parsed) in sp3.data.values().zip(parsed.data.values()) {
+            assert_eq!(entry.position_km, parsed.position_km);
+            assert_eq!(entry.clock_us, parsed.clock_us);
+        }
+    }
+
+    #[test]
+    fn build_late_satellite() {
+        let g01 = SV::from_str("G01").unwrap();
+        let g02 = SV::from_str("G02").unwrap();
+
+        let t0 = Epoch::from_str("2023-08-27T06:00:00 GPST").unwrap();
+        let dt = 15.0 * Unit::Minute;
+
+        let mut builder = SP3Builder::default()
+            .with_timescale(TimeScale::GPST)
+            .with_sampling_period(dt);
+
+        // G01 sorts first but only appears on the second epoch
+        builder.push_entry(t0, g02, dummy_entry());
+        builder.push_entry(t0 + dt, g02, dummy_entry());
+        builder.push_entry(t0 + dt, g01, SP3Entry::from_position_km((4.0, 5.0, 6.0)));
+
+        let sp3 = builder.build().unwrap();
+
+        assert_eq!(sp3.header.release_epoch, t0);
+        assert_eq!(sp3.header.mjd, 60183);
+        assert!((sp3.header.mjd_fraction - 0.25).abs() < 1.0E-9);
+        assert_eq!(sp3.header.num_epochs, 2);
+    }
+
+    #[test]
+    fn append_epoch() {
+        let g01 = SV::from_str("G01").unwrap();
+        let g02 = SV::from_str("G02").unwrap();
+        let g03 = SV::from_str("G03").unwrap();
+
+        let t0 = t0();
+        let dt = 15.0 * Unit::Minute;
+
+        let mut sp3 = SP3::default();
+        sp3.header.timescale = TimeScale::GPST;
+        sp3.header.sampling_period = dt;
+
+        // appending nothing is a no-op
+        sp3.append_epoch(t0, &[]).unwrap();
+        assert_eq!(sp3.header.num_epochs, 0);
+
+        sp3.append_epoch(
+            t0,
+            &[
+                (g02, SP3Entry::from_position_km((2000.0, 20000.0, 15000.0))),
+                (g01, SP3Entry::from_position_km((1000.0, 20000.0, 15000.0))),
+            ],
+        )
+        .unwrap();
+
+        assert_eq!(sp3.header.release_epoch, t0);
+        assert_eq!(sp3.header.week, 2277);
+        assert_eq!(sp3.header.week_nanos, 0);
+        assert_eq!(sp3.header.mjd, 60183);
+        assert_eq!(sp3.header.satellites, vec![g01, g02]);
+
+        sp3.append_epoch(
+            t0 + dt,
+            &[
+                (g01, SP3Entry::from_position_km((1001.0, 20000.0, 15000.0))),
+                (g03, SP3Entry::from_position_km((3001.0, 20000.0, 15000.0))),
+            ],
+        )
+        .unwrap();
+
+        assert_eq!(sp3.header.num_epochs, 2);
+        assert_eq!(sp3.header.satellites, vec![g01, g02, g03]);
+        assert_eq!(sp3.data.len(), 4);
+        assert_eq!(sp3.header.num_epochs, sp3.total_epochs() as u64);
+
+        // header stayed consistent
+        let mut canonical = sp3.clone();
+        canonical.canonicalize_mut();
+
+        assert_eq!(canonical.header.num_epochs, sp3.header.num_epochs);
+        assert_eq!(canonical.header.week, sp3.header.week);
+        assert_eq!(canonical.header.week_nanos, sp3.header.week_nanos);
+        assert_eq!(canonical.header.sampling_period, sp3.header.sampling_period);
+        assert_eq!(canonical.header.satellites, sp3.header.satellites);
+
+        // non increasing epoch
+        let entries = [(g01, SP3Entry::from_position_km((1002.0, 20000.0, 15000.0)))];
+
+        for t in [t0, t0 + dt] {
+            match sp3.append_epoch(t, &entries) {
+                Err(Error::NonIncreasingEpoch(epoch)) => assert_eq!(epoch, t),
+                other => panic!("unexpected result {:?}", other),
+            }
+        }
+
+        // spacing does not match sampling period
+        match sp3.append_epoch(t0 + 3.0 * dt, &entries) {
+            Err(Error::EpochSpacing(spacing, sampling)) => {
+                assert_eq!(spacing, 2.0 * dt);
+                assert_eq!(sampling, dt);
+            },
+            other => panic!("unexpected result {:?}", other),
+        }
+
+        // record is untouched on failure
+        assert_eq!(sp3.data.len(), 4);
+        assert_eq!(sp3.header.num_epochs, 2);
+
+        // undefined sampling period: spacing is not validated
+        sp3.header.sampling_period = Duration::ZERO;
+        sp3.append_epoch(t0 + 3.0 * dt, &entries).unwrap();
+        assert_eq!(sp3.header.num_epochs, 3);
+        assert_eq!(sp3.last_epoch(), Some(t0 + 3.0 * dt));
+    }
+
+    #[test]
+    fn builder_validation() {
+        let g01 = SV::from_str("G01").unwrap();
+        let t0 = t0();
+
+        // no entry
+        let builder = SP3Builder::default().with_timescale(TimeScale::GPST);
+        assert!(matches!(builder.build(), Err(Error::EmptyBuilder)));
+
+        // undefined timescale
+        let mut builder = SP3Builder::default();
+        builder.push_entry(t0, g01, dummy_entry());
+        assert!(matches!(builder.build(), Err(Error::UndefinedTimescale)));
+    }
+}
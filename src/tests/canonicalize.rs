@@ -0,0 +1,230 @@
+//! SP3 canonicalization tests
+#[cfg(test)]
+mod test {
+    use crate::prelude::*;
+    use crate::tests::fixtures::{dummy_entry, sp3_header, t0};
+    use hifitime::Unit;
+    use std::str::FromStr;
+
+    #[test]
+    fn canonicalize_week_on_earliest_epoch() {
+        let g01 = SV::from_str("G01").unwrap();
+        let g03 = SV::from_str("G03").unwrap();
+
+        let t0 = t0();
+        let dt = 15.0 * Unit::Minute;
+
+        let mut sp3 = SP3::default();
+        sp3.header.timescale = TimeScale::GPST;
+
+        for i in 0..4 {
+            sp3.data.insert(
+                SP3Key {
+                    epoch: t0 + i as f64 * dt,
+                    sv: g01,
+                },
+                dummy_entry(),
+            );
+        }
+
+        // G03 sorts last, but starts earlier
+        sp3.data.insert(
+            SP3Key {
+                epoch: t0 - dt,
+                sv: g03,
+            },
+            dummy_entry(),
+        );
+
+        sp3.canonicalize_mut();
+
+        assert_eq!(
+            Epoch::from_time_of_week(sp3.header.week, sp3.header.week_nanos, sp3.header.timescale),
+            t0 - dt,
+        );
+    }
+
+    #[test]
+    fn canonicalize_after_insertion() {
+        let g01 = SV::from_str("G01").unwrap();
+        let g02 = SV::from_str("G02").unwrap();
+        let g03 = SV::from_str("G03").unwrap();
+
+        let t0 = t0();
+
+        let mut sp3 = SP3::default();
+        sp3.header.timescale = TimeScale::GPST;
+        sp3.header.sampling_period = 15.0 * Unit::Minute;
+
+        for i in 0..3 {
+            let epoch = t0 + i as f64 * 15.0 * Unit::Minute;
+
+            for sv in [g02, g01] {
+                sp3.data.insert(SP3Key { epoch, sv }, dummy_entry());
+            }
+        }
+
+        assert!(sp3.canonicalize_mut(), "epoch grid should be steady");
+
+        assert_eq!(sp3.header.satellites, vec![g01, g02]);
+        assert_eq!(sp3.header.num_epochs, 3);
+
+        // manual insertion
+        sp3.data.insert(
+            SP3Key {
+                epoch: t0 + 45.0 * Unit::Minute,
+                sv: g03,
+            },
+            dummy_entry(),
+        );
+
+        assert_eq!(sp3.header.num_epochs, 3, "header should be stale");
+
+        assert!(sp3.canonicalize_mut(), "epoch grid should be steady");
+
+        assert_eq!(sp3.header.satellites, vec![g01, g02, g03]);
+        assert_eq!(sp3.header.num_epochs, 4);
+
+        assert_eq!(
+            Epoch::from_time_of_week(sp3.header.week, sp3.header.week_nanos, sp3.header.timescale),
+            t0,
+        );
+
+        // gap in the epoch grid
+        sp3.data.insert(
+            SP3Key {
+                epoch: t0 + 2.0 * Unit::Hour,
+                sv: g01,
+            },
+            dummy_entry(),
+        );
+
+        assert!(!sp3.canonicalize_mut(), "epoch grid is not steady");
+        assert_eq!(sp3.header.num_epochs, 5);
+    }
+
+    #[test]
+    fn effective_sampling_period() {
+        let g01 = SV::from_str("G01").unwrap();
+        let g02 = SV::from_str("G02").unwrap();
+
+        let t0 = t0();
+        let dt = 15.0 * Unit::Minute;
+
+        let mut sp3 = SP3::default();
+        sp3.header.timescale = TimeScale::GPST;
+        sp3.header.sampling_period = dt;
+
+        assert!(sp3.effective_sampling_period().is_none());
+
+        for i in 0..8 {
+            let epoch = t0 + i as f64 * dt;
+
+            for sv in [g01, g02] {
+                sp3.data.insert(SP3Key { epoch, sv }, dummy_entry());
+            }
+        }
+
+        assert_eq!(sp3.effective_sampling_period(), Some(dt));
+
+        // decimate
+        sp3.data
+            .retain(|k, _| ((k.epoch - t0).to_seconds() / dt.to_seconds()) as u32 % 2 == 0);
+
+        assert_eq!(sp3.effective_sampling_period(), Some(2.0 * dt));
+        assert_eq!(sp3.header.sampling_period, dt, "header should be stale");
+
+        assert!(sp3.canonicalize_mut(), "epoch grid should be steady");
+
+        assert_eq!(sp3.header.sampling_period, 2.0 * dt);
+        assert_eq!(sp3.effective_sampling_period(), Some(2.0 * dt));
+    }
+
+    #[test]
+    fn retain_full_epochs() {
+        let content = sp3_header(Version::D, DataType::Position, &["G01", "E05"], 3)
+            + "/* one partial epoch
+*  2023  8 27  0  0  0.00000000
+PG01  13000.000000 -20000.000000  15000.123456    -10.500000
+PE05  13001.000000 -19000.000000  15000.123456     -9.500000
+*  2023  8 27  0 15  0.00000000
+PG01  13100.000000 -20000.000000  15000.123456    -10.500000
+*  2023  8 27  0 30  0.00000000
+PG01  13200.000000 -20000.000000  15000.123456    -10.500000
+PE05  13201.000000 -19000.000000  15000.123456     -9.500000
+EOF";
+
+        let mut sp3 = SP3::from_str(&content).unwrap();
+
+        assert_eq!(sp3.total_epochs(), 3);
+        assert_eq!(sp3.data.len(), 5);
+
+        sp3.retain_full_epochs_mut();
+
+        let t0 = t0();
+        let t2 = Epoch::from_str("2023-08-27T00:30:00 GPST").unwrap();
+
+        assert_eq!(sp3.epochs_iter().collect::<Vec<_>>(), vec![t0, t2]);
+        assert_eq!(sp3.data.len(), 4);
+
+        assert_eq!(sp3.header.num_epochs, 2);
+        assert_eq!(sp3.header.satellites.len(), 2);
+
+        // already complete
+        let copy = sp3.clone();
+        sp3.retain_full_epochs_mut();
+        assert_eq!(sp3, copy);
+    }
+
+    #[test]
+    fn retain_interpolatable() {
+        let content = sp3_header(Version::D, DataType::Position, &["G01", "G02", "E05"], 5)
+            + "/* E05 short arc, G02 two short arcs
+*  2023  8 27  0  0  0.00000000
+PG01  13000.000000 -20000.000000  15000.123456    -10.500000
+PG02  14000.000000 -20000.000000  15000.123456    -10.500000
+*  2023  8 27  0 15  0.00000000
+PG01  13100.000000 -20000.000000  15000.123456    -10.500000
+PG02  14100.000000 -20000.000000  15000.123456    -10.500000
+PE05  13101.000000 -19000.000000  15000.123456     -9.500000
+*  2023  8 27  0 30  0.00000000
+PG01  13200.000000 -20000.000000  15000.123456    -10.500000
+PE05  13201.000000 -19000.000000  15000.123456     -9.500000
+*  2023  8 27  0 45  0.00000000
+PG01  13300.000000 -20000.000000  15000.123456    -10.500000
+PG02  14300.000000 -20000.000000  15000.123456    -10.500000
+*  2023  8 27  1  0  0.00000000
+PG01  13400.000000 -20000.000000  15000.123456    -10.500000
+PG02  14400.000000 -20000.000000  15000.123456    -10.500000
+EOF";
+
+        let g01 = SV::from_str("G01").unwrap();
+        let g02 = SV::from_str("G02").unwrap();
+        let e05 = SV::from_str("E05").unwrap();
+
+        let sp3 = SP3::from_str(&content).unwrap();
+        assert_eq!(sp3.header.satellites, vec![g01, g02, e05]);
+
+        // all satellites support a linear interpolation
+        let mut linear = sp3.clone();
+        linear.retain_interpolatable_mut(1);
+        assert_eq!(linear.data, sp3.data);
+        assert_eq!(linear.header.satellites, sp3.header.satellites);
+
+        // E05 arc is too short, G02 arcs too
+        let mut cubic = sp3.clone();
+        cubic.retain_interpolatable_mut(2);
+
+        assert_eq!(cubic.header.satellites, vec![g01]);
+        assert_eq!(cubic.satellites_iter().collect::<Vec<_>>(), vec![g01]);
+        assert_eq!(cubic.data.len(), 5);
+        assert_eq!(cubic.header.num_epochs, 5);
+
+        // nothing remains
+        let mut high_order = sp3.clone();
+        high_order.retain_interpolatable_mut(5);
+
+        assert!(high_order.data.is_empty());
+        assert!(high_order.header.satellites.is_empty());
+    }
+}
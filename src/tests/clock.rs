@@ -0,0 +1,313 @@
+//! Satellite clock analysis tests
+#[cfg(test)]
+mod test {
+    use crate::prelude::*;
+    use crate::tests::fixtures::{dummy_entry, t0};
+    use hifitime::Unit;
+    use std::path::PathBuf;
+    use std::str::FromStr;
+
+    #[test]
+    fn inter_system_clock_bias() {
+        let g01 = SV::from_str("G01").unwrap();
+        let g02 = SV::from_str("G02").unwrap();
+        let e05 = SV::from_str("E05").unwrap();
+        let r03 = SV::from_str("R03").unwrap();
+
+        let t0 = t0();
+
+        let mut sp3 = SP3::default();
+
+        for i in 0..2 {
+            let epoch = t0 + i as f64 * 15.0 * Unit::Minute;
+            let offset_us = i as f64;
+
+            for (sv, clock_us) in [(g01, -10.0), (g02, -12.0), (e05, 3.0)] {
+                sp3.data.insert(
+                    SP3Key { epoch, sv },
+                    dummy_entry().with_clock_offset_us(clock_us + offset_us),
+                );
+            }
+        }
+
+        // no clock data: not taken into account
+        sp3.data
+            .insert(SP3Key { epoch: t0, sv: r03 }, dummy_entry());
+
+        let biases = sp3.inter_system_clock_bias();
+        assert_eq!(biases.len(), 1);
+
+        let (pair, bias_s) = biases.iter().next().unwrap();
+
+        let expected_s = if *pair == (Constellation::GPS, Constellation::Galileo) {
+            -14.0E-6
+        } else {
+            assert_eq!(*pair, (Constellation::Galileo, Constellation::GPS));
+            14.0E-6
+        };
+
+        assert!(
+            (bias_s - expected_s).abs() < 1.0E-12,
+            "bad bias: {}",
+            bias_s
+        );
+    }
+
+    #[test]
+    fn mixed_inter_system_clock_bias() {
+        let path = PathBuf::new()
+            .join(env!("CARGO_MANIFEST_DIR"))
+            .join("data/SP3")
+            .join("D")
+            .join("example.txt");
+
+        let sp3 = SP3::from_file(&path).unwrap();
+
+        let biases = sp3.inter_system_clock_bias();
+
+        let gps_gal = biases
+            .get(&(Constellation::GPS, Constellation::Galileo))
+            .or(biases.get(&(Constellation::Galileo, Constellation::GPS)))
+            .expect("missing GPS/Galileo bias");
+
+        assert!(gps_gal.is_finite());
+        assert!(gps_gal.abs() < 1.0E-2, "implausible bias: {} s", gps_gal);
+
+        for (lhs, rhs) in biases.keys() {
+            assert!(lhs < rhs, "pairs should be sorted");
+        }
+    }
+
+    #[test]
+    fn clock_offset_interpolation() {
+        let g01 = SV::from_str("G01").unwrap();
+        let t0 = Epoch::from_str("2020-06-24T00:00:00 GPST").unwrap();
+        let dt = 15.0 * Unit::Minute;
+
+        let mut sp3 = SP3::default();
+
+        // linear clock: 1us offset, 1E-3 us/s drift
+        for i in 0..12 {
+            let epoch = t0 + i as f64 * dt;
+            sp3.data.insert(
+                SP3Key { epoch, sv: g01 },
+                dummy_entry().with_clock_offset_us(1.0 + (epoch - t0).to_seconds() * 1.0E-3),
+            );
+        }
+
+        for order in [1, 3, 5, 7] {
+            // between two samples, in the middle of the dataset
+            let t = t0 + 5.5 * dt;
+            let expected_us = 1.0 + (t - t0).to_seconds() * 1.0E-3;
+
+            let clock_us = sp3
+                .satellite_clock_offset_interpolate(g01, t, order)
+                .unwrap_or_else(|| panic!("order {}: interpolation failed", order));
+
+            assert!(
+                (clock_us - expected_us).abs() < 1.0E-9,
+                "order {}: got {} expecting {}",
+                order,
+                clock_us,
+                expected_us
+            );
+        }
+
+        // too early
+        assert!(sp3
+            .satellite_clock_offset_interpolate(g01, t0 + 0.5 * dt, 7)
+            .is_none());
+
+        // too late
+        assert!(sp3
+            .satellite_clock_offset_interpolate(g01, t0 + 10.5 * dt, 7)
+            .is_none());
+
+        // unknown satellite
+        let g02 = SV::from_str("G02").unwrap();
+        assert!(sp3
+            .satellite_clock_offset_interpolate(g02, t0 + 5.5 * dt, 7)
+            .is_none());
+    }
+
+    #[test]
+    #[should_panic]
+    fn clock_offset_even_interpolation_order() {
+        let g01 = SV::from_str("G01").unwrap();
+        let t0 = Epoch::from_str("2020-06-24T00:00:00 GPST").unwrap();
+        let _ = SP3::default().satellite_clock_offset_interpolate(g01, t0, 2);
+    }
+
+    #[test]
+    #[cfg(feature = "flate2")]
+    fn grg0mgxfin_clock_offset_interpolation() {
+        let path = PathBuf::new()
+            .join(env!("CARGO_MANIFEST_DIR"))
+            .join("data/SP3")
+            .join("C")
+            .join("GRG0MGXFIN_20201760000_01D_15M_ORB.SP3.gz");
+
+        let sp3 = SP3::from_gzip_file(&path).unwrap();
+
+        let g01 = SV::from_str("G01").unwrap();
+        let dt = sp3.header.sampling_period;
+
+        let samples = sp3
+            .data
+            .iter()
+            .filter_map(|(k, v)| {
+                if k.sv == g01 {
+                    Some((k.epoch, v.clock_us?))
+                } else {
+                    None
+                }
+            })
+            .collect::<Vec<_>>();
+
+        assert!(samples.len() > 10, "not enough G01 clock samples");
+
+        let mut tested = 0;
+
+        for pair in samples.windows(2).skip(4).take(samples.len() - 10) {
+            let ((t_0, clock_0_us), (t_1, clock_1_us)) = (pair[0], pair[1]);
+
+            if t_1 - t_0 != dt {
+                continue;
+            }
+
+            // mid-point between two samples
+            let t = t_0 + dt * 0.5;
+
+            let clock_us = sp3
+                .satellite_clock_offset_interpolate(g01, t, 3)
+                .unwrap_or_else(|| panic!("failed to interpolate G01 clock @ {}", t));
+
+            // clocks are smooth over 15' and remain close to both surrounding samples
+            let (min_us, max_us) = (clock_0_us.min(clock_1_us), clock_0_us.max(clock_1_us));
+
+            assert!(
+                clock_us > min_us - 1.0E-3 && clock_us < max_us + 1.0E-3,
+                "G01 clock @ {}: {} not within [{}, {}]",
+                t,
+                clock_us,
+                min_us,
+                max_us
+            );
+
+            tested += 1;
+        }
+
+        assert!(tested > 0, "no epochs tested");
+
+        // exact sample is reproduced
+        let (t_5, clock_5_us) = samples[5];
+
+        let clock_us = sp3.satellite_clock_offset_interpolate(g01, t_5, 3).unwrap();
+
+        assert!((clock_us - clock_5_us).abs() < 1.0E-9);
+    }
+
+    #[test]
+    fn clock_iter() {
+        let g01 = SV::from_str("G01").unwrap();
+        let g02 = SV::from_str("G02").unwrap();
+        let g03 = SV::from_str("G03").unwrap();
+
+        let t0 = t0();
+
+        let mut sp3 = SP3::default();
+
+        for i in 0..3 {
+            let epoch = t0 + i as f64 * 15.0 * Unit::Minute;
+
+            let entry = dummy_entry();
+
+            // offset only
+            sp3.data
+                .insert(SP3Key { epoch, sv: g01 }, entry.with_clock_offset_us(1.0));
+
+            // offset and drift
+            sp3.data.insert(
+                SP3Key { epoch, sv: g02 },
+                entry.with_clock_offset_us(2.0).with_clock_drift_ns(1.0),
+            );
+
+            // no clock data
+            sp3.data.insert(SP3Key { epoch, sv: g03 }, entry);
+        }
+
+        let with_clock = sp3
+            .data
+            .values()
+            .filter(|v| v.clock_us.is_some() || v.clock_drift_ns.is_some())
+            .count();
+
+        assert_eq!(sp3.satellites_clock_iter().count(), with_clock);
+        assert_eq!(with_clock, 6);
+
+        for (_, sv, offset_s, drift_s_s) in sp3.satellites_clock_iter() {
+            if sv == g01 {
+                assert_eq!(offset_s, Some(1.0E-6));
+                assert_eq!(drift_s_s, None);
+            } else {
+                assert_eq!(sv, g02);
+                assert_eq!(offset_s, Some(2.0E-6));
+                assert_eq!(drift_s_s, Some(1.0E-9));
+            }
+        }
+    }
+
+    #[test]
+    fn clock_maps() {
+        let g01 = SV::from_str("G01").unwrap();
+        let g02 = SV::from_str("G02").unwrap();
+
+        let t0 = t0();
+
+        let mut sp3 = SP3::default();
+
+        for i in 0..4 {
+            let epoch = t0 + i as f64 * 15.0 * Unit::Minute;
+
+            let entry = dummy_entry();
+
+            sp3.data.insert(
+                SP3Key { epoch, sv: g01 },
+                entry.with_clock_offset_us(i as f64),
+            );
+
+            sp3.data.insert(
+                SP3Key { epoch, sv: g02 },
+                entry
+                    .with_clock_offset_us(2.0 * i as f64)
+                    .with_clock_drift_ns(1.0),
+            );
+        }
+
+        let offsets = sp3.clock_offsets_map();
+        let drifts = sp3.clock_drifts_map();
+
+        assert_eq!(
+            offsets.len(),
+            sp3.satellites_clock_offset_sec_iter().count()
+        );
+        assert_eq!(offsets.len(), 8);
+
+        for (t, sv, offset_s) in sp3.satellites_clock_offset_sec_iter() {
+            assert_eq!(offsets.get(&(sv, t)), Some(&offset_s));
+        }
+
+        assert_eq!(
+            drifts.len(),
+            sp3.satellites_clock_drift_sec_sec_iter().count()
+        );
+        assert_eq!(drifts.len(), 4);
+
+        for (t, sv, drift_s_s) in sp3.satellites_clock_drift_sec_sec_iter() {
+            assert_eq!(drifts.get(&(sv, t)), Some(&drift_s_s));
+        }
+
+        assert!(drifts.keys().all(|(sv, _)| *sv == g02));
+        assert_eq!(offsets.get(&(g01, t0 + 15.0 * Unit::Minute)), Some(&1.0E-6));
+    }
+}
@@ -0,0 +1,186 @@
+//! SP3 concatenation tests
+#[cfg(test)]
+mod test {
+    use crate::prelude::*;
+    use crate::tests::fixtures::t0;
+    use hifitime::Unit;
+    use std::path::PathBuf;
+    use std::str::FromStr;
+
+    // One file of this many epochs, starting at t0
+    fn synthetic(t0: Epoch, num_epochs: usize, satellites: &[SV]) -> SP3 {
+        let dt = 15.0 * Unit::Minute;
+
+        let mut builder = SP3Builder::default()
+            .with_agency("TEST")
+            .with_coord_system("IGS20")
+            .with_constellation(Constellation::GPS)
+            .with_timescale(TimeScale::GPST)
+            .with_sampling_period(dt);
+
+        for i in 0..num_epochs {
+            for (j, sv) in satellites.iter().enumerate() {
+                let x_km = 10_000.0 * (j + 1) as f64 + i as f64;
+
+                builder.push_entry(
+                    t0 + i as f64 * dt,
+                    *sv,
+                    SP3Entry::from_position_km((x_km, 20000.0, 15000.0)),
+                );
+            }
+        }
+
+        builder.build().unwrap()
+    }
+
+    #[test]
+    fn concatenation() {
+        let g01 = SV::from_str("G01").unwrap();
+        let g02 = SV::from_str("G02").unwrap();
+        let g03 = SV::from_str("G03").unwrap();
+
+        let t0 = t0();
+        let dt = 15.0 * Unit::Minute;
+
+        let first = synthetic(t0, 4, &[g01, g02]);
+        let second = synthetic(t0 + 4.0 * dt, 4, &[g01, g03]);
+        let third = synthetic(t0 + 8.0 * dt, 2, &[g02]);
+
+        let concatenated = SP3::concatenate(&[first.clone(), second.clone(), third]).unwrap();
+
+        assert_eq!(concatenated.first_epoch(), Some(t0));
+        assert_eq!(concatenated.epochs_iter().max(), Some(t0 + 9.0 * dt));
+        assert_eq!(concatenated.header.num_epochs, 10);
+        assert_eq!(concatenated.header.satellites, vec![g01, g02, g03]);
+        assert_eq!(concatenated.header.week, first.header.week);
+        assert_eq!(concatenated.header.mjd, first.header.mjd);
+        assert_eq!(concatenated.data.len(), 8 + 8 + 2);
+        assert!(concatenated.has_steady_sampling());
+
+        // satellites remain sorted
+        let sorted = SP3::concatenate(&[
+            synthetic(t0, 4, &[g03]),
+            synthetic(t0 + 4.0 * dt, 4, &[g02, g01]),
+        ])
+        .unwrap();
+
+        assert_eq!(sorted.header.satellites, vec![g01, g02, g03]);
+        assert_eq!(sorted.header.num_satellites, 3);
+
+        // single file
+        let single = SP3::concatenate(std::slice::from_ref(&first)).unwrap();
+        assert_eq!(single, first);
+
+        // no file
+        assert!(matches!(
+            SP3::concatenate(&[]),
+            Err(Error::EmptyConcatenation)
+        ));
+
+        // overlapping
+        let overlapping = synthetic(t0 + 3.0 * dt, 4, &[g01]);
+
+        match SP3::concatenate(&[first.clone(), overlapping]) {
+            Err(Error::ConcatenationOverlap(last, next)) => {
+                assert_eq!(last, t0 + 3.0 * dt);
+                assert_eq!(next, t0 + 3.0 * dt);
+            },
+            other => panic!("unexpected result {:?}", other),
+        }
+
+        // reversed order
+        assert!(matches!(
+            SP3::concatenate(&[second.clone(), first.clone()]),
+            Err(Error::ConcatenationOverlap(_, _))
+        ));
+
+        // gap
+        let gapped = synthetic(t0 + 5.0 * dt, 4, &[g01]);
+
+        match SP3::concatenate(&[first.clone(), gapped]) {
+            Err(Error::ConcatenationGap(last, next)) => {
+                assert_eq!(last, t0 + 3.0 * dt);
+                assert_eq!(next, t0 + 5.0 * dt);
+            },
+            other => panic!("unexpected result {:?}", other),
+        }
+
+        // production context mismatch
+        let mut other_agency = second.clone();
+        other_agency.header.agency = "OTHER".to_string();
+
+        match SP3::concatenate(&[first.clone(), other_agency]) {
+            Err(Error::ConcatenationMismatch(field, lhs, rhs)) => {
+                assert_eq!(field, "agency");
+                assert_eq!(lhs, "TEST");
+                assert_eq!(rhs, "OTHER");
+            },
+            other => panic!("unexpected result {:?}", other),
+        }
+
+        let mut other_frame = second.clone();
+        other_frame.header.coord_system = "ITRF2".to_string();
+
+        assert!(matches!(
+            SP3::concatenate(&[first.clone(), other_frame]),
+            Err(Error::ConcatenationMismatch("coordinates system", _, _))
+        ));
+
+        let mut other_timescale = second.clone();
+        other_timescale.header.timescale = TimeScale::GST;
+
+        assert!(matches!(
+            SP3::concatenate(&[first, other_timescale]),
+            Err(Error::ConcatenationMismatch("timescale", _, _))
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "flate2")]
+    fn grg0mgxfin_concatenation() {
+        let dir = PathBuf::new()
+            .join(env!("CARGO_MANIFEST_DIR"))
+            .join("data/SP3")
+            .join("C");
+
+        let day176 =
+            SP3::from_gzip_file(dir.join("GRG0MGXFIN_20201760000_01D_15M_ORB.SP3.gz")).unwrap();
+
+        let day177 =
+            SP3::from_gzip_file(dir.join("GRG0MGXFIN_20201770000_01D_15M_ORB.SP3.gz")).unwrap();
+
+        let concatenated = SP3::concatenate(&[day176.clone(), day177.clone()]).unwrap();
+
+        assert_eq!(concatenated.first_epoch(), day176.first_epoch());
+        assert_eq!(concatenated.epochs_iter().max(), day177.epochs_iter().max());
+
+        assert_eq!(
+            concatenated.header.num_epochs,
+            day176.header.num_epochs + day177.header.num_epochs
+        );
+
+        assert_eq!(
+            concatenated.data.len(),
+            day176.data.len() + day177.data.len()
+        );
+
+        assert_eq!(concatenated.header.week, day176.header.week);
+        assert_eq!(concatenated.header.mjd, day176.header.mjd);
+        assert!(concatenated.has_steady_sampling());
+
+        for sv in day176
+            .header
+            .satellites
+            .iter()
+            .chain(day177.header.satellites.iter())
+        {
+            assert!(concatenated.header.satellites.contains(sv));
+        }
+
+        // both days in reversed order
+        assert!(matches!(
+            SP3::concatenate(&[day177, day176]),
+            Err(Error::ConcatenationOverlap(_, _))
+        ));
+    }
+}
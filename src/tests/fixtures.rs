@@ -0,0 +1,72 @@
+//! Synthetic SP3 fixtures, shared by the test suite
+use crate::prelude::*;
+use std::str::FromStr;
+
+/// First [Epoch] of the synthetic files: 2023-08-27 midnight
+/// (GPS week 2277, MJD 60183).
+pub fn t0() -> Epoch {
+    Epoch::from_str("2023-08-27T00:00:00 GPST").unwrap()
+}
+
+/// Placeholder state, for tests where coordinates do not matter.
+pub fn dummy_entry() -> SP3Entry {
+    SP3Entry::from_position_km((1.0, 2.0, 3.0))
+}
+
+/// [SP3Builder] preset of the synthetic files (IGS, IGS20, GPST).
+pub fn sp3_builder(constellation: Constellation) -> SP3Builder {
+    SP3Builder::default()
+        .with_agency("IGS")
+        .with_coord_system("IGS20")
+        .with_constellation(constellation)
+        .with_timescale(TimeScale::GPST)
+}
+
+/// SP3 header of the synthetic files: these satellites sampled every 15',
+/// starting at [t0]. The epoch blocks and "EOF" are to be appended.
+pub fn sp3_header(
+    version: Version,
+    data_type: DataType,
+    satellites: &[&str],
+    num_epochs: usize,
+) -> String {
+    let constellation = match satellites.first() {
+        Some(first) if satellites.iter().all(|sv| sv[..1] == first[..1]) => &first[..1],
+        _ => "M",
+    };
+
+    let mut content = format!(
+        "#{}{}2023  8 27  0  0  0.00000000 {:>7} ORBIT IGS20 FIT  IGS
+## 2277      0.00000000   900.00000000 60183 0.0000000000000
++{:5}   {}",
+        version,
+        data_type,
+        num_epochs,
+        satellites.len(),
+        satellites.concat(),
+    );
+
+    for _ in satellites.len()..17 {
+        content.push_str("  0");
+    }
+
+    content.push_str(&format!(
+        "
+++         0  0  0  0  0  0  0  0  0  0  0  0  0  0  0  0  0
+%c {}  cc GPS ccc cccc cccc cccc cccc ccccc ccccc ccccc ccccc
+%c cc cc ccc ccc cccc cccc cccc cccc ccccc ccccc ccccc ccccc
+%f  1.2500000  1.025000000  0.00000000000  0.000000000000000
+%f  0.0000000  0.000000000  0.00000000000  0.000000000000000
+%i    0    0    0    0      0      0      0      0         0
+%i    0    0    0    0      0      0      0      0         0
+",
+        constellation
+    ));
+
+    content
+}
+
+/// SP3-d header of the synthetic files, describing G01 only (see [sp3_header]).
+pub fn sp3_d_header(num_epochs: usize) -> String {
+    sp3_header(Version::D, DataType::Position, &["G01"], num_epochs)
+}
@@ -2,6 +2,7 @@
 #[cfg(test)]
 mod test {
     use crate::prelude::*;
+    use crate::tests::fixtures::t0;
     use hifitime::Unit;
     use std::path::PathBuf;
     use std::str::FromStr;
@@ -162,4 +163,588 @@ mod test {
                 .is_some());
         }
     }
+
+    #[test]
+    #[cfg(feature = "flate2")]
+    fn interpolate_all_positions() {
+        let path = PathBuf::new()
+            .join(env!("CARGO_MANIFEST_DIR"))
+            .join("data/SP3")
+            .join("C")
+            .join("EMR0OPSULT_20232391800_02D_15M_ORB.SP3.gz");
+
+        let sp3 = SP3::from_gzip_file(&path).unwrap();
+
+        let num_satellites = sp3.satellites_iter().count();
+
+        // mid-window time
+        let t = Epoch::from_str("2023-08-28T12:07:30 GPST").unwrap();
+
+        let snapshot = sp3.interpolate_all_positions(t, 9);
+
+        assert!(
+            snapshot.len() * 10 >= num_satellites * 9,
+            "only {}/{} satellites interpolated",
+            snapshot.len(),
+            num_satellites
+        );
+
+        for (sv, position_km) in snapshot.iter() {
+            let single = sp3
+                .satellite_position_lagrangian_9_interpolation(*sv, t)
+                .unwrap();
+
+            assert_eq!(*position_km, single, "{} - snapshot differs", sv);
+        }
+
+        // too early: nothing to center on
+        let t0 = Epoch::from_str("2023-08-27T18:00:00 GPST").unwrap();
+        assert!(sp3.interpolate_all_positions(t0, 9).is_empty());
+    }
+
+    #[test]
+    fn interpolation_context() {
+        let g01 = SV::from_str("G01").unwrap();
+        let e05 = SV::from_str("E05").unwrap();
+        let g10 = SV::from_str("G10").unwrap();
+
+        let t0 = t0();
+        let dt = 15.0 * Unit::Minute;
+
+        let mut sp3 = SP3::default();
+        sp3.header.sampling_period = dt;
+
+        for i in 0..20 {
+            let t = t0 + i as f64 * dt;
+            let x = i as f64;
+
+            for (sv, offset_km) in [(g01, 0.0), (e05, 1000.0), (g10, 2000.0)] {
+                // G10: short arc
+                if sv == g10 && i > 2 {
+                    continue;
+                }
+
+                let entry = SP3Entry::from_position_km((
+                    20_000.0 + offset_km + 10.0 * x,
+                    -15_000.0 + x * x,
+                    5_000.0 - 0.1 * x * x * x,
+                ));
+
+                sp3.data.insert(SP3Key { epoch: t, sv }, entry);
+            }
+        }
+
+        sp3.canonicalize_mut();
+
+        for order in [3, 5, 7] {
+            for i in 4..15 {
+                // on grid and off grid
+                for t in [t0 + i as f64 * dt, t0 + i as f64 * dt + 7.0 * Unit::Minute] {
+                    let context = sp3.interpolation_context(t, order);
+
+                    assert_eq!(context.epoch(), t);
+                    assert_eq!(context.order(), order);
+
+                    for sv in [g01, e05] {
+                        let expected = sp3
+                            .satellite_position_lagrangian_interpolation(sv, t, order)
+                            .unwrap();
+
+                        assert_eq!(
+                            context.position(sv),
+                            Some(expected),
+                            "{}({}) order={}",
+                            t,
+                            sv,
+                            order
+                        );
+                    }
+
+                    // G10 arc is too short
+                    assert!(context.position(g10).is_none());
+                    assert_eq!(
+                        context.satellites_iter().collect::<Vec<_>>(),
+                        vec![g01, e05]
+                    );
+                }
+            }
+        }
+
+        // too early, too late
+        for t in [t0, t0 + 19.0 * dt, t0 - dt, t0 + 30.0 * dt] {
+            let context = sp3.interpolation_context(t, 7);
+            assert_eq!(context.satellites_iter().count(), 0, "{}", t);
+            assert!(context.position(g01).is_none());
+        }
+
+        // unknown satellite
+        let context = sp3.interpolation_context(t0 + 10.0 * dt, 7);
+        assert!(context.position(SV::from_str("R01").unwrap()).is_none());
+    }
+
+    #[test]
+    fn interpolation_coverage() {
+        let g01 = SV::from_str("G01").unwrap();
+        let g02 = SV::from_str("G02").unwrap();
+
+        let t0 = Epoch::from_str("2020-06-24T00:00:00 GPST").unwrap();
+        let dt = 15.0 * Unit::Minute;
+
+        let mut sp3 = SP3::default();
+        sp3.header.sampling_period = dt;
+
+        for i in 0..10 {
+            let x_km = 20_000.0 + 100.0 * i as f64;
+            let entry = SP3Entry::from_position_km((x_km, -x_km, 1000.0));
+
+            sp3.data.insert(
+                SP3Key {
+                    epoch: t0 + i as f64 * dt,
+                    sv: g01,
+                },
+                entry,
+            );
+
+            // G02: short arc
+            if i < 4 {
+                sp3.data.insert(
+                    SP3Key {
+                        epoch: t0 + i as f64 * dt,
+                        sv: g02,
+                    },
+                    entry,
+                );
+            }
+        }
+
+        sp3.canonicalize_mut();
+
+        let coverage = sp3.interpolation_coverage(1);
+        assert_eq!(coverage.get(&g01), Some(&(t0 + dt, t0 + 8.0 * dt)));
+        assert_eq!(coverage.get(&g02), Some(&(t0 + dt, t0 + 2.0 * dt)));
+
+        let coverage = sp3.interpolation_coverage(3);
+        assert_eq!(coverage.get(&g01), Some(&(t0 + 2.0 * dt, t0 + 7.0 * dt)));
+        assert!(!coverage.contains_key(&g02));
+
+        let coverage = sp3.interpolation_coverage(7);
+        assert_eq!(coverage.get(&g01), Some(&(t0 + 4.0 * dt, t0 + 5.0 * dt)));
+
+        assert!(sp3.interpolation_coverage(9).is_empty());
+
+        // window shrinks as order increases
+        let mut past = Option::<(Epoch, Epoch)>::None;
+
+        for order in [3, 5, 7] {
+            let (start, end) = sp3.interpolation_coverage(order)[&g01];
+
+            if let Some((past_start, past_end)) = past {
+                assert!(start > past_start);
+                assert!(end < past_end);
+            }
+
+            // interpolation is feasible within the window
+            for t in [start, start + 0.5 * dt, end] {
+                assert!(
+                    sp3.satellite_position_lagrangian_interpolation(g01, t, order)
+                        .is_some(),
+                    "order {} interpolation should be feasible at {}",
+                    order,
+                    t
+                );
+            }
+
+            past = Some((start, end));
+        }
+    }
+
+    #[test]
+    fn interpolation_feasible_range() {
+        let g01 = SV::from_str("G01").unwrap();
+        let g02 = SV::from_str("G02").unwrap();
+
+        let t0 = Epoch::from_str("2020-06-24T00:00:00 GPST").unwrap();
+        let dt = 15.0 * Unit::Minute;
+
+        let mut sp3 = SP3::default();
+        sp3.header.sampling_period = dt;
+
+        for i in 0..12 {
+            let x_km = 20_000.0 + 100.0 * i as f64;
+
+            sp3.data.insert(
+                SP3Key {
+                    epoch: t0 + i as f64 * dt,
+                    sv: g01,
+                },
+                SP3Entry::from_position_km((x_km, -x_km, 1000.0)),
+            );
+        }
+
+        sp3.canonicalize_mut();
+
+        assert_eq!(
+            sp3.interpolation_feasible_range(g01, 1),
+            Some((t0 + dt, t0 + 10.0 * dt))
+        );
+
+        assert_eq!(
+            sp3.interpolation_feasible_range(g01, 9),
+            Some((t0 + 5.0 * dt, t0 + 6.0 * dt))
+        );
+
+        assert!(sp3.interpolation_feasible_range(g01, 11).is_none());
+        assert!(sp3.interpolation_feasible_range(g02, 1).is_none());
+
+        // range shrinks as order grows
+        let mut past = Option::<(Epoch, Epoch)>::None;
+
+        for order in [1, 3, 5, 7, 9] {
+            let (start, end) = sp3.interpolation_feasible_range(g01, order).unwrap();
+
+            if let Some((past_start, past_end)) = past {
+                assert!(start > past_start, "order {}", order);
+                assert!(end < past_end, "order {}", order);
+            }
+
+            // interpolation is feasible at both edges
+            for t in [start, end] {
+                assert!(
+                    sp3.satellite_position_lagrangian_interpolation(g01, t, order)
+                        .is_some(),
+                    "order {} interpolation should be feasible at {}",
+                    order,
+                    t
+                );
+            }
+
+            assert_eq!(sp3.interpolation_coverage(order)[&g01], (start, end));
+
+            past = Some((start, end));
+        }
+    }
+
+    #[test]
+    fn interpolation_with_error() {
+        let g01 = SV::from_str("G01").unwrap();
+        let t0 = Epoch::from_str("2020-06-24T00:00:00 GPST").unwrap();
+
+        // circular orbit
+        let (radius_km, period_s) = (26_560.0_f64, 43_082.0_f64);
+        let omega = 2.0 * std::f64::consts::PI / period_s;
+
+        let position_km = |t_s: f64| {
+            (
+                radius_km * (omega * t_s).cos(),
+                radius_km * (omega * t_s).sin() * 0.5,
+                radius_km * (omega * t_s).sin() * 0.8,
+            )
+        };
+
+        let dt_s = 900.0;
+        let mut sp3 = SP3::default();
+
+        for i in 0..24 {
+            sp3.data.insert(
+                SP3Key {
+                    epoch: t0 + i as f64 * dt_s * Unit::Second,
+                    sv: g01,
+                },
+                SP3Entry::from_position_km(position_km(i as f64 * dt_s)),
+            );
+        }
+
+        sp3.canonicalize_mut();
+
+        let t_s = 11.5 * dt_s;
+        let t = t0 + t_s * Unit::Second;
+        let truth_km = position_km(t_s);
+
+        // order is too low
+        assert!(sp3
+            .satellite_position_lagrangian_interpolation_with_error(g01, t, 1)
+            .is_none());
+
+        let mut past_error_km = f64::MAX;
+
+        for order in [3, 5, 7, 9] {
+            let (position_km, error_km) = sp3
+                .satellite_position_lagrangian_interpolation_with_error(g01, t, order)
+                .unwrap();
+
+            assert_eq!(
+                Some(position_km),
+                sp3.satellite_position_lagrangian_interpolation(g01, t, order)
+            );
+
+            let error_km = error_km.0.max(error_km.1).max(error_km.2);
+            assert!(
+                error_km < past_error_km,
+                "order {} error did not shrink",
+                order
+            );
+
+            // estimate is conservative with respect to actual error
+            let actual_km = (position_km.0 - truth_km.0)
+                .abs()
+                .max((position_km.1 - truth_km.1).abs())
+                .max((position_km.2 - truth_km.2).abs());
+
+            assert!(
+                actual_km <= error_km,
+                "order {}: {} > {}",
+                order,
+                actual_km,
+                error_km
+            );
+
+            past_error_km = error_km;
+        }
+    }
+
+    #[test]
+    fn first_order_interpolation() {
+        let g01 = SV::from_str("G01").unwrap();
+        let t0 = Epoch::from_str("2020-06-24T00:00:00 GPST").unwrap();
+        let dt = 15.0 * Unit::Minute;
+
+        // quadratic arc: each interval has its own slope
+        let position_km = |i: f64| (1000.0 * i * i, -500.0 * i * i, 20_000.0);
+
+        let mut sp3 = SP3::default();
+
+        for i in 0..8 {
+            sp3.data.insert(
+                SP3Key {
+                    epoch: t0 + i as f64 * dt,
+                    sv: g01,
+                },
+                SP3Entry::from_position_km(position_km(i as f64)),
+            );
+        }
+
+        sp3.canonicalize_mut();
+
+        // 1st order window is made of the two neighbouring states,
+        // not the last two states of the arc
+        for i in 1..8 {
+            let t = t0 + (i as f64 - 0.5) * dt;
+
+            let (before, after) = (position_km(i as f64 - 1.0), position_km(i as f64));
+
+            let interpolated = sp3
+                .satellite_position_lagrangian_interpolation(g01, t, 1)
+                .unwrap_or_else(|| panic!("1st order interpolation failed @ {}", t));
+
+            assert!((interpolated.0 - (before.0 + after.0) / 2.0).abs() < 1.0E-6);
+            assert!((interpolated.1 - (before.1 + after.1) / 2.0).abs() < 1.0E-6);
+            assert!((interpolated.2 - (before.2 + after.2) / 2.0).abs() < 1.0E-6);
+
+            assert_eq!(
+                sp3.interpolation_context(t, 1).position(g01),
+                Some(interpolated)
+            );
+        }
+
+        // too early
+        assert!(sp3
+            .satellite_position_lagrangian_interpolation(g01, t0 - 0.5 * dt, 1)
+            .is_none());
+    }
+
+    #[test]
+    fn hermite_interpolation() {
+        let g01 = SV::from_str("G01").unwrap();
+        let t0 = Epoch::from_str("2020-06-24T00:00:00 GPST").unwrap();
+
+        // circular orbit
+        let (radius_km, period_s) = (26_560.0_f64, 43_082.0_f64);
+        let omega = 2.0 * std::f64::consts::PI / period_s;
+
+        let state = |t_s: f64| {
+            let position_km = (
+                radius_km * (omega * t_s).cos(),
+                radius_km * (omega * t_s).sin(),
+                0.0,
+            );
+
+            let velocity_km_s = (
+                -radius_km * omega * (omega * t_s).sin(),
+                radius_km * omega * (omega * t_s).cos(),
+                0.0,
+            );
+
+            (position_km, velocity_km_s)
+        };
+
+        let dt_s = 1800.0;
+        let mut sp3 = SP3::default();
+
+        for i in 0..12 {
+            let (position_km, velocity_km_s) = state(i as f64 * dt_s);
+
+            sp3.data.insert(
+                SP3Key {
+                    epoch: t0 + i as f64 * dt_s * Unit::Second,
+                    sv: g01,
+                },
+                SP3Entry::from_position_velocity_km_km_s(position_km, velocity_km_s),
+            );
+        }
+
+        let error_km = |(x_km, y_km, z_km): (f64, f64, f64), t_s: f64| {
+            let ((x, y, z), _) = state(t_s);
+            ((x_km - x).powi(2) + (y_km - y).powi(2) + (z_km - z).powi(2)).sqrt()
+        };
+
+        // between two samples, in the middle of the dataset
+        let t_s = 5.5 * dt_s;
+        let t = t0 + t_s * Unit::Second;
+
+        for order in [3, 5, 7] {
+            let hermite_km = sp3
+                .satellite_position_hermite_interpolation(g01, t, order)
+                .unwrap_or_else(|| panic!("order {}: hermite interpolation failed", order));
+
+            let lagrange_km = sp3
+                .satellite_position_lagrangian_interpolation(g01, t, order)
+                .unwrap_or_else(|| panic!("order {}: lagrange interpolation failed", order));
+
+            let (hermite_err_km, lagrange_err_km) =
+                (error_km(hermite_km, t_s), error_km(lagrange_km, t_s));
+
+            assert!(
+                hermite_err_km < lagrange_err_km,
+                "order {}: hermite error {} km, lagrange error {} km",
+                order,
+                hermite_err_km,
+                lagrange_err_km
+            );
+        }
+
+        let hermite_km = sp3
+            .satellite_position_hermite_interpolation(g01, t, 3)
+            .unwrap();
+
+        assert!(error_km(hermite_km, t_s) < 1.0E-3);
+
+        // nodes are reproduced
+        let hermite_km = sp3
+            .satellite_position_hermite_interpolation(g01, t0 + 5.0 * dt_s * Unit::Second, 3)
+            .unwrap();
+
+        assert!(error_km(hermite_km, 5.0 * dt_s) < 1.0E-9);
+
+        // not centered
+        assert!(sp3
+            .satellite_position_hermite_interpolation(g01, t0 + 0.5 * dt_s * Unit::Second, 3)
+            .is_none());
+
+        // velocity missing in the time frame
+        let key = SP3Key {
+            epoch: t0 + 6.0 * dt_s * Unit::Second,
+            sv: g01,
+        };
+
+        sp3.data.get_mut(&key).unwrap().velocity_km_s = None;
+
+        assert!(sp3
+            .satellite_position_hermite_interpolation(g01, t, 3)
+            .is_none());
+    }
+
+    #[test]
+    #[should_panic]
+    fn hermite_even_interpolation_order() {
+        let g01 = SV::from_str("G01").unwrap();
+        let t0 = Epoch::from_str("2020-06-24T00:00:00 GPST").unwrap();
+        let _ = SP3::default().satellite_position_hermite_interpolation(g01, t0, 2);
+    }
+
+    #[test]
+    #[cfg(feature = "flate2")]
+    fn grg0mgxfin_hermite_interpolation() {
+        let path = PathBuf::new()
+            .join(env!("CARGO_MANIFEST_DIR"))
+            .join("data/SP3")
+            .join("C")
+            .join("GRG0MGXFIN_20201760000_01D_15M_ORB.SP3.gz");
+
+        let sp3 = SP3::from_gzip_file(&path).unwrap();
+
+        let g01 = SV::from_str("G01").unwrap();
+        let epochs = sp3.epochs_iter().collect::<Vec<_>>();
+
+        // position file: nothing to interpolate
+        assert!(sp3
+            .satellite_position_hermite_interpolation(g01, epochs[48], 3)
+            .is_none());
+
+        // first order differences: first epoch remains undefined
+        let resolved = sp3.resolve_velocities();
+
+        assert!(resolved
+            .satellite_position_hermite_interpolation(g01, epochs[2], 3)
+            .is_none());
+
+        assert!(resolved
+            .satellite_position_hermite_interpolation(g01, epochs[48], 3)
+            .is_some());
+
+        // use odd samples as reference: interpolate the decimated (30') dataset,
+        // with accurate velocity vectors
+        let mut decimated = sp3.clone();
+        decimated.resolve_dynamics_interpolated_mut(9);
+
+        decimated
+            .data
+            .retain(|k, _| epochs.iter().position(|t| *t == k.epoch).unwrap() % 2 == 0);
+
+        let (mut hermite_max_err_km, mut lagrange_max_err_km) = (0.0_f64, 0.0_f64);
+        let mut tested = 0;
+
+        for (k, v) in sp3.data.iter() {
+            if k.sv != g01 || epochs.iter().position(|t| *t == k.epoch).unwrap() % 2 == 0 {
+                continue;
+            }
+
+            let hermite_km = decimated.satellite_position_hermite_interpolation(g01, k.epoch, 3);
+
+            let lagrange_km =
+                decimated.satellite_position_lagrangian_interpolation(g01, k.epoch, 3);
+
+            if let (Some(hermite_km), Some(lagrange_km)) = (hermite_km, lagrange_km) {
+                let (x_km, y_km, z_km) = v.position_km;
+
+                let hermite_err_km = ((hermite_km.0 - x_km).powi(2)
+                    + (hermite_km.1 - y_km).powi(2)
+                    + (hermite_km.2 - z_km).powi(2))
+                .sqrt();
+
+                let lagrange_err_km = ((lagrange_km.0 - x_km).powi(2)
+                    + (lagrange_km.1 - y_km).powi(2)
+                    + (lagrange_km.2 - z_km).powi(2))
+                .sqrt();
+
+                hermite_max_err_km = hermite_max_err_km.max(hermite_err_km);
+                lagrange_max_err_km = lagrange_max_err_km.max(lagrange_err_km);
+                tested += 1;
+            }
+        }
+
+        assert!(tested > 0, "no epochs tested");
+
+        assert!(
+            hermite_max_err_km < lagrange_max_err_km,
+            "hermite error {} km, lagrange error {} km",
+            hermite_max_err_km,
+            lagrange_max_err_km
+        );
+
+        assert!(
+            hermite_max_err_km < 1.0E-2,
+            "hermite error {} km",
+            hermite_max_err_km
+        );
+    }
 }
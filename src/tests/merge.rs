@@ -3,7 +3,8 @@
 #[cfg(test)]
 mod test {
     use crate::prelude::*;
-    use qc_traits::Merge;
+    use crate::tests::fixtures::sp3_d_header;
+    use qc_traits::{Merge, MergeError};
     use std::path::PathBuf;
     use std::str::FromStr;
 
@@ -81,4 +82,165 @@ mod test {
         //     Some(Epoch::from_str("2023-08-27T23:45:00 GPST").unwrap())
         // );
     }
+
+    #[test]
+    #[cfg(feature = "qc")]
+    #[cfg(feature = "flate2")]
+    fn grg0mgxfin_merged_header() {
+        let test_pool = PathBuf::new()
+            .join(env!("CARGO_MANIFEST_DIR"))
+            .join("data/SP3")
+            .join("C");
+
+        let file_a =
+            SP3::from_gzip_file(test_pool.join("GRG0MGXFIN_20201760000_01D_15M_ORB.SP3.gz"))
+                .unwrap();
+
+        let file_b =
+            SP3::from_gzip_file(test_pool.join("GRG0MGXFIN_20201770000_01D_15M_ORB.SP3.gz"))
+                .unwrap();
+
+        let merged = file_a.merge(&file_b).unwrap();
+
+        let unique_epochs = file_a
+            .epochs_iter()
+            .chain(file_b.epochs_iter())
+            .collect::<std::collections::BTreeSet<_>>();
+
+        assert_eq!(merged.header.num_epochs, unique_epochs.len() as u64);
+        assert_eq!(merged.header.num_epochs, merged.total_epochs() as u64);
+
+        let mut satellites = merged.header.satellites.clone();
+        satellites.sort();
+        assert_eq!(merged.header.satellites, satellites);
+
+        for sv in merged.satellites_iter() {
+            assert!(
+                file_a.header.satellites.contains(&sv) || file_b.header.satellites.contains(&sv)
+            );
+        }
+
+        // merging a file with itself does not modify the counters
+        let itself = file_a.merge(&file_a).unwrap();
+        assert_eq!(itself.header.num_epochs, file_a.header.num_epochs);
+    }
+
+    #[test]
+    #[cfg(feature = "qc")]
+    #[cfg(feature = "flate2")]
+    fn merge_warnings() {
+        let test_pool = PathBuf::new()
+            .join(env!("CARGO_MANIFEST_DIR"))
+            .join("data/SP3")
+            .join("C");
+
+        let path_a = test_pool
+            .clone()
+            .join("ESA0OPSRAP_20232390000_01D_15M_ORB.SP3.gz");
+
+        let mut file_a = SP3::from_gzip_file(&path_a).unwrap();
+        assert_eq!(file_a.header.orbit_type, OrbitType::BHN);
+
+        let path_b = test_pool
+            .clone()
+            .join("ESA0OPSULT_20232320600_02D_15M_ORB.SP3.gz");
+
+        let mut file_b = SP3::from_gzip_file(&path_b).unwrap();
+        file_b.header.orbit_type = OrbitType::FIT;
+
+        let warnings = file_a.merge_mut_with_warnings(&file_b).unwrap();
+
+        assert_eq!(warnings.len(), 1);
+
+        assert!(warnings.contains(&MergeWarning::OrbitTypeMismatch(
+            OrbitType::BHN,
+            OrbitType::FIT
+        )));
+
+        // merge was not prevented
+        assert_eq!(file_a.total_epochs(), 192 + 96);
+    }
+
+    #[test]
+    #[cfg(feature = "qc")]
+    fn merge_conflict_policy() {
+        let content_a = sp3_d_header(2)
+            + "*  2023  8 27  0  0  0.00000000
+PG01  13000.000000 -20000.000000  15000.000000    -10.000000
+*  2023  8 27  0 15  0.00000000
+PG01  13100.000000 -20000.000000  15000.000000    -10.000000
+EOF";
+
+        // overlaps on 2nd epoch, with different position
+        let content_b = sp3_d_header(2)
+            + "*  2023  8 27  0 15  0.00000000
+PG01  13100.002000 -20000.000000  15000.000000    -12.000000
+*  2023  8 27  0 30  0.00000000
+PG01  13200.000000 -20000.000000  15000.000000    -10.000000
+EOF";
+
+        let file_a = SP3::from_str(&content_a).unwrap();
+        let file_b = SP3::from_str(&content_b).unwrap();
+
+        let g01 = SV::from_str("G01").unwrap();
+        let t1 = Epoch::from_str("2023-08-27T00:15:00 GPST").unwrap();
+
+        // default: lhs is preserved
+        let merged = file_a.merge(&file_b).unwrap();
+        let default = file_a
+            .merge_with_policy(&file_b, MergeConflictPolicy::default())
+            .unwrap();
+
+        assert_eq!(merged, default);
+        assert_eq!(merged.total_epochs(), 3);
+        assert_eq!(merged.header.num_epochs, 3);
+        assert_eq!(
+            merged.get_position_km(g01, t1),
+            Some((13100.0, -20000.0, 15000.0))
+        );
+
+        // reject
+        match file_a.merge_with_policy(&file_b, MergeConflictPolicy::Reject) {
+            Err(MergeConflictError::PositionConflict { sv, epoch }) => {
+                assert_eq!(sv, g01);
+                assert_eq!(epoch, t1);
+            },
+            Err(e) => panic!("invalid error: {}", e),
+            Ok(_) => panic!("conflicting positions should have been rejected"),
+        }
+
+        let mut rejected = file_a.clone();
+        assert!(rejected
+            .merge_mut_with_policy(&file_b, MergeConflictPolicy::Reject)
+            .is_err());
+        assert_eq!(rejected, file_a, "rejected merge should not modify lhs");
+
+        // identical positions are not conflicting
+        assert!(file_a
+            .merge_with_policy(&file_a, MergeConflictPolicy::Reject)
+            .is_ok());
+
+        // average
+        let averaged = file_a
+            .merge_with_policy(&file_b, MergeConflictPolicy::Average)
+            .unwrap();
+
+        assert_eq!(averaged.total_epochs(), 3);
+
+        let entry = averaged.get(g01, t1).unwrap();
+
+        assert!((entry.position_km.0 - 13100.001).abs() < 1.0E-9);
+        assert_eq!(entry.position_km.1, -20000.0);
+        assert_eq!(entry.position_km.2, 15000.0);
+        assert_eq!(entry.clock_us, Some(-11.0));
+
+        // merge errors are still reported
+        let mut file_c = file_b.clone();
+        file_c.header.agency = "ESA".to_string();
+
+        assert!(matches!(
+            file_a.merge_with_policy(&file_c, MergeConflictPolicy::Average),
+            Err(MergeConflictError::Merge(MergeError::DataProviderMismatch))
+        ));
+    }
 }
@@ -1,10 +1,24 @@
+pub mod fixtures;
 pub mod formatting;
 
+mod builder;
+mod canonicalize;
+mod clock;
+mod concatenation;
 mod interpolation;
 mod maneuver;
+mod parser_3a;
 mod parser_3c;
 mod parser_3d;
+mod production;
+mod quality;
+mod roundtrip;
+mod sampling;
+mod split;
+mod stream;
 mod test_pool;
+mod transform;
+mod validation;
 
 #[cfg(feature = "qc")]
 mod merge;
@@ -12,6 +26,9 @@ mod merge;
 #[cfg(feature = "processing")]
 mod substract;
 
+#[cfg(feature = "async")]
+mod async_reader;
+
 //#[cfg(feature = "qc")]
 //mod decimation;
 
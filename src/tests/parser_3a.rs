@@ -0,0 +1,77 @@
+//! SP3-a parsing tests
+#[cfg(test)]
+mod test {
+    use crate::prelude::*;
+    use crate::tests::fixtures::{sp3_header, t0};
+    use std::str::FromStr;
+
+    #[test]
+    fn glonass_only_omitted_constellation() {
+        let content = "#aP2023  8 27  0  0  0.00000000       2 ORBIT IGS20 FIT  IAC
+## 2277      0.00000000   900.00000000 60183 0.0000000000000
++    2    1  5  0  0  0  0  0  0  0  0  0  0  0  0  0  0  0
+++         0  0  0  0  0  0  0  0  0  0  0  0  0  0  0  0  0
+%c R  cc GPS ccc cccc cccc cccc cccc ccccc ccccc ccccc ccccc
+%c cc cc ccc ccc cccc cccc cccc cccc ccccc ccccc ccccc ccccc
+/* GLONASS only
+*  2023  8 27  0  0  0.00000000
+P  1  13000.000000 -20000.000000  15000.123456    -10.500000
+P  5  13001.000000 -19000.000000  15000.123456     -9.500000
+*  2023  8 27  0 15  0.00000000
+P  1  13100.000000 -20000.000000  15000.123456    -10.500000
+P  5  13101.000000 -19000.000000  15000.123456     -9.500000
+EOF";
+
+        let sp3 = SP3::from_str(content).unwrap();
+
+        assert_eq!(sp3.header.version, Version::A);
+        assert_eq!(sp3.header.constellation, Constellation::Glonass);
+        assert_eq!(sp3.header.timescale, TimeScale::GPST);
+
+        let r01 = SV::from_str("R01").unwrap();
+        let r05 = SV::from_str("R05").unwrap();
+
+        assert_eq!(sp3.satellites_iter().collect::<Vec<_>>(), vec![r01, r05]);
+        assert_eq!(sp3.data.len(), 4);
+
+        // legacy GPS-only file
+        let content = content.replace("%c R ", "%c cc");
+        let sp3 = SP3::from_str(&content).unwrap();
+
+        assert_eq!(sp3.header.constellation, Constellation::GPS);
+
+        assert!(sp3
+            .satellites_iter()
+            .all(|sv| sv.constellation == Constellation::GPS));
+    }
+
+    #[test]
+    fn omitted_seconds() {
+        let content = sp3_header(Version::A, DataType::Position, &["G01"], 3)
+            + "*  2023  8 27  0  0
+P  1  13000.000000 -20000.000000  15000.123456    -10.500000
+*  2023  8 27  0 15  0
+P  1  13100.000000 -20000.000000  15000.123456    -10.500000
+*  2023  8 27  0 30  0.0000
+P  1  13200.000000 -20000.000000  15000.123456    -10.500000
+EOF";
+
+        let sp3 = SP3::from_str(&content).unwrap();
+
+        let t0 = t0();
+        let dt = Duration::from_seconds(900.0);
+
+        assert_eq!(
+            sp3.epochs_iter().collect::<Vec<_>>(),
+            vec![t0, t0 + dt, t0 + 2.0 * dt]
+        );
+
+        assert_eq!(sp3.data.len(), 3);
+
+        // truncated epoch lines
+        for truncated in ["*  2023  8 27  0", "*  2023  8", "*  2023"] {
+            let content = content.replace("*  2023  8 27  0 15  0", truncated);
+            assert!(SP3::from_str(&content).is_err());
+        }
+    }
+}
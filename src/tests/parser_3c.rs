@@ -3,7 +3,10 @@
 mod test {
     use crate::prelude::*;
     use crate::production::Campaign;
+    use crate::tests::fixtures::{sp3_header, t0};
     use gnss::prelude::Constellation;
+    use hifitime::Unit;
+    use std::io::{BufReader, BufWriter};
     use std::path::PathBuf;
     use std::str::FromStr;
 
@@ -26,14 +29,15 @@ mod test {
         assert!(!sp3.has_satellite_velocity());
         assert!(!sp3.has_satellite_maneuver());
 
-        assert_eq!(
-            sp3.first_epoch().unwrap(),
-            Epoch::from_str("2023-08-27T00:00:00 GPST").unwrap()
-        );
+        assert_eq!(sp3.first_epoch().unwrap(), t0());
 
         assert_eq!(sp3.total_epochs(), 96, "bad number of epochs");
 
         assert_eq!(sp3.header.coord_system, "ITRF2");
+        assert_eq!(
+            sp3.header.try_reference_frame(),
+            Some(ReferenceFrameHint::ITRF)
+        );
         assert_eq!(sp3.header.orbit_type, OrbitType::BHN);
         assert_eq!(sp3.header.timescale, TimeScale::GPST);
         assert_eq!(sp3.header.constellation, Constellation::Mixed);
@@ -53,11 +57,7 @@ mod test {
         for (index, epoch) in sp3.epochs_iter().enumerate() {
             match index {
                 0 => {
-                    assert_eq!(
-                        epoch,
-                        Epoch::from_str("2023-08-27T00:00:00 GPST").unwrap(),
-                        "parsed wrong epoch"
-                    );
+                    assert_eq!(epoch, t0(), "parsed wrong epoch");
                 },
                 1 => {
                     assert_eq!(
@@ -107,4 +107,299 @@ mod test {
             "ESA0OPSRAP_20232390000_01D_15M_ORB.SP3.gz"
         );
     }
+
+    #[test]
+    #[cfg(feature = "flate2")]
+    fn expecting_revision() {
+        use flate2::read::GzDecoder;
+        use std::fs::File;
+        use std::io::BufReader;
+
+        let path = PathBuf::new()
+            .join(env!("CARGO_MANIFEST_DIR"))
+            .join("data/SP3")
+            .join("C")
+            .join("ESA0OPSRAP_20232390000_01D_15M_ORB.SP3.gz");
+
+        let fd = File::open(&path).unwrap();
+        let mut reader = BufReader::new(GzDecoder::new(fd));
+
+        match SP3::from_reader_expecting(&mut reader, Version::D) {
+            Err(Error::ParsingError(ParsingError::UnexpectedRevision(expected, found))) => {
+                assert_eq!(expected, Version::D);
+                assert_eq!(found, Version::C);
+            },
+            Err(e) => panic!("invalid error: {}", e),
+            Ok(_) => panic!("SP3-c file should have been rejected"),
+        }
+
+        let fd = File::open(&path).unwrap();
+        let mut reader = BufReader::new(GzDecoder::new(fd));
+
+        let sp3 = SP3::from_reader_expecting(&mut reader, Version::C).unwrap();
+        assert_eq!(sp3.header.version, Version::C);
+    }
+
+    #[test]
+    #[cfg(feature = "mmap")]
+    fn mmap_parsing() {
+        let path = PathBuf::new()
+            .join(env!("CARGO_MANIFEST_DIR"))
+            .join("data/SP3")
+            .join("C")
+            .join("co108870.sp3");
+
+        let sp3 = SP3::from_file(&path).unwrap();
+
+        // SAFETY: test data is never modified
+        let mapped = unsafe { SP3::from_mmap(&path) }.unwrap();
+
+        assert_eq!(mapped, sp3);
+    }
+
+    fn velocity_example() -> String {
+        sp3_header(Version::C, DataType::Velocity, &["G01"], 2)
+            + "/* velocity example
+*  2023  8 27  0  0  0.00000000
+PG01  13000.000000 -20000.000000  15000.123456    -10.500000
+VG01  -6034.974221  19271.123456   2813.455200      1.234567
+*  2023  8 27  0 15  0.00000000
+PG01  12450.000000 -18200.000000  15230.654321    -10.498889
+VG01  -6120.001234  19111.987654   2790.100000      1.234321
+EOF"
+    }
+
+    #[test]
+    fn velocity_components() {
+        let sp3 = SP3::from_str(&velocity_example()).unwrap();
+
+        assert_eq!(sp3.header.data_type, DataType::Velocity);
+        assert!(sp3.has_satellite_velocity());
+
+        let g01 = SV::from_str("G01").unwrap();
+        let t0 = t0();
+
+        let entry = sp3.data.get(&SP3Key { epoch: t0, sv: g01 }).unwrap();
+
+        let (vx_km_s, vy_km_s, vz_km_s) = entry.velocity_km_s.unwrap();
+
+        assert!(
+            (vx_km_s - -0.6034974221).abs() < 1.0E-12,
+            "bad vx: {}",
+            vx_km_s
+        );
+        assert!(
+            (vy_km_s - 1.9271123456).abs() < 1.0E-12,
+            "bad vy: {}",
+            vy_km_s
+        );
+        assert!(
+            (vz_km_s - 0.28134552).abs() < 1.0E-12,
+            "bad vz: {}",
+            vz_km_s
+        );
+
+        let drift_ns = entry.clock_drift_ns.unwrap();
+        assert!(
+            (drift_ns - 123.4567).abs() < 1.0E-9,
+            "bad drift: {}",
+            drift_ns
+        );
+    }
+
+    #[test]
+    fn velocity_roundtrip() {
+        let sp3 = SP3::from_str(&velocity_example()).unwrap();
+
+        let mut writer = BufWriter::new(Vec::new());
+        sp3.format(&mut writer).unwrap();
+
+        let content = writer.into_inner().unwrap();
+        let mut reader = BufReader::new(content.as_slice());
+
+        let parsed_back = SP3::from_reader(&mut reader).unwrap();
+
+        assert_eq!(parsed_back.header.data_type, DataType::Velocity);
+
+        let velocities = sp3.satellites_velocity_km_s_iter().collect::<Vec<_>>();
+        let parsed_velocities = parsed_back
+            .satellites_velocity_km_s_iter()
+            .collect::<Vec<_>>();
+
+        assert_eq!(velocities.len(), 2);
+        assert_eq!(parsed_velocities.len(), velocities.len());
+
+        for ((_, _, (vx, vy, vz)), (_, _, (parsed_vx, parsed_vy, parsed_vz))) in
+            velocities.iter().zip(parsed_velocities.iter())
+        {
+            // X component must survive
+            assert!(
+                (vx - parsed_vx).abs() < 1.0E-10,
+                "vx: {} != {}",
+                vx,
+                parsed_vx
+            );
+            assert!(
+                (vy - parsed_vy).abs() < 1.0E-10,
+                "vy: {} != {}",
+                vy,
+                parsed_vy
+            );
+            assert!(
+                (vz - parsed_vz).abs() < 1.0E-10,
+                "vz: {} != {}",
+                vz,
+                parsed_vz
+            );
+            assert_ne!(vx, vy);
+        }
+    }
+
+    // Formats this [SP3] and returns the `%c` lines
+    fn formatted_descriptors(sp3: &SP3) -> Vec<String> {
+        let mut writer = BufWriter::new(Vec::new());
+        sp3.format(&mut writer).unwrap();
+
+        let content = String::from_utf8(writer.into_inner().unwrap()).unwrap();
+
+        content
+            .lines()
+            .filter(|line| line.starts_with("%c"))
+            .map(|line| line.trim_end().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn file_descriptor_roundtrip() {
+        // reserved fields are preserved
+        let content = velocity_example()
+            .replace(
+                "%c G  cc GPS ccc cccc cccc cccc cccc ccccc ccccc ccccc ccccc",
+                "%c G  ab GPS xyz cccc 1234 cccc cccc ccccc ccccc ccccc 00042",
+            )
+            .replace(
+                "%c cc cc ccc ccc cccc cccc cccc cccc ccccc ccccc ccccc ccccc",
+                "%c R  cc ccc ccc cccc cccc cccc cccc ccccc abcde ccccc ccccc",
+            );
+
+        let sp3 = SP3::from_str(&content).unwrap();
+
+        assert_eq!(sp3.header.constellation, Constellation::GPS);
+        assert_eq!(sp3.header.timescale, TimeScale::GPST);
+        assert_eq!(sp3.header.file_descriptor.line1_reserved[0], "ab");
+        assert_eq!(sp3.header.file_descriptor.line2_reserved[9], "abcde");
+
+        let expected = content
+            .lines()
+            .filter(|line| line.starts_with("%c"))
+            .collect::<Vec<_>>();
+
+        assert_eq!(formatted_descriptors(&sp3), expected);
+
+        // placeholders
+        let sp3 = SP3::from_str(&velocity_example()).unwrap();
+        assert_eq!(sp3.header.file_descriptor, FileDescriptor::default());
+
+        assert_eq!(
+            formatted_descriptors(&sp3),
+            vec![
+                "%c G  cc GPS ccc cccc cccc cccc cccc ccccc ccccc ccccc ccccc",
+                "%c cc cc ccc ccc cccc cccc cccc cccc ccccc ccccc ccccc ccccc",
+            ]
+        );
+    }
+
+    #[test]
+    fn co108870_file_descriptor_roundtrip() {
+        let path = PathBuf::new()
+            .join(env!("CARGO_MANIFEST_DIR"))
+            .join("data/SP3")
+            .join("C")
+            .join("co108870.sp3");
+
+        let content = std::fs::read_to_string(&path).unwrap();
+
+        let expected = content
+            .lines()
+            .filter(|line| line.starts_with("%c"))
+            .map(|line| line.trim_end())
+            .collect::<Vec<_>>();
+
+        assert_eq!(expected.len(), 2);
+
+        let sp3 = SP3::from_file(&path).unwrap();
+        assert_eq!(formatted_descriptors(&sp3), expected);
+    }
+
+    #[test]
+    #[cfg(feature = "flate2")]
+    fn grg0mgxfin_entry_accessors() {
+        let path = PathBuf::new()
+            .join(env!("CARGO_MANIFEST_DIR"))
+            .join("data/SP3")
+            .join("C")
+            .join("GRG0MGXFIN_20201760000_01D_15M_ORB.SP3.gz");
+
+        let sp3 = SP3::from_gzip_file(&path).unwrap();
+
+        let g01 = SV::from_str("G01").unwrap();
+
+        let (t, _, _, _, position_km) = sp3
+            .satellites_position_km_iter()
+            .find(|(_, sv, _, _, _)| *sv == g01)
+            .expect("missing G01 position");
+
+        let entry = sp3.get(g01, t).expect("missing G01 entry");
+
+        assert_eq!(entry.position_km, position_km);
+        assert_eq!(sp3.get_position_km(g01, t), Some(position_km));
+
+        // exact epoch match only
+        let dt = Duration::from_seconds(1.0);
+
+        assert!(sp3.get(g01, t + dt).is_none());
+        assert!(sp3.get_position_km(g01, t + dt).is_none());
+
+        let g99 = SV::from_str("G99").unwrap();
+        assert!(sp3.get(g99, t).is_none());
+    }
+
+    #[test]
+    fn entry_accessors() {
+        let content = sp3_header(Version::C, DataType::Position, &["G01"], 1)
+            + "*  2023  8 27  0  0  0.00000000
+PG01 -22335.782004 -14656.280389  -1218.238499   -176.397152
+EOF";
+
+        let sp3 = SP3::from_str(&content).unwrap();
+
+        let g01 = SV::from_str("G01").unwrap();
+        let t0 = t0();
+
+        let entry = sp3.get(g01, t0).unwrap();
+        assert_eq!(entry.clock_us, Some(-176.397152));
+
+        assert_eq!(
+            sp3.get_position_km(g01, t0),
+            Some((-22335.782004, -14656.280389, -1218.238499))
+        );
+
+        let t1 = Epoch::from_str("2023-08-27T00:15:00 GPST").unwrap();
+        assert!(sp3.get(g01, t1).is_none());
+        assert!(sp3.get_position_km(g01, t1).is_none());
+
+        // 1ns offset: exact match fails
+        let t = t0 + 1.0 * Unit::Nanosecond;
+        assert!(sp3.get(g01, t).is_none());
+
+        let entry = sp3.get_near(g01, t, 10.0 * Unit::Nanosecond).unwrap();
+        assert_eq!(entry.clock_us, Some(-176.397152));
+
+        let t = t0 - 1.0 * Unit::Nanosecond;
+        assert!(sp3.get_near(g01, t, 1.0 * Unit::Nanosecond).is_some());
+        assert!(sp3.get_near(g01, t, Duration::ZERO).is_none());
+
+        let g02 = SV::from_str("G02").unwrap();
+        assert!(sp3.get_near(g02, t0, 1.0 * Unit::Second).is_none());
+    }
 }
@@ -2,7 +2,10 @@
 #[cfg(test)]
 mod test {
     use crate::prelude::*;
+    use crate::tests::fixtures::{sp3_d_header, sp3_header, t0};
     use gnss_rs::prelude::Constellation;
+    use itertools::Itertools;
+    use std::io::{BufReader, BufWriter};
     use std::path::PathBuf;
     use std::str::FromStr;
 
@@ -34,11 +37,30 @@ mod test {
 
         assert_eq!(sp3.total_epochs(), 1);
 
+        assert_eq!(
+            sp3.expected_records_per_epoch(),
+            sp3.satellites_iter().count()
+        );
+        assert!(sp3.expected_records_per_epoch() > 0);
+
+        for epoch in sp3.epochs_iter() {
+            assert_eq!(sp3.records_at(epoch), sp3.expected_records_per_epoch());
+        }
+
+        assert_eq!(
+            sp3.records_at(Epoch::from_str("2019-10-27T00:15:00 GPST").unwrap()),
+            0
+        );
+
         assert!(sp3.has_satellite_clock_offset());
         assert!(!sp3.has_satellite_clock_drift());
         assert!(!sp3.has_satellite_velocity());
 
         assert_eq!(sp3.header.coord_system, "IGS14");
+        assert_eq!(
+            sp3.header.try_reference_frame(),
+            Some(ReferenceFrameHint::IGS14)
+        );
         assert_eq!(sp3.header.orbit_type, OrbitType::FIT);
         assert_eq!(sp3.header.timescale, TimeScale::GPST);
         assert_eq!(sp3.header.constellation, Constellation::Mixed);
@@ -55,6 +77,10 @@ mod test {
         assert_eq!(sp3.header.mjd, 58783);
         assert_eq!(sp3.header.mjd_fraction, 0.0);
 
+        assert_eq!(sp3.header.pos_vel_base, (1.25, 1.025));
+        assert_eq!(sp3.header.float_params, [0.0; 6]);
+        assert_eq!(sp3.header.int_params, [0; 18]);
+
         let c01 = SV::from_str("C01").unwrap();
         let e01 = SV::from_str("E01").unwrap();
         let g01 = SV::from_str("G01").unwrap();
@@ -159,4 +185,669 @@ mod test {
             ],
         );
     }
+
+    #[test]
+    fn declared_satellites() {
+        let content = "#dP2023  8 27  0  0  0.00000000       2 ORBIT IGS20 FIT  IGS
+## 2277      0.00000000   900.00000000 60183 0.0000000000000
++    2   G02G01  0  0  0  0  0  0  0  0  0  0  0  0  0  0  0
+++         0  0  0  0  0  0  0  0  0  0  0  0  0  0  0  0  0
+%c G  cc GPS ccc cccc cccc cccc cccc ccccc ccccc ccccc ccccc
+%c cc cc ccc ccc cccc cccc cccc cccc ccccc ccccc ccccc ccccc
+*  2023  8 27  0  0  0.00000000
+PG01 -22335.782004 -14656.280389  -1218.238499   -176.397152
+PG02  13001.000000 -19000.000000  15000.123456     -9.500000
+*  2023  8 27  0 15  0.00000000
+PG01 -22335.000000 -14656.000000  -1218.000000   -176.400000
+EOF";
+
+        let sp3 = SP3::from_str(content).unwrap();
+
+        let g01 = SV::from_str("G01").unwrap();
+        let g02 = SV::from_str("G02").unwrap();
+
+        // sorted, whatever the declaration order
+        assert_eq!(sp3.header.satellites, vec![g01, g02]);
+        assert_eq!(sp3.header.num_satellites, 2);
+
+        assert_eq!(sp3.expected_records_per_epoch(), 2);
+
+        let t1 = Epoch::from_str("2023-08-27T00:15:00 GPST").unwrap();
+        assert_eq!(sp3.records_at(t1), 1);
+
+        // G01 is described but not declared
+        let undeclared = content.replace("+    2   G02G01  0", "+    1   G02  0  0");
+
+        let sp3 = SP3::from_str(&undeclared).unwrap();
+
+        assert_eq!(sp3.header.satellites, vec![g01, g02]);
+        assert_eq!(sp3.expected_records_per_epoch(), 1);
+
+        assert!(sp3
+            .validate()
+            .unwrap_err()
+            .contains(&ValidationIssue::SatelliteCount {
+                declared: 1,
+                found: 2
+            }));
+
+        // missing satellite lines: we expect the satellites of the record
+        let missing = content
+            .lines()
+            .filter(|line| !line.starts_with('+'))
+            .join("\n");
+
+        let sp3 = SP3::from_str(&missing).unwrap();
+
+        assert_eq!(sp3.header.satellites, vec![g01, g02]);
+        assert_eq!(sp3.expected_records_per_epoch(), 2);
+    }
+
+    #[test]
+    fn from_str_content() {
+        let content = sp3_header(Version::D, DataType::Position, &["G01", "E05"], 2)
+            + "/* embedded example
+*  2023  8 27  0  0  0.00000000
+PG01  13000.000000 -20000.000000  15000.123456    -10.500000
+PE05  13001.000000 -19000.000000  15000.123456     -9.500000
+*  2023  8 27  0 15  0.00000000
+PG01  13100.000000 -20000.000000  15000.123456    -10.500000
+PE05  13101.000000 -19000.000000  15000.123456     -9.500000
+EOF";
+
+        let sp3 = content.parse::<SP3>().unwrap();
+
+        assert_eq!(sp3.header.version, Version::D);
+        assert_eq!(sp3.header.agency, "IGS");
+        assert_eq!(sp3.header.coord_system, "IGS20");
+        assert_eq!(
+            sp3.header.try_reference_frame(),
+            Some(ReferenceFrameHint::IGS20)
+        );
+        assert_eq!(sp3.header.num_epochs, 2);
+        assert_eq!(sp3.header.constellation, Constellation::Mixed);
+        assert_eq!(sp3.header.timescale, TimeScale::GPST);
+
+        assert_eq!(sp3.comments, vec!["embedded example".to_string()]);
+        assert_eq!(sp3.total_epochs(), 2);
+        assert_eq!(sp3.data.len(), 4);
+
+        let e05 = SV::from_str("E05").unwrap();
+        let g01 = SV::from_str("G01").unwrap();
+        assert!(sp3.satellites_iter().any(|sv| sv == e05));
+        assert!(sp3.satellites_iter().any(|sv| sv == g01));
+
+        // TryFrom is strictly equivalent
+        let other = SP3::try_from(content.as_str()).unwrap();
+        assert_eq!(other, sp3);
+    }
+
+    #[test]
+    fn predicted_clock_vs_orbit() {
+        // G01: predicted clock, fitted orbit
+        // E05: fitted clock, predicted orbit
+        // G02: fitted clock and orbit
+        let content = sp3_header(Version::D, DataType::Position, &["G01", "G02", "E05"], 2)
+            + "/* mixed prediction flags
+*  2023  8 27  0  0  0.00000000
+PG01  13000.000000 -20000.000000  15000.123456    -10.500000 10  9 11 102  P
+PG02  14000.000000 -21000.000000  16000.123456    -11.500000
+PE05  13001.000000 -19000.000000  15000.123456     -9.500000 10  9 11 102      P
+*  2023  8 27  0 15  0.00000000
+PG01  13100.000000 -20000.000000  15000.123456    -10.400000 10  9 11 102  P
+PG02  14100.000000 -21000.000000  16000.123456    -11.400000
+PE05  13101.000000 -19000.000000  15000.123456     -9.400000 10  9 11 102      P
+EOF";
+
+        let sp3 = SP3::from_str(&content).unwrap();
+
+        let e05 = SV::from_str("E05").unwrap();
+        let g01 = SV::from_str("G01").unwrap();
+
+        let predicted_clocks = sp3.satellites_predicted_clock_iter().collect::<Vec<_>>();
+        assert_eq!(predicted_clocks.len(), 2);
+
+        for (_, sv, clock_us) in predicted_clocks.iter() {
+            assert_eq!(*sv, g01);
+            assert!(*clock_us == -10.5 || *clock_us == -10.4);
+        }
+
+        let predicted_orbits = sp3
+            .satellites_stable_predicted_position_km_iter()
+            .collect::<Vec<_>>();
+
+        assert_eq!(predicted_orbits.len(), 2);
+
+        for (_, sv, _) in predicted_orbits.iter() {
+            assert_eq!(*sv, e05);
+        }
+    }
+
+    #[test]
+    fn release_epoch_recovery() {
+        let content = sp3_header(Version::D, DataType::Position, &["G01", "E05"], 2)
+            .replace("#dP2023  8 27  0  0", "#dP   0  0  0  0  0")
+            + "/* zeroed line #1 epoch
+*  2023  8 27  0  0  0.00000000
+PG01  13000.000000 -20000.000000  15000.123456    -10.500000
+PE05  13001.000000 -19000.000000  15000.123456     -9.500000
+EOF";
+
+        let t0 = t0();
+
+        // recovered from week / time of week
+        let sp3 = SP3::from_str(&content).unwrap();
+        assert_eq!(sp3.header.release_epoch, t0);
+        assert_eq!(sp3.header.release_epoch_from_time_fields(), Some(t0));
+
+        // recovered from MJD
+        let mjd_only = content.replace("## 2277      0.00000000", "##    0      0.00000000");
+
+        let sp3 = SP3::from_str(&mjd_only).unwrap();
+        assert_eq!(sp3.header.release_epoch, t0);
+
+        // cannot be recovered
+        let unrecoverable = mjd_only.replace(" 60183 ", "     0 ");
+        assert!(SP3::from_str(&unrecoverable).is_err());
+    }
+
+    #[test]
+    fn accuracy_exponents() {
+        let content = "#dP2023  8 27  0  0  0.00000000       1 ORBIT IGS20 FIT  IGS
+## 2277      0.00000000   900.00000000 60183 0.0000000000000
++    3   G01E05R07  0  0  0  0  0  0  0  0  0  0  0  0  0  0
++          0  0  0  0  0  0  0  0  0  0  0  0  0  0  0  0  0
++          0  0  0  0  0  0  0  0  0  0  0  0  0  0  0  0  0
++          0  0  0  0  0  0  0  0  0  0  0  0  0  0  0  0  0
++          0  0  0  0  0  0  0  0  0  0  0  0  0  0  0  0  0
+++         5 10  0  0  0  0  0  0  0  0  0  0  0  0  0  0  0
+++         0  0  0  0  0  0  0  0  0  0  0  0  0  0  0  0  0
+++         0  0  0  0  0  0  0  0  0  0  0  0  0  0  0  0  0
+++         0  0  0  0  0  0  0  0  0  0  0  0  0  0  0  0  0
+++         0  0  0  0  0  0  0  0  0  0  0  0  0  0  0  0  0
+%c M  cc GPS ccc cccc cccc cccc cccc ccccc ccccc ccccc ccccc
+%c cc cc ccc ccc cccc cccc cccc cccc ccccc ccccc ccccc ccccc
+%f  1.2500000  1.025000000  0.00000000000  0.000000000000000
+%f  0.0000000  0.000000000  0.00000000000  0.000000000000000
+%i    0    0    0    0      0      0      0      0         0
+%i    0    0    0    0      0      0      0      0         0
+/* accuracy exponents
+*  2023  8 27  0  0  0.00000000
+PG01  13000.000000 -20000.000000  15000.123456    -10.500000
+PE05  13001.000000 -19000.000000  15000.123456     -9.500000
+PR07  13002.000000 -18000.000000  15000.123456     -8.500000
+EOF";
+
+        let sp3 = SP3::from_str(content).unwrap();
+
+        let g01 = SV::from_str("G01").unwrap();
+        let e05 = SV::from_str("E05").unwrap();
+        let r07 = SV::from_str("R07").unwrap();
+
+        assert_eq!(sp3.header.accuracy_exponents.len(), 2);
+        assert_eq!(sp3.header.satellite_accuracy(g01), Some(0.032));
+        assert_eq!(sp3.header.satellite_accuracy(e05), Some(1.024));
+
+        // unknown accuracy
+        assert_eq!(sp3.header.satellite_accuracy(r07), None);
+
+        // undeclared
+        let g02 = SV::from_str("G02").unwrap();
+        assert_eq!(sp3.header.satellite_accuracy(g02), None);
+
+        // +/++ blocks are regenerated
+        let mut writer = BufWriter::new(Vec::new());
+        sp3.format(&mut writer).unwrap();
+
+        let formatted = String::from_utf8(writer.into_inner().unwrap()).unwrap();
+
+        for line in content.lines().filter(|line| line.starts_with('+')) {
+            assert!(formatted.contains(line), "missing line \"{}\"", line);
+        }
+
+        let parsed_back = SP3::from_str(&formatted).unwrap();
+
+        assert_eq!(
+            parsed_back.header.accuracy_exponents,
+            sp3.header.accuracy_exponents
+        );
+    }
+
+    #[test]
+    fn standard_deviations_roundtrip() {
+        let content = sp3_header(Version::D, DataType::Position, &["G01", "E05"], 1)
+            + "/* standard deviations
+*  2023  8 27  0  0  0.00000000
+PG01 -22335.782004 -14656.280389  -1218.238499   -176.397152 10  9 11 102 EP  MP
+PE05  13001.000000 -19000.000000  15000.123456     -9.500000
+EOF";
+
+        let sp3 = SP3::from_str(&content).unwrap();
+
+        let g01 = SV::from_str("G01").unwrap();
+        let e05 = SV::from_str("E05").unwrap();
+
+        let (_, g01_entry) = sp3.data.iter().find(|(k, _)| k.sv == g01).unwrap();
+        let (_, e05_entry) = sp3.data.iter().find(|(k, _)| k.sv == e05).unwrap();
+
+        assert_eq!(g01_entry.position_std_mm, Some((10, 9, 11)));
+        assert_eq!(g01_entry.clock_std_ps, Some(102));
+
+        assert_eq!(sp3.header.pos_vel_base, (1.25, 1.025));
+
+        let (std_x, std_y, std_z) = sp3
+            .header
+            .position_std_mm(g01_entry.position_std_mm.unwrap())
+            .unwrap();
+
+        assert_eq!(std_x, 1.25_f64.powi(10));
+        assert_eq!(std_y, 1.25_f64.powi(9));
+        assert_eq!(std_z, 1.25_f64.powi(11));
+
+        let clock_std = sp3
+            .header
+            .clock_std_ps(g01_entry.clock_std_ps.unwrap())
+            .unwrap();
+
+        assert_eq!(clock_std, 1.025_f64.powi(102));
+
+        assert_eq!(e05_entry.position_std_mm, None);
+        assert_eq!(e05_entry.clock_std_ps, None);
+
+        let mut writer = BufWriter::new(Vec::new());
+        sp3.format(&mut writer).unwrap();
+
+        let formatted = String::from_utf8(writer.into_inner().unwrap()).unwrap();
+
+        assert!(formatted.contains(
+            "PG01 -22335.782004 -14656.280389  -1218.238499   -176.397152 10  9 11 102 EP  MP"
+        ));
+
+        assert!(
+            formatted.contains("%f  1.2500000  1.025000000  0.00000000000  0.000000000000000"),
+            "%f line not preserved: {}",
+            formatted
+        );
+
+        let parsed_back = SP3::from_str(&formatted).unwrap();
+
+        assert_eq!(parsed_back.header.pos_vel_base, sp3.header.pos_vel_base);
+
+        for (entry, parsed_entry) in sp3.data.values().zip(parsed_back.data.values()) {
+            assert_eq!(entry.position_std_mm, parsed_entry.position_std_mm);
+            assert_eq!(entry.clock_std_ps, parsed_entry.clock_std_ps);
+        }
+    }
+
+    #[test]
+    fn malformed_parameters() {
+        let content = sp3_d_header(1).replace("1.025000000", "1.0250x0000")
+            + "*  2023  8 27  0  0  0.00000000
+PG01 -22335.782004 -14656.280389  -1218.238499   -176.397152
+EOF";
+
+        // lenient: malformed line is ignored
+        let sp3 = SP3::from_str(&content).unwrap();
+        assert_eq!(sp3.header.pos_vel_base, (0.0, 0.0));
+        assert_eq!(sp3.data.len(), 1);
+
+        let opts = ParseOptions {
+            strict: true,
+            ..Default::default()
+        };
+
+        let mut reader = BufReader::new(content.as_bytes());
+
+        assert!(matches!(
+            SP3::from_reader_with_opts(&mut reader, opts),
+            Err(Error::ParsingError(ParsingError::AdditionalParameters(_)))
+        ));
+    }
+
+    #[test]
+    fn parse_options() {
+        let content = sp3_header(Version::D, DataType::Position, &["G01", "E05"], 1)
+            + "/* parse options
+*  2023  8 27  0  0  0.00000000
+PG01 -22335.782004 -14656.280389  -1218.238499   -176.397152 10  9 11 102 EP  MP
+PE05  13001.000000 -19000.000000  15000.123456     -9.500000
+*  2023  8 27  0 15  0.00000000
+PG01 -22335.782004 -14656.280389  -1218.238499   -176.397152 10  9 11 102
+PE05  13001.000000 -19000.000000  15000.123456     -9.500000
+EOF";
+
+        let opts = ParseOptions {
+            read_clocks: false,
+            ..Default::default()
+        };
+
+        let mut reader = BufReader::new(content.as_bytes());
+        let sp3 = SP3::from_reader_with_opts(&mut reader, opts).unwrap();
+
+        assert_eq!(sp3.data.len(), 4);
+
+        for (k, v) in sp3.data.iter() {
+            assert!(
+                v.clock_us.is_none(),
+                "{}({}): clock should be dropped",
+                k.epoch,
+                k.sv
+            );
+            assert!(v.clock_std_ps.is_none());
+            assert!(!v.clock_event);
+            assert!(!v.predicted_clock);
+        }
+
+        let g01 = SV::from_str("G01").unwrap();
+
+        let (_, g01_entry) = sp3.data.iter().find(|(k, _)| k.sv == g01).unwrap();
+        assert_eq!(g01_entry.position_std_mm, Some((10, 9, 11)));
+
+        let opts = ParseOptions {
+            read_stddev: false,
+            ..Default::default()
+        };
+
+        let mut reader = BufReader::new(content.as_bytes());
+        let sp3 = SP3::from_reader_with_opts(&mut reader, opts).unwrap();
+
+        assert_eq!(sp3.data.len(), 4);
+
+        for v in sp3.data.values() {
+            assert!(v.clock_us.is_some());
+            assert!(v.position_std_mm.is_none());
+            assert!(v.clock_std_ps.is_none());
+        }
+    }
+
+    #[test]
+    fn malformed_descriptor() {
+        let content = "#dP2023  8 27  0  0  0.00000000       1 ORBIT IGS20 FIT  IGS
+## 2277      0.00000000   900.00000000 60183 0.0000000000000
++    1   G01  0  0  0  0  0  0  0  0  0  0  0  0  0  0  0  0
+++         0  0  0  0  0  0  0  0  0  0  0  0  0  0  0  0  0
+DESCRIPTOR
+%c cc cc ccc ccc cccc cccc cccc cccc ccccc ccccc ccccc ccccc
+*  2023  8 27  0  0  0.00000000
+PG01 -22335.782004 -14656.280389  -1218.238499   -176.397152
+EOF";
+
+        for descriptor in [
+            // 60 characters, unknown constellation
+            "%c ?? cc GPS ccc cccc cccc cccc cccc ccccc ccccc ccccc ccccc",
+            // 60 characters, unknown timescale
+            "%c G  cc ??? ccc cccc cccc cccc cccc ccccc ccccc ccccc ccccc",
+            // long enough, but not on a character boundary
+            "%cé G cc GPS ccc cccc cccc cccc cccc ccccc ccccc ccccc ccccc",
+        ] {
+            let content = content.replace("DESCRIPTOR", descriptor);
+
+            match SP3::from_str(&content) {
+                Err(Error::ParsingError(ParsingError::MalformedDescriptor(line))) => {
+                    assert_eq!(line, descriptor);
+                },
+                Err(e) => panic!("unexpected error for \"{}\": {}", descriptor, e),
+                Ok(_) => panic!("\"{}\" should not be accepted", descriptor),
+            }
+        }
+
+        let content = content.replace(
+            "DESCRIPTOR",
+            "%c G  cc GPS ccc cccc cccc cccc cccc ccccc ccccc ccccc ccccc",
+        );
+
+        let sp3 = SP3::from_str(&content).unwrap();
+        assert_eq!(sp3.header.constellation, Constellation::GPS);
+        assert_eq!(sp3.header.timescale, TimeScale::GPST);
+    }
+
+    #[test]
+    fn single_null_coordinate() {
+        let content = sp3_header(Version::D, DataType::Position, &["G01", "E05"], 1)
+            + "*  2023  8 27  0  0  0.00000000
+PG01 -22335.782004      0.000000  -1218.238499   -176.397152
+PE05      0.000000      0.000000      0.000000     -9.500000
+EOF";
+
+        let sp3 = SP3::from_str(&content).unwrap();
+
+        let g01 = SV::from_str("G01").unwrap();
+        let e05 = SV::from_str("E05").unwrap();
+
+        let (_, g01_entry) = sp3
+            .data
+            .iter()
+            .find(|(k, _)| k.sv == g01)
+            .expect("single null coordinate should be retained");
+
+        assert_eq!(g01_entry.position_km, (-22335.782004, 0.0, -1218.238499));
+
+        assert!(
+            sp3.data.keys().all(|k| k.sv != e05),
+            "null vectors should be dropped"
+        );
+    }
+
+    #[test]
+    fn single_null_velocity_component() {
+        let content = sp3_header(Version::D, DataType::Velocity, &["G01", "E05"], 1)
+            + "*  2023  8 27  0  0  0.00000000
+PG01 -22335.782004 -14656.280389  -1218.238499   -176.397152
+VG01  -2194.315790      0.000000  25543.416138     -0.006880
+PE05  -7143.938315  25966.946197  -9640.110765     -9.500000
+VE05      0.000000      0.000000      0.000000     -0.001000
+EOF";
+
+        let sp3 = SP3::from_str(&content).unwrap();
+
+        let g01 = SV::from_str("G01").unwrap();
+        let e05 = SV::from_str("E05").unwrap();
+
+        let (_, g01_entry) = sp3.data.iter().find(|(k, _)| k.sv == g01).unwrap();
+
+        let (vx_km_s, vy_km_s, vz_km_s) = g01_entry
+            .velocity_km_s
+            .expect("single null velocity component should be retained");
+
+        assert!((vx_km_s - -0.219431579).abs() < 1.0E-9);
+        assert_eq!(vy_km_s, 0.0);
+        assert!((vz_km_s - 2.5543416138).abs() < 1.0E-9);
+        assert!(g01_entry.clock_drift_ns.is_some());
+
+        // null vector: position is preserved, velocity and drift are dropped
+        let (_, e05_entry) = sp3.data.iter().find(|(k, _)| k.sv == e05).unwrap();
+
+        assert_eq!(
+            e05_entry.position_km,
+            (-7143.938315, 25966.946197, -9640.110765)
+        );
+        assert!(
+            e05_entry.velocity_km_s.is_none(),
+            "null vectors should be dropped"
+        );
+        assert!(e05_entry.clock_drift_ns.is_none());
+    }
+
+    #[test]
+    fn duplicate_entries() {
+        let content = sp3_d_header(1)
+            + "*  2023  8 27  0  0  0.00000000
+PG01 -22335.782004 -14656.280389  -1218.238499   -176.397152
+PG01 -22000.000000 -14000.000000  -1000.000000   -176.397152
+EOF";
+
+        let g01 = SV::from_str("G01").unwrap();
+        let t0 = t0();
+
+        // lenient: last description prevails
+        let sp3 = SP3::from_str(&content).unwrap();
+
+        assert_eq!(
+            sp3.get_position_km(g01, t0),
+            Some((-22000.0, -14000.0, -1000.0))
+        );
+
+        let opts = ParseOptions {
+            strict: true,
+            ..Default::default()
+        };
+
+        let mut reader = BufReader::new(content.as_bytes());
+
+        match SP3::from_reader_with_opts(&mut reader, opts) {
+            Err(Error::ParsingError(ParsingError::DuplicateEntry { sv, epoch })) => {
+                assert_eq!(sv, g01);
+                assert_eq!(epoch, t0);
+            },
+            Err(e) => panic!("unexpected error: {}", e),
+            Ok(_) => panic!("duplicate entry should not be accepted"),
+        }
+
+        // strictly identical descriptions are tolerated
+        let content = content.replace(
+            "PG01 -22000.000000 -14000.000000  -1000.000000   -176.397152",
+            "PG01 -22335.782004 -14656.280389  -1218.238499   -176.397152",
+        );
+
+        let mut reader = BufReader::new(content.as_bytes());
+        let sp3 = SP3::from_reader_with_opts(&mut reader, opts).unwrap();
+        assert_eq!(sp3.data.len(), 1);
+
+        // same position, different clock offset
+        let content = content.replacen(
+            "PG01 -22335.782004 -14656.280389  -1218.238499   -176.397152",
+            "PG01 -22335.782004 -14656.280389  -1218.238499   -180.000000",
+            1,
+        );
+
+        let sp3 = SP3::from_str(&content).unwrap();
+        assert_eq!(sp3.get(g01, t0).unwrap().clock_us, Some(-176.397152));
+
+        let mut reader = BufReader::new(content.as_bytes());
+
+        assert!(
+            matches!(
+                SP3::from_reader_with_opts(&mut reader, opts),
+                Err(Error::ParsingError(ParsingError::DuplicateEntry { .. }))
+            ),
+            "clock duplicate should not be accepted"
+        );
+    }
+
+    #[test]
+    fn satellite_epochs() {
+        let content = sp3_header(Version::D, DataType::Position, &["G01", "E05"], 3)
+            + "*  2023  8 27  0  0  0.00000000
+PG01 -22335.782004 -14656.280389  -1218.238499   -176.397152
+*  2023  8 27  0 15  0.00000000
+PG01 -22335.000000 -14656.000000  -1218.000000   -176.400000
+PE05  13001.000000 -19000.000000  15000.123456     -9.500000
+*  2023  8 27  0 30  0.00000000
+PG01 -22334.000000 -14655.000000  -1217.000000   -176.500000
+EOF";
+
+        let sp3 = SP3::from_str(&content).unwrap();
+
+        let g01 = SV::from_str("G01").unwrap();
+        let e05 = SV::from_str("E05").unwrap();
+        let g02 = SV::from_str("G02").unwrap();
+
+        let t0 = t0();
+        let t1 = Epoch::from_str("2023-08-27T00:15:00 GPST").unwrap();
+        let t2 = Epoch::from_str("2023-08-27T00:30:00 GPST").unwrap();
+
+        assert_eq!(sp3.first_epoch(), Some(t0));
+        assert_eq!(sp3.last_epoch(), Some(t2));
+
+        assert_eq!(sp3.satellite_first_epoch(g01), Some(t0));
+        assert_eq!(sp3.satellite_last_epoch(g01), Some(t2));
+        assert_eq!(
+            sp3.satellite_epochs_iter(g01).collect::<Vec<_>>(),
+            vec![t0, t1, t2]
+        );
+
+        // E05 only appears once
+        assert_eq!(sp3.satellite_first_epoch(e05), Some(t1));
+        assert_eq!(sp3.satellite_last_epoch(e05), Some(t1));
+        assert_eq!(sp3.satellite_epochs_iter(e05).collect::<Vec<_>>(), vec![t1]);
+
+        // not described
+        assert!(sp3.satellite_first_epoch(g02).is_none());
+        assert!(sp3.satellite_last_epoch(g02).is_none());
+        assert_eq!(sp3.satellite_epochs_iter(g02).count(), 0);
+    }
+
+    #[test]
+    fn observables_roundtrip() {
+        let content = sp3_d_header(1).replace(" ORBIT ", " __u+U ")
+            + "*  2023  8 27  0  0  0.00000000
+PG01 -22335.782004 -14656.280389  -1218.238499   -176.397152
+EOF";
+
+        for observables in ["__u+U", "  u+U", "ORBIT"] {
+            let content = content.replace("__u+U", observables);
+
+            let sp3 = SP3::from_str(&content).unwrap();
+            assert_eq!(sp3.header.observables, observables);
+
+            let mut writer = BufWriter::new(Vec::new());
+            sp3.format(&mut writer).unwrap();
+
+            let formatted = String::from_utf8(writer.into_inner().unwrap()).unwrap();
+
+            let line1 = formatted.lines().next().unwrap();
+            let expected = content.lines().next().unwrap();
+
+            assert_eq!(&line1[40..45], observables);
+            assert_eq!(line1, expected);
+
+            let parsed_back = SP3::from_str(&formatted).unwrap();
+            assert_eq!(parsed_back.header.observables, observables);
+        }
+
+        // column is preserved
+        let mut sp3 = SP3::from_str(&content).unwrap();
+        sp3.header.observables = "u+U+ORBIT".to_string();
+
+        let mut writer = BufWriter::new(Vec::new());
+        sp3.format(&mut writer).unwrap();
+
+        let formatted = String::from_utf8(writer.into_inner().unwrap()).unwrap();
+        let line1 = formatted.lines().next().unwrap();
+
+        assert_eq!(&line1[40..45], "u+U+O");
+        assert_eq!(&line1[45..51], " IGS20");
+    }
+
+    #[test]
+    fn observables_and_orbit_type_columns() {
+        let content = sp3_d_header(1).replace(" ORBIT ", " __u+U ")
+            + "*  2023  8 27  0  0  0.00000000
+PG01 -22335.782004 -14656.280389  -1218.238499   -176.397152
+EOF";
+
+        // data used (columns 41-45) and orbit (fit) type (columns 53-55)
+        for (observables, orbit_type) in [
+            ("__u+U", OrbitType::FIT),
+            ("ORBIT", OrbitType::EXT),
+            ("  u+U", OrbitType::HLM),
+            ("d+D+U", OrbitType::BCT),
+            ("mixed", OrbitType::BHN),
+        ] {
+            let content = content
+                .replace("__u+U", observables)
+                .replace("IGS20 FIT", &format!("IGS20 {}", orbit_type));
+
+            let sp3 = SP3::from_str(&content).unwrap();
+
+            assert_eq!(sp3.header.observables, observables);
+            assert_eq!(sp3.header.orbit_type, orbit_type);
+            assert_eq!(sp3.header.coord_system, "IGS20");
+            assert_eq!(sp3.header.agency, "IGS");
+        }
+
+        // unknown orbit type
+        let content = content.replace("IGS20 FIT", "IGS20 XYZ");
+        assert!(SP3::from_str(&content).is_err());
+    }
 }
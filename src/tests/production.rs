@@ -0,0 +1,327 @@
+//! SP3 production attributes
+#[cfg(test)]
+mod test {
+    use crate::prelude::*;
+    use crate::production::Campaign;
+    use crate::tests::fixtures::{dummy_entry, t0};
+    use std::path::PathBuf;
+    use std::str::FromStr;
+
+    #[test]
+    fn infer_production_attributes() {
+        let path = PathBuf::new()
+            .join(env!("CARGO_MANIFEST_DIR"))
+            .join("data/SP3")
+            .join("C")
+            .join("co108870.sp3");
+
+        let sp3 = SP3::from_file(&path).unwrap();
+
+        assert!(
+            sp3.prod_attributes.is_none(),
+            "legacy file names do not come with production attributes"
+        );
+
+        let attributes = sp3.infer_production_attributes();
+
+        assert_eq!(
+            attributes.agency,
+            sp3.header.agency.chars().take(3).collect::<String>()
+        );
+
+        assert_eq!(attributes.batch_id, 0);
+        assert_eq!(attributes.campaign, Campaign::OPS);
+        assert_eq!(attributes.availability, Availability::Rapid);
+        assert_eq!(attributes.release_period, ReleasePeriod::Daily);
+        assert_eq!(attributes.sampling_period, sp3.header.sampling_period);
+        assert_eq!(attributes.release_date.year, 2000);
+
+        assert_eq!(
+            attributes.release_date,
+            ReleaseDate::from(sp3.header.release_epoch)
+        );
+    }
+
+    #[test]
+    fn legacy_standardized_filename() {
+        let path = PathBuf::new()
+            .join(env!("CARGO_MANIFEST_DIR"))
+            .join("data/SP3")
+            .join("C")
+            .join("co108870.sp3");
+
+        let sp3 = SP3::from_file(&path).unwrap();
+
+        let agency = sp3.header.agency.chars().take(3).collect::<String>();
+
+        let (year, doy) = (
+            sp3.header.release_epoch.year(),
+            sp3.header.release_epoch.day_of_year() as u16,
+        );
+
+        let filename = sp3.standardized_filename();
+
+        assert!(
+            filename.starts_with(&format!("{}0OPSRAP_{}{:03}", agency, year, doy)),
+            "invalid filename: {}",
+            filename
+        );
+
+        assert!(
+            filename.ends_with("_ORB.SP3"),
+            "invalid filename: {}",
+            filename
+        );
+    }
+
+    #[test]
+    fn short_agency_standardized_filename() {
+        let g01 = SV::from_str("G01").unwrap();
+        let t0 = t0();
+        let dt = Duration::from_seconds(900.0);
+
+        let mut builder = SP3Builder::default()
+            .with_agency("IA")
+            .with_constellation(Constellation::GPS)
+            .with_timescale(TimeScale::GPST)
+            .with_sampling_period(dt);
+
+        builder.push_entry(t0, g01, dummy_entry());
+        builder.push_entry(t0 + dt, g01, dummy_entry());
+
+        let sp3 = builder.build().unwrap();
+
+        let attributes = sp3.infer_production_attributes();
+        assert_eq!(attributes.agency, "IAX");
+
+        let filename = sp3.standardized_filename();
+
+        assert_eq!(filename, "IAX0OPSRAP_20232390000_01D_15M_ORB.SP3");
+
+        let mut attributes = attributes;
+        attributes.agency = "É".to_string();
+
+        assert!(
+            attributes.to_string().starts_with("XXX0OPSRAP_2023239"),
+            "invalid filename: {}",
+            attributes
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "flate2")]
+    fn product_summary() {
+        let path = PathBuf::new()
+            .join(env!("CARGO_MANIFEST_DIR"))
+            .join("data/SP3")
+            .join("C")
+            .join("ESA0OPSRAP_20232390000_01D_15M_ORB.SP3.gz");
+
+        let sp3 = SP3::from_gzip_file(&path).unwrap();
+
+        let summary = sp3.product_summary();
+
+        assert_eq!(summary.agency, "ESA");
+        assert_eq!(summary.campaign, Campaign::OPS);
+        assert_eq!(summary.availability, Availability::Rapid);
+        assert_eq!(summary.frame, "ITRF2");
+        assert_eq!(summary.timescale, TimeScale::GPST);
+        assert!(!summary.is_prediction_heavy);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    #[cfg(feature = "flate2")]
+    fn production_metadata_json() {
+        let path = PathBuf::new()
+            .join(env!("CARGO_MANIFEST_DIR"))
+            .join("data/SP3")
+            .join("C")
+            .join("ESA0OPSRAP_20232390000_01D_15M_ORB.SP3.gz");
+
+        let sp3 = SP3::from_gzip_file(&path).unwrap();
+
+        let json = sp3
+            .production_metadata_json()
+            .expect("metadata should exist for standardized file names");
+
+        assert!(
+            json.contains("\"agency\":\"ESA\""),
+            "invalid json: {}",
+            json
+        );
+        assert!(json.contains("\"batch_id\":0"), "invalid json: {}", json);
+        assert!(
+            json.contains("\"campaign\":\"OPS\""),
+            "invalid json: {}",
+            json
+        );
+        assert!(
+            json.contains("\"release_date\":{\"year\":2023,\"doy\":239}"),
+            "invalid json: {}",
+            json
+        );
+
+        let path = PathBuf::new()
+            .join(env!("CARGO_MANIFEST_DIR"))
+            .join("data/SP3")
+            .join("C")
+            .join("co108870.sp3");
+
+        let sp3 = SP3::from_file(&path).unwrap();
+
+        assert!(
+            sp3.production_metadata_json().is_none(),
+            "legacy file names do not come with production attributes"
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "flate2")]
+    fn filename_sampling_matches() {
+        let path = PathBuf::new()
+            .join(env!("CARGO_MANIFEST_DIR"))
+            .join("data/SP3")
+            .join("C")
+            .join("GRG0MGXFIN_20201760000_01D_15M_ORB.SP3.gz");
+
+        let sp3 = SP3::from_gzip_file(&path).unwrap();
+        assert_eq!(sp3.filename_sampling_matches(), Some(true));
+
+        let path = PathBuf::new()
+            .join(env!("CARGO_MANIFEST_DIR"))
+            .join("data/SP3")
+            .join("C")
+            .join("co108870.sp3");
+
+        let sp3 = SP3::from_file(&path).unwrap();
+        assert_eq!(sp3.filename_sampling_matches(), None);
+    }
+
+    #[test]
+    fn filename_sampling_mismatch() {
+        use hifitime::Unit;
+
+        let mut sp3 = SP3::default();
+        sp3.header.sampling_period = 15.0 * Unit::Minute;
+
+        // no production attributes
+        assert_eq!(sp3.filename_sampling_matches(), None);
+
+        sp3.prod_attributes =
+            Some(ProductionAttributes::from_str("GRG0MGXFIN_20201760000_01D_15M_ORB.SP3").unwrap());
+
+        assert_eq!(sp3.filename_sampling_matches(), Some(true));
+
+        // mislabeled file
+        sp3.prod_attributes =
+            Some(ProductionAttributes::from_str("GRG0MGXFIN_20201760000_01D_05M_ORB.SP3").unwrap());
+
+        assert_eq!(sp3.filename_sampling_matches(), Some(false));
+    }
+
+    #[test]
+    #[cfg(feature = "flate2")]
+    fn prediction_regimes() {
+        let path = PathBuf::new()
+            .join(env!("CARGO_MANIFEST_DIR"))
+            .join("data/SP3")
+            .join("C")
+            .join("EMR0OPSULT_20232391800_02D_15M_ORB.SP3.gz");
+
+        let sp3 = SP3::from_gzip_file(&path).unwrap();
+
+        let regimes = sp3.prediction_regimes();
+        assert_eq!(regimes.len(), sp3.total_epochs());
+
+        // chronological order
+        for (prev, next) in regimes.iter().zip(regimes.iter().skip(1)) {
+            assert!(prev.0 < next.0);
+        }
+
+        // ultra rapid products: 24h of fitted states, followed by 24h of predictions
+        let t0 = Epoch::from_str("2023-08-27T18:00:00 GPST").unwrap();
+        let transition = Epoch::from_str("2023-08-28T18:00:00 GPST").unwrap();
+
+        assert_eq!(regimes[0].0, t0);
+
+        let transitions = regimes
+            .iter()
+            .zip(regimes.iter().skip(1))
+            .filter(|(prev, next)| prev.1 != next.1)
+            .map(|(_, next)| next.0)
+            .collect::<Vec<_>>();
+
+        assert_eq!(transitions, vec![transition]);
+
+        for (epoch, orbit, _) in regimes.iter() {
+            assert_eq!(*orbit, *epoch >= transition, "{}", epoch);
+        }
+    }
+
+    #[test]
+    fn prediction_regimes_majority() {
+        use hifitime::Unit;
+
+        let g01 = SV::from_str("G01").unwrap();
+        let g02 = SV::from_str("G02").unwrap();
+        let e05 = SV::from_str("E05").unwrap();
+
+        let t0 = Epoch::from_str("2023-08-27T18:00:00 GPST").unwrap();
+        let dt = 15.0 * Unit::Minute;
+
+        let mut builder = SP3Builder::default()
+            .with_agency("EMR")
+            .with_coord_system("IGS20")
+            .with_constellation(Constellation::Mixed)
+            .with_timescale(TimeScale::GPST)
+            .with_sampling_period(dt);
+
+        // observed for 2 hours, then predicted. E05 clock is predicted from the start.
+        for i in 0..16 {
+            let t = t0 + i as f64 * dt;
+            let predicted = i >= 8;
+
+            for sv in [g01, g02, e05] {
+                let position_km = (13000.0, -20000.0, 15000.0);
+
+                let entry = if predicted {
+                    SP3Entry::from_predicted_position_km(position_km)
+                } else {
+                    SP3Entry::from_position_km(position_km)
+                };
+
+                let entry = if predicted || sv == e05 {
+                    entry.with_predicted_clock_offset_us(-10.5)
+                } else {
+                    entry.with_clock_offset_us(-10.5)
+                };
+
+                builder.push_entry(t, sv, entry);
+            }
+        }
+
+        let sp3 = builder.build().unwrap();
+
+        let regimes = sp3.prediction_regimes();
+        assert_eq!(regimes.len(), 16);
+
+        let transition = t0 + 8.0 * dt;
+
+        for (epoch, orbit, clock) in regimes.iter() {
+            let predicted = *epoch >= transition;
+            assert_eq!(*orbit, predicted, "{}", epoch);
+            assert_eq!(*clock, predicted, "{}", epoch);
+        }
+
+        // first predicted epoch
+        let first_predicted = regimes
+            .iter()
+            .find(|(_, orbit, _)| *orbit)
+            .map(|(epoch, _, _)| *epoch);
+
+        assert_eq!(first_predicted, Some(transition));
+
+        assert!(SP3::default().prediction_regimes().is_empty());
+    }
+}
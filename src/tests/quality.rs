@@ -0,0 +1,292 @@
+//! Orbit quality metrics tests
+#[cfg(test)]
+mod test {
+    use crate::prelude::*;
+    use crate::tests::fixtures::t0;
+    use hifitime::Unit;
+    use std::path::PathBuf;
+    use std::str::FromStr;
+
+    #[test]
+    #[cfg(feature = "flate2")]
+    fn grg0mgxfin_overlap_rms() {
+        let prefix = PathBuf::new()
+            .join(env!("CARGO_MANIFEST_DIR"))
+            .join("data/SP3")
+            .join("C");
+
+        let day_176 =
+            SP3::from_gzip_file(prefix.join("GRG0MGXFIN_20201760000_01D_15M_ORB.SP3.gz")).unwrap();
+
+        let day_177 =
+            SP3::from_gzip_file(prefix.join("GRG0MGXFIN_20201770000_01D_15M_ORB.SP3.gz")).unwrap();
+
+        let rms = day_176.overlap_rms(&day_177, 30.0 * Unit::Minute);
+
+        assert!(!rms.is_empty(), "no satellite in common");
+
+        let num_satellites = day_176.satellites_iter().count();
+
+        assert!(
+            rms.len() * 10 >= num_satellites * 8,
+            "only {}/{} satellites compared",
+            rms.len(),
+            num_satellites
+        );
+
+        for (sv, rms_km) in rms.iter() {
+            // final products: sub-meter continuity
+            assert!(
+                *rms_km < 1.0E-3,
+                "{} - overlap rms too large: {} km",
+                sv,
+                rms_km
+            );
+        }
+
+        // not consecutive: nothing to compare
+        assert!(day_176
+            .overlap_rms(&SP3::default(), 1.0 * Unit::Hour)
+            .is_empty());
+    }
+
+    /// Circular orbit (GPS like), sampled every `dt`
+    fn circular_orbit(dt: Duration, num_epochs: usize) -> SP3 {
+        let g01 = SV::from_str("G01").unwrap();
+        let t0 = t0();
+
+        let (radius_km, period_s) = (26560.0_f64, 43082.0_f64);
+
+        let mut builder = SP3Builder::default()
+            .with_constellation(Constellation::GPS)
+            .with_timescale(TimeScale::GPST)
+            .with_sampling_period(dt);
+
+        for i in 0..num_epochs {
+            let t = t0 + dt * i as f64;
+            let phase = 2.0 * std::f64::consts::PI * (t - t0).to_seconds() / period_s;
+
+            builder.push_entry(
+                t,
+                g01,
+                SP3Entry::from_position_km((
+                    radius_km * phase.cos(),
+                    radius_km * phase.sin() * 0.5,
+                    radius_km * phase.sin() * 0.866,
+                )),
+            );
+        }
+
+        builder.build().unwrap()
+    }
+
+    #[test]
+    fn overlap_rms_boundary() {
+        let g01 = SV::from_str("G01").unwrap();
+        let g02 = SV::from_str("G02").unwrap();
+
+        let t0 = t0();
+        let dt = 15.0 * Unit::Minute;
+
+        let (radius_km, period_s) = (26560.0_f64, 43082.0_f64);
+
+        let position_km = |t: Epoch| {
+            let phase = 2.0 * std::f64::consts::PI * (t - t0).to_seconds() / period_s;
+            (
+                radius_km * phase.cos(),
+                radius_km * phase.sin() * 0.5,
+                radius_km * phase.sin() * 0.866,
+            )
+        };
+
+        let (mut day, mut next_day) = (SP3::default(), SP3::default());
+
+        for i in 0..96 {
+            let t = t0 + dt * i as f64;
+            let next_t = t + 1.0 * Unit::Day;
+
+            day.data.insert(
+                SP3Key { epoch: t, sv: g01 },
+                SP3Entry::from_position_km(position_km(t)),
+            );
+
+            // G01 sorts first, but is only described from 02:00 next day
+            if i >= 8 {
+                next_day.data.insert(
+                    SP3Key {
+                        epoch: next_t,
+                        sv: g01,
+                    },
+                    SP3Entry::from_position_km(position_km(next_t)),
+                );
+            }
+
+            for (sp3, t) in [(&mut day, t), (&mut next_day, next_t)] {
+                sp3.data.insert(
+                    SP3Key { epoch: t, sv: g02 },
+                    SP3Entry::from_position_km(position_km(t)),
+                );
+            }
+        }
+
+        day.canonicalize_mut();
+        next_day.canonicalize_mut();
+
+        // window is centered on midnight, where G02 is continuous
+        let rms = day.overlap_rms(&next_day, 30.0 * Unit::Minute);
+
+        assert!(
+            rms[&g02] < 1.0E-3,
+            "overlap rms too large: {} km",
+            rms[&g02]
+        );
+    }
+
+    #[test]
+    fn interpolation_accuracy() {
+        let truth = circular_orbit(15.0 * Unit::Minute, 96);
+        let decimated = circular_orbit(30.0 * Unit::Minute, 48);
+
+        let g01 = SV::from_str("G01").unwrap();
+
+        let accuracy = decimated.interpolation_accuracy(&truth, 9);
+
+        assert_eq!(accuracy.len(), 1);
+
+        let rms_km = accuracy[&g01];
+        assert!(
+            rms_km < 1.0E-4,
+            "interpolation error too large: {} km",
+            rms_km
+        );
+
+        // higher orders perform better
+        let rms_3_km = decimated.interpolation_accuracy(&truth, 3)[&g01];
+        assert!(rms_3_km > rms_km);
+
+        // same grid: nothing to compare
+        assert!(truth.interpolation_accuracy(&truth, 9).is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "flate2")]
+    fn grg0mgxfin_interpolation_accuracy() {
+        let path = PathBuf::new()
+            .join(env!("CARGO_MANIFEST_DIR"))
+            .join("data/SP3")
+            .join("C")
+            .join("GRG0MGXFIN_20201760000_01D_15M_ORB.SP3.gz");
+
+        let truth = SP3::from_gzip_file(&path).unwrap();
+
+        let t0 = truth.first_epoch().unwrap();
+
+        // 30' decimation
+        let mut decimated = truth.clone();
+
+        decimated
+            .data
+            .retain(|k, _| ((k.epoch - t0).to_seconds() as u64) % 1800 == 0);
+
+        let accuracy = decimated.interpolation_accuracy(&truth, 9);
+
+        assert!(!accuracy.is_empty(), "no satellite compared");
+
+        for (sv, rms_km) in accuracy.iter() {
+            assert!(
+                *rms_km < 1.0E-2,
+                "{} - interpolation error too large: {} km",
+                sv,
+                rms_km
+            );
+        }
+    }
+
+    #[test]
+    fn axis_anomalies() {
+        let g01 = SV::from_str("G01").unwrap();
+        let g02 = SV::from_str("G02").unwrap();
+        let g03 = SV::from_str("G03").unwrap();
+        let g04 = SV::from_str("G04").unwrap();
+
+        let t0 = t0();
+        let dt = 15.0 * Unit::Minute;
+
+        let (radius_km, period_s) = (26560.0_f64, 43082.0_f64);
+
+        let mut reference = SP3::default();
+
+        for i in 0..16 {
+            let t = t0 + dt * i as f64;
+
+            for (j, sv) in [g01, g02, g03, g04].iter().enumerate() {
+                let phase =
+                    2.0 * std::f64::consts::PI * (t - t0).to_seconds() / period_s + j as f64 * 1.5;
+
+                reference.data.insert(
+                    SP3Key { epoch: t, sv: *sv },
+                    SP3Entry::from_position_km((
+                        radius_km * phase.cos(),
+                        radius_km * phase.sin() * 0.5,
+                        radius_km * phase.sin() * 0.866,
+                    ))
+                    .with_velocity_km_s((
+                        -phase.sin(),
+                        phase.cos() * 0.5,
+                        phase.cos() * 0.866,
+                    )),
+                );
+            }
+        }
+
+        // sane product, with centimeter differences
+        let mut product = reference.clone();
+
+        for v in product.data.values_mut() {
+            v.position_km.0 += 1.0E-5;
+            v.position_km.2 -= 2.0E-5;
+        }
+
+        assert!(product.detect_axis_anomalies(&reference).is_empty());
+
+        // corrupt two satellites
+        let mut corrupted = product.clone();
+
+        for (k, v) in corrupted.data.iter_mut() {
+            let anomaly = if k.sv == g02 {
+                AxisAnomaly::SwapXY
+            } else if k.sv == g03 {
+                AxisAnomaly::SignFlipZ
+            } else {
+                continue;
+            };
+
+            v.position_km = anomaly.repair(v.position_km);
+            v.velocity_km_s = v.velocity_km_s.map(|velocity| anomaly.repair(velocity));
+        }
+
+        let anomalies = corrupted.detect_axis_anomalies(&reference);
+
+        assert_eq!(
+            anomalies,
+            vec![(g02, AxisAnomaly::SwapXY), (g03, AxisAnomaly::SignFlipZ)]
+        );
+
+        // repair
+        corrupted.repair_axis_anomalies_mut(&anomalies);
+        assert_eq!(corrupted, product);
+        assert!(corrupted.detect_axis_anomalies(&reference).is_empty());
+
+        // unrelated large residuals are not explained by an axis anomaly
+        let mut shifted = product.clone();
+
+        for v in shifted.data.values_mut() {
+            v.position_km.0 += 10.0;
+        }
+
+        assert!(shifted.detect_axis_anomalies(&reference).is_empty());
+
+        // nothing in common
+        assert!(product.detect_axis_anomalies(&SP3::default()).is_empty());
+    }
+}
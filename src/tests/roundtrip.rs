@@ -0,0 +1,232 @@
+//! Parsing / formatting round trip, over the whole data pool
+#[cfg(test)]
+mod test {
+    use crate::prelude::*;
+    use std::path::{Path, PathBuf};
+
+    /// Positions and clocks are formatted with 1E-6 resolution
+    const TOLERANCE: f64 = 1.0E-6;
+
+    /// Velocities are formatted with 1E-4 dm/s resolution
+    const VELOCITY_TOLERANCE: f64 = 1.0E-7;
+
+    /// Asserts that both [SP3] are equal, within formatting resolution.
+    fn approx_eq(lhs: &SP3, rhs: &SP3, name: &str) {
+        assert_eq!(lhs.header.version, rhs.header.version, "{}: revision", name);
+        assert_eq!(
+            lhs.header.data_type, rhs.header.data_type,
+            "{}: data type",
+            name
+        );
+        assert_eq!(
+            lhs.header.timescale, rhs.header.timescale,
+            "{}: timescale",
+            name
+        );
+        assert_eq!(lhs.header.agency, rhs.header.agency, "{}: agency", name);
+
+        assert_eq!(
+            lhs.header.coord_system, rhs.header.coord_system,
+            "{}: coordinates system",
+            name
+        );
+
+        assert_eq!(
+            lhs.header.sampling_period, rhs.header.sampling_period,
+            "{}: sampling period",
+            name
+        );
+
+        assert_eq!(
+            lhs.header.satellites, rhs.header.satellites,
+            "{}: satellites",
+            name
+        );
+
+        if lhs.header.version >= Version::C {
+            assert_eq!(
+                lhs.header.accuracy_exponents, rhs.header.accuracy_exponents,
+                "{}: accuracy exponents",
+                name
+            );
+        }
+
+        assert_eq!(
+            lhs.header.pos_vel_base, rhs.header.pos_vel_base,
+            "{}: %f base",
+            name
+        );
+
+        assert_eq!(
+            lhs.header.int_params, rhs.header.int_params,
+            "{}: %i parameters",
+            name
+        );
+
+        assert_eq!(lhs.comments, rhs.comments, "{}: comments", name);
+        assert_eq!(
+            lhs.data.len(),
+            rhs.data.len(),
+            "{}: number of entries",
+            name
+        );
+
+        for ((lhs_k, lhs_v), (rhs_k, rhs_v)) in lhs.data.iter().zip(rhs.data.iter()) {
+            assert_eq!(lhs_k, rhs_k, "{}: entries mismatch", name);
+
+            let (x_km, y_km, z_km) = lhs_v.position_km;
+            let (rhs_x_km, rhs_y_km, rhs_z_km) = rhs_v.position_km;
+
+            assert!(
+                (x_km - rhs_x_km).abs() < TOLERANCE
+                    && (y_km - rhs_y_km).abs() < TOLERANCE
+                    && (z_km - rhs_z_km).abs() < TOLERANCE,
+                "{}: {}({}) position mismatch",
+                name,
+                lhs_k.sv,
+                lhs_k.epoch
+            );
+
+            match (lhs_v.clock_us, rhs_v.clock_us) {
+                (Some(lhs_us), Some(rhs_us)) => assert!(
+                    (lhs_us - rhs_us).abs() < TOLERANCE,
+                    "{}: {}({}) clock mismatch",
+                    name,
+                    lhs_k.sv,
+                    lhs_k.epoch
+                ),
+                (None, None) => {},
+                _ => panic!("{}: {}({}) clock mismatch", name, lhs_k.sv, lhs_k.epoch),
+            }
+
+            match (lhs_v.velocity_km_s, rhs_v.velocity_km_s) {
+                (Some((vx, vy, vz)), Some((rhs_vx, rhs_vy, rhs_vz))) => assert!(
+                    (vx - rhs_vx).abs() < VELOCITY_TOLERANCE
+                        && (vy - rhs_vy).abs() < VELOCITY_TOLERANCE
+                        && (vz - rhs_vz).abs() < VELOCITY_TOLERANCE,
+                    "{}: {}({}) velocity mismatch",
+                    name,
+                    lhs_k.sv,
+                    lhs_k.epoch
+                ),
+                (None, None) => {},
+                _ => panic!("{}: {}({}) velocity mismatch", name, lhs_k.sv, lhs_k.epoch),
+            }
+
+            assert_eq!(
+                (lhs_v.predicted_orbit, lhs_v.predicted_clock),
+                (rhs_v.predicted_orbit, rhs_v.predicted_clock),
+                "{}: {}({}) flags mismatch",
+                name,
+                lhs_k.sv,
+                lhs_k.epoch
+            );
+
+            assert_eq!(
+                (lhs_v.position_std_mm, lhs_v.clock_std_ps),
+                (rhs_v.position_std_mm, rhs_v.clock_std_ps),
+                "{}: {}({}) standard deviations mismatch",
+                name,
+                lhs_k.sv,
+                lhs_k.epoch
+            );
+
+            assert_eq!(
+                (lhs_v.maneuver, lhs_v.clock_event),
+                (rhs_v.maneuver, rhs_v.clock_event),
+                "{}: {}({}) flags mismatch",
+                name,
+                lhs_k.sv,
+                lhs_k.epoch
+            );
+        }
+    }
+
+    fn parse(path: &Path) -> Result<SP3, Error> {
+        let name = path.file_name().unwrap().to_string_lossy();
+
+        if name.ends_with(".gz") {
+            #[cfg(feature = "flate2")]
+            return SP3::from_gzip_file(path);
+
+            #[cfg(not(feature = "flate2"))]
+            panic!("gzip compressed files require the flate2 feature");
+        }
+
+        SP3::from_file(path)
+    }
+
+    #[test]
+    fn roundtrip_all() {
+        let prefix = PathBuf::new()
+            .join(env!("CARGO_MANIFEST_DIR"))
+            .join("data/SP3");
+
+        for revision in ["A", "C", "D"] {
+            let dir = prefix.join(revision);
+
+            for entry in std::fs::read_dir(&dir).unwrap() {
+                let path = entry.unwrap().path();
+                let name = path.file_name().unwrap().to_string_lossy().to_string();
+
+                #[cfg(not(feature = "flate2"))]
+                if name.ends_with(".gz") {
+                    continue;
+                }
+
+                let name = format!("{}/{}", revision, name);
+
+                let sp3 = parse(&path).unwrap_or_else(|e| {
+                    panic!("failed to parse {}: {}", name, e);
+                });
+
+                let output = std::env::temp_dir().join(format!(
+                    "sp3-roundtrip-{}-{}",
+                    revision,
+                    path.file_stem().unwrap().to_string_lossy()
+                ));
+
+                sp3.to_file(&output).unwrap_or_else(|e| {
+                    panic!("failed to dump {}: {}", name, e);
+                });
+
+                let parsed_back = SP3::from_file(&output).unwrap_or_else(|e| {
+                    panic!("failed to parse dumped {}: {}", name, e);
+                });
+
+                approx_eq(&sp3, &parsed_back, &name);
+
+                let _ = std::fs::remove_file(&output);
+            }
+        }
+    }
+
+    #[test]
+    fn revision_a_satellites() {
+        use crate::tests::fixtures::{dummy_entry, sp3_builder, t0};
+        use std::io::BufWriter;
+        use std::str::FromStr;
+
+        let g01 = SV::from_str("G01").unwrap();
+        let g02 = SV::from_str("G02").unwrap();
+
+        let mut builder = sp3_builder(Constellation::GPS).with_version(Version::A);
+
+        builder.push_entry(t0(), g01, dummy_entry());
+        builder.push_entry(t0(), g02, dummy_entry());
+
+        let sp3 = builder.build().unwrap();
+
+        let mut writer = BufWriter::new(Vec::new());
+        sp3.format(&mut writer).unwrap();
+
+        let formatted = String::from_utf8(writer.into_inner().unwrap()).unwrap();
+        assert!(formatted.contains("+    2   G01G02"));
+
+        let parsed_back = SP3::from_str(&formatted).unwrap();
+
+        assert_eq!(parsed_back.header.version, Version::A);
+        assert_eq!(parsed_back.header.satellites, vec![g01, g02]);
+        assert_eq!(parsed_back.header.num_satellites, 2);
+    }
+}
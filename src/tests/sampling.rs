@@ -0,0 +1,366 @@
+//! SP3 sampling tests
+#[cfg(test)]
+mod test {
+    use crate::prelude::*;
+    use crate::tests::fixtures::{dummy_entry, t0};
+    use hifitime::Unit;
+    use std::str::FromStr;
+
+    #[test]
+    fn interval_histogram() {
+        let g01 = SV::from_str("G01").unwrap();
+        let g02 = SV::from_str("G02").unwrap();
+
+        let t0 = t0();
+        let dt = 15.0 * Unit::Minute;
+
+        let mut sp3 = SP3::default();
+        assert!(sp3.interval_histogram().is_empty());
+
+        for i in 0..8 {
+            let epoch = t0 + i as f64 * dt;
+
+            for sv in [g01, g02] {
+                sp3.data.insert(SP3Key { epoch, sv }, dummy_entry());
+            }
+        }
+
+        let histogram = sp3.interval_histogram();
+        assert_eq!(histogram.len(), 1, "clean file should have a single bucket");
+        assert_eq!(histogram.get(&dt), Some(&14));
+
+        // single gap
+        sp3.data.remove(&SP3Key {
+            epoch: t0 + 4.0 * dt,
+            sv: g01,
+        });
+
+        let histogram = sp3.interval_histogram();
+        assert_eq!(histogram.len(), 2);
+        assert_eq!(histogram.get(&dt), Some(&12));
+        assert_eq!(histogram.get(&(2.0 * dt)), Some(&1));
+    }
+
+    #[test]
+    fn sampling_gaps() {
+        let g01 = SV::from_str("G01").unwrap();
+        let g02 = SV::from_str("G02").unwrap();
+
+        let t0 = t0();
+        let dt = 15.0 * Unit::Minute;
+
+        let mut sp3 = SP3::default();
+        sp3.header.sampling_period = dt;
+
+        for i in 0..8 {
+            let epoch = t0 + i as f64 * dt;
+
+            for sv in [g01, g02] {
+                sp3.data.insert(SP3Key { epoch, sv }, dummy_entry());
+            }
+        }
+
+        assert!(sp3.has_steady_sampling());
+        assert!(sp3.sampling_gaps().is_empty());
+
+        // removed epoch, for G02 only
+        sp3.data.remove(&SP3Key {
+            epoch: t0 + 3.0 * dt,
+            sv: g02,
+        });
+
+        assert_eq!(sp3.sampling_gaps(), vec![(g02, t0 + 2.0 * dt, 2.0 * dt)]);
+
+        // removed epoch, for all satellites
+        sp3.data.retain(|k, _| k.epoch != t0 + 6.0 * dt);
+
+        assert_eq!(
+            sp3.sampling_gaps(),
+            vec![
+                (g01, t0 + 5.0 * dt, 2.0 * dt),
+                (g02, t0 + 2.0 * dt, 2.0 * dt),
+                (g02, t0 + 5.0 * dt, 2.0 * dt),
+            ]
+        );
+
+        assert!(!sp3.has_steady_sampling());
+    }
+
+    #[test]
+    fn with_sampling_period() {
+        let g01 = SV::from_str("G01").unwrap();
+        let g02 = SV::from_str("G02").unwrap();
+
+        let t0 = t0();
+        let dt = 15.0 * Unit::Minute;
+
+        // circular orbit
+        let (radius_km, period_s) = (26_560.0_f64, 43_082.0_f64);
+        let omega = 2.0 * std::f64::consts::PI / period_s;
+
+        let position_km = |t: Epoch, phase: f64| {
+            let t_s = (t - t0).to_seconds();
+            (
+                radius_km * (omega * t_s + phase).cos(),
+                radius_km * (omega * t_s + phase).sin(),
+                0.0,
+            )
+        };
+
+        let mut builder = SP3Builder::default()
+            .with_timescale(TimeScale::GPST)
+            .with_sampling_period(dt);
+
+        for i in 0..12 {
+            let t = t0 + i as f64 * dt;
+
+            for (sv, phase) in [(g01, 0.0), (g02, 1.0)] {
+                let entry = SP3Entry::from_position_km(position_km(t, phase))
+                    .with_clock_offset_us(10.0 + i as f64);
+
+                builder.push_entry(t, sv, entry);
+            }
+        }
+
+        let sp3 = builder.build().unwrap();
+        assert_eq!(sp3.header.sampling_period, dt);
+
+        let dt_5min = 5.0 * Unit::Minute;
+        let resampled = sp3.with_sampling_period(dt_5min, 7).unwrap();
+
+        // 7th order: 4 epochs are lost on each side
+        let first = t0 + 4.0 * dt;
+        let last = t0 + 7.0 * dt;
+
+        let epochs = resampled.epochs_iter().collect::<Vec<_>>();
+        assert_eq!(epochs.len(), 10);
+        assert_eq!(epochs.first(), Some(&first));
+        assert_eq!(epochs.last(), Some(&last));
+
+        for pair in epochs.windows(2) {
+            assert_eq!(pair[1] - pair[0], dt_5min);
+        }
+
+        assert!(resampled.has_steady_sampling());
+        assert!(resampled.sampling_gaps().is_empty());
+
+        assert_eq!(resampled.header.sampling_period, dt_5min);
+        assert_eq!(resampled.header.num_epochs, 10);
+        assert_eq!(resampled.header.satellites, vec![g01, g02]);
+        assert_eq!(resampled.data.len(), 20);
+
+        // first epoch is 1h later, within same day
+        assert_eq!(resampled.header.mjd, sp3.header.mjd);
+        assert!((resampled.header.mjd_fraction - 1.0 / 24.0).abs() < 1.0E-9);
+
+        for (k, entry) in resampled.data.iter() {
+            let phase = if k.sv == g01 { 0.0 } else { 1.0 };
+            let expected = position_km(k.epoch, phase);
+
+            let err_km = ((entry.position_km.0 - expected.0).powi(2)
+                + (entry.position_km.1 - expected.1).powi(2)
+                + (entry.position_km.2 - expected.2).powi(2))
+            .sqrt();
+
+            assert!(err_km < 1.0E-3, "{}({}) error {} km", k.sv, k.epoch, err_km);
+
+            // clock offsets are linear
+            let expected_us = 10.0 + (k.epoch - t0).to_seconds() / dt.to_seconds();
+            let clock_us = entry.clock_us.unwrap();
+
+            assert!((clock_us - expected_us).abs() < 1.0E-6);
+        }
+    }
+
+    #[test]
+    fn resample() {
+        let g01 = SV::from_str("G01").unwrap();
+
+        let t0 = t0();
+        let dt = 15.0 * Unit::Minute;
+
+        let mut builder = SP3Builder::default()
+            .with_timescale(TimeScale::GPST)
+            .with_sampling_period(dt);
+
+        for i in 0..12 {
+            let x_km = 20_000.0 + 10.0 * i as f64;
+
+            builder.push_entry(
+                t0 + i as f64 * dt,
+                g01,
+                SP3Entry::from_position_km((x_km, -x_km, 1000.0))
+                    .with_clock_offset_us(10.0 + i as f64),
+            );
+        }
+
+        let sp3 = builder.build().unwrap();
+
+        let dt_5min = 5.0 * Unit::Minute;
+        let resampled = sp3.resample(dt_5min, 7).unwrap();
+
+        let epochs = resampled.epochs_iter().collect::<Vec<_>>();
+        assert_eq!(epochs.len(), 10);
+
+        for pair in epochs.windows(2) {
+            assert_eq!(pair[1] - pair[0], dt_5min);
+        }
+
+        assert_eq!(resampled.header.sampling_period, dt_5min);
+        assert!(resampled.has_steady_sampling());
+
+        // points outside the interpolation window are omitted
+        let coverage = sp3.interpolation_coverage(7)[&g01];
+        assert_eq!(resampled.first_epoch(), Some(coverage.0));
+        assert_eq!(resampled.last_epoch(), Some(coverage.1));
+
+        for (k, entry) in resampled.data.iter() {
+            // only interpolated states are flagged
+            let on_grid = sp3.get(g01, k.epoch).is_some();
+
+            assert_eq!(entry.predicted_orbit, !on_grid, "{}", k.epoch);
+            assert_eq!(entry.predicted_clock, !on_grid, "{}", k.epoch);
+
+            let expected_km = 20_000.0 + 10.0 * (k.epoch - t0).to_seconds() / dt.to_seconds();
+            assert!((entry.position_km.0 - expected_km).abs() < 1.0E-6);
+            assert!(entry.clock_us.is_some());
+        }
+
+        // identical grid
+        let same = resampled.data.keys().collect::<Vec<_>>();
+        let unflagged = sp3.with_sampling_period(dt_5min, 7).unwrap();
+        assert_eq!(unflagged.data.keys().collect::<Vec<_>>(), same);
+        assert!(unflagged.data.values().all(|v| !v.predicted_orbit));
+
+        // invalid parameters
+        assert!(matches!(
+            sp3.resample(dt_5min, 8),
+            Err(Error::InterpolationOrder(8))
+        ));
+
+        assert!(matches!(
+            sp3.resample(Duration::ZERO, 7),
+            Err(Error::SamplingPeriod(_))
+        ));
+
+        assert!(matches!(
+            sp3.with_sampling_period(dt_5min, 8),
+            Err(Error::InterpolationOrder(8))
+        ));
+
+        assert!(matches!(
+            sp3.with_sampling_period(-dt_5min, 7),
+            Err(Error::SamplingPeriod(_))
+        ));
+    }
+
+    #[test]
+    fn resample_late_satellite() {
+        let g01 = SV::from_str("G01").unwrap();
+        let g02 = SV::from_str("G02").unwrap();
+
+        let t0 = t0();
+        let dt = 15.0 * Unit::Minute;
+
+        let mut builder = SP3Builder::default()
+            .with_timescale(TimeScale::GPST)
+            .with_sampling_period(dt);
+
+        for i in 0..24 {
+            let x_km = 20_000.0 + 10.0 * i as f64;
+
+            let entry = SP3Entry::from_position_km((x_km, -x_km, 1000.0))
+                .with_clock_offset_us(10.0 + i as f64);
+
+            builder.push_entry(t0 + i as f64 * dt, g02, entry);
+
+            // G01 sorts first but only appears at 03:00
+            if i >= 12 {
+                builder.push_entry(t0 + i as f64 * dt, g01, entry);
+            }
+        }
+
+        let sp3 = builder.build().unwrap();
+
+        let dt_5min = 5.0 * Unit::Minute;
+        let resampled = sp3.resample(dt_5min, 7).unwrap();
+
+        // grid and time references follow the earliest satellite
+        let first = resampled.data.keys().map(|k| k.epoch).min().unwrap();
+        assert_eq!(first, sp3.interpolation_coverage(7)[&g02].0);
+
+        assert_eq!(resampled.header.release_epoch, first);
+        assert_eq!(resampled.header.mjd, 60183);
+
+        let mjd_fraction = (first - t0).to_unit(Unit::Day);
+        assert!((resampled.header.mjd_fraction - mjd_fraction).abs() < 1.0E-9);
+
+        for (k, entry) in resampled.data.iter() {
+            assert_eq!(((k.epoch - t0).to_seconds() % dt_5min.to_seconds()), 0.0);
+
+            // clock offsets are interpolated as well
+            let expected_us = 10.0 + (k.epoch - t0).to_seconds() / dt.to_seconds();
+            let clock_us = entry.clock_us.unwrap();
+            assert!(
+                (clock_us - expected_us).abs() < 1.0E-6,
+                "{}({})",
+                k.sv,
+                k.epoch
+            );
+        }
+
+        assert!(resampled.data.keys().any(|k| k.sv == g01));
+    }
+
+    #[test]
+    fn satellite_count() {
+        let g01 = SV::from_str("G01").unwrap();
+        let g02 = SV::from_str("G02").unwrap();
+        let e05 = SV::from_str("E05").unwrap();
+
+        let t0 = t0();
+        let dt = 15.0 * Unit::Minute;
+
+        let mut sp3 = SP3::default();
+        assert_eq!(sp3.satellite_count_iter().count(), 0);
+
+        for i in 0..8 {
+            let epoch = t0 + i as f64 * dt;
+
+            for sv in [g01, g02, e05] {
+                sp3.data.insert(SP3Key { epoch, sv }, dummy_entry());
+            }
+        }
+
+        sp3.canonicalize_mut();
+
+        // clean file
+        let counts = sp3.satellite_count_iter().collect::<Vec<_>>();
+
+        assert_eq!(counts.len(), 8);
+
+        for (i, (epoch, count)) in counts.iter().enumerate() {
+            assert_eq!(*epoch, t0 + i as f64 * dt);
+            assert_eq!(*count, sp3.header.satellites.len());
+            assert_eq!(*count, sp3.records_at(*epoch));
+        }
+
+        // coverage dip
+        sp3.data.remove(&SP3Key {
+            epoch: t0 + 3.0 * dt,
+            sv: g02,
+        });
+
+        sp3.data.remove(&SP3Key {
+            epoch: t0 + 3.0 * dt,
+            sv: e05,
+        });
+
+        let dips = sp3
+            .satellite_count_iter()
+            .filter(|(_, count)| *count < 3)
+            .collect::<Vec<_>>();
+
+        assert_eq!(dips, vec![(t0 + 3.0 * dt, 1)]);
+    }
+}
@@ -0,0 +1,309 @@
+//! SP3 split tests
+#[cfg(test)]
+mod test {
+    use crate::prelude::*;
+    use std::io::{BufReader, BufWriter};
+    use std::path::PathBuf;
+
+    // Formats and parses back this [SP3]
+    fn roundtrip(sp3: &SP3) -> SP3 {
+        let mut writer = BufWriter::new(Vec::new());
+        sp3.format(&mut writer).unwrap();
+
+        let content = writer.into_inner().unwrap();
+        let mut reader = BufReader::new(content.as_slice());
+
+        SP3::from_reader(&mut reader).unwrap()
+    }
+
+    #[test]
+    fn split_by_satellite() {
+        let path = PathBuf::new()
+            .join(env!("CARGO_MANIFEST_DIR"))
+            .join("data/SP3")
+            .join("D")
+            .join("example.txt");
+
+        let sp3 = SP3::from_file(&path).unwrap();
+
+        let split = sp3.split_by_satellite();
+
+        assert_eq!(split.len(), sp3.satellites_iter().count());
+
+        let mut total = 0;
+
+        for (sv, single) in split.iter() {
+            assert_eq!(single.header.satellites, vec![*sv]);
+            assert_eq!(single.header.constellation, sv.constellation);
+            assert!(single.data.keys().all(|k| k.sv == *sv));
+
+            total += single.data.len();
+
+            let parsed = roundtrip(single);
+
+            assert_eq!(parsed.header.satellites, vec![*sv]);
+            assert_eq!(parsed.data, single.data, "{} - round trip failed", sv);
+        }
+
+        assert_eq!(total, sp3.data.len(), "some entries were lost");
+    }
+
+    #[test]
+    fn split_by_constellation() {
+        let path = PathBuf::new()
+            .join(env!("CARGO_MANIFEST_DIR"))
+            .join("data/SP3")
+            .join("D")
+            .join("example.txt");
+
+        let sp3 = SP3::from_file(&path).unwrap();
+        assert_eq!(sp3.header.constellation, Constellation::Mixed);
+
+        let split = sp3.split_by_constellation();
+
+        let constellations = sp3.constellations_iter().collect::<Vec<_>>();
+        assert!(constellations.len() > 1, "mixed sample expected");
+        assert_eq!(split.len(), constellations.len());
+
+        let mut total = 0;
+
+        for (constellation, single) in split.iter() {
+            assert!(constellations.contains(constellation));
+            assert_ne!(*constellation, Constellation::Mixed);
+
+            assert_eq!(single.header.constellation, *constellation);
+            assert_eq!(
+                single.constellations_iter().collect::<Vec<_>>(),
+                vec![*constellation]
+            );
+
+            assert!(single
+                .header
+                .satellites
+                .iter()
+                .all(|sv| sv.constellation == *constellation));
+
+            total += single.data.len();
+
+            let parsed = roundtrip(single);
+            assert_eq!(
+                parsed.data, single.data,
+                "{} - round trip failed",
+                constellation
+            );
+        }
+
+        assert_eq!(total, sp3.data.len(), "some entries were lost");
+    }
+
+    #[test]
+    #[cfg(feature = "processing")]
+    fn split_even_dt() {
+        use crate::tests::fixtures::{dummy_entry, sp3_builder, t0};
+        use hifitime::Unit;
+        use std::str::FromStr;
+
+        let g01 = SV::from_str("G01").unwrap();
+        let e05 = SV::from_str("E05").unwrap();
+
+        let t0 = t0();
+        let dt = 15.0 * Unit::Minute;
+
+        let mut builder = sp3_builder(Constellation::Mixed).with_sampling_period(dt);
+
+        for i in 0..96 {
+            let t = t0 + i as f64 * dt;
+
+            builder.push_entry(t, g01, dummy_entry());
+
+            // E05 only appears in the morning
+            if i < 40 {
+                builder.push_entry(t, e05, SP3Entry::from_position_km((4.0, 5.0, 6.0)));
+            }
+        }
+
+        let sp3 = builder.build().unwrap();
+
+        // not a no-op
+        let pieces = sp3.split_even_dt(6.0 * Unit::Hour);
+        assert_eq!(pieces.len(), 4);
+
+        let mut total = 0;
+
+        for (i, piece) in pieces.iter().enumerate() {
+            let first = t0 + i as f64 * 6.0 * Unit::Hour;
+            let last = first + 6.0 * Unit::Hour - dt;
+
+            assert_eq!(piece.first_epoch(), Some(first));
+            assert_eq!(piece.last_epoch(), Some(last));
+
+            assert_eq!(piece.header.num_epochs, 24);
+            assert_eq!(piece.header.sampling_period, dt);
+            assert_eq!(piece.header.release_epoch, first);
+            assert!(piece.has_steady_sampling());
+
+            let mjd_fraction = i as f64 * 0.25;
+            assert_eq!(piece.header.mjd, sp3.header.mjd);
+            assert!((piece.header.mjd_fraction - mjd_fraction).abs() < 1.0E-9);
+
+            if i < 2 {
+                assert_eq!(piece.header.satellites, vec![g01, e05]);
+            } else {
+                assert_eq!(piece.header.satellites, vec![g01]);
+            }
+
+            total += piece.data.len();
+        }
+
+        assert_eq!(total, sp3.data.len(), "some entries were lost");
+
+        // the header is split on the same boundaries
+        let headers = sp3.header.split_even_dt(6.0 * Unit::Hour);
+        assert_eq!(headers.len(), pieces.len());
+
+        for (header, piece) in headers.iter().zip(pieces.iter()) {
+            assert_eq!(header.release_epoch, piece.header.release_epoch);
+            assert_eq!(header.num_epochs, piece.header.num_epochs);
+            assert_eq!(header.mjd, piece.header.mjd);
+            assert!((header.mjd_fraction - piece.header.mjd_fraction).abs() < 1.0E-9);
+        }
+
+        // single piece
+        let pieces = sp3.split_even_dt(1.0 * Unit::Day);
+        assert_eq!(pieces.len(), 1);
+        assert_eq!(pieces[0].data, sp3.data);
+
+        // empty
+        assert!(SP3::default().split_even_dt(6.0 * Unit::Hour).is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "processing")]
+    fn split_even_dt_late_satellite() {
+        use crate::tests::fixtures::{dummy_entry, sp3_builder, t0};
+        use hifitime::Unit;
+        use std::str::FromStr;
+
+        let g01 = SV::from_str("G01").unwrap();
+        let g02 = SV::from_str("G02").unwrap();
+
+        let t0 = t0();
+        let dt = 15.0 * Unit::Minute;
+
+        let mut builder = sp3_builder(Constellation::GPS).with_sampling_period(dt);
+
+        for i in 0..96 {
+            let t = t0 + i as f64 * dt;
+
+            builder.push_entry(t, g02, dummy_entry());
+
+            // G01 sorts first but only appears at 03:00
+            if i >= 12 {
+                builder.push_entry(t, g01, SP3Entry::from_position_km((4.0, 5.0, 6.0)));
+            }
+        }
+
+        let sp3 = builder.build().unwrap();
+
+        let pieces = sp3.split_even_dt(6.0 * Unit::Hour);
+        assert_eq!(pieces.len(), 4);
+
+        for (i, piece) in pieces.iter().enumerate() {
+            let first = t0 + i as f64 * 6.0 * Unit::Hour;
+            let last = first + 6.0 * Unit::Hour - dt;
+
+            assert_eq!(piece.data.keys().map(|k| k.epoch).min(), Some(first));
+            assert_eq!(piece.data.keys().map(|k| k.epoch).max(), Some(last));
+            assert_eq!(piece.header.num_epochs, 24);
+            assert_eq!(piece.header.release_epoch, first);
+        }
+
+        let total = pieces.iter().map(|piece| piece.data.len()).sum::<usize>();
+        assert_eq!(total, sp3.data.len(), "some entries were lost");
+    }
+
+    #[test]
+    #[cfg(feature = "processing")]
+    fn split_time_references() {
+        use crate::tests::fixtures::{dummy_entry, sp3_builder, t0};
+        use hifitime::Unit;
+        use std::str::FromStr;
+
+        let g01 = SV::from_str("G01").unwrap();
+
+        let t0 = t0();
+        let dt = 15.0 * Unit::Minute;
+
+        let mut builder = sp3_builder(Constellation::GPS).with_sampling_period(dt);
+
+        for i in 0..96 {
+            builder.push_entry(t0 + i as f64 * dt, g01, dummy_entry());
+        }
+
+        let sp3 = builder.build().unwrap();
+
+        let mut pieces = sp3.split_even_dt(6.0 * Unit::Hour);
+
+        // both halves of a regular split are realigned as well
+        let (lhs, rhs) = sp3.split(t0 + 12.0 * Unit::Hour);
+        pieces.push(lhs);
+        pieces.push(rhs);
+
+        for piece in pieces.iter() {
+            let first = piece.first_epoch().unwrap();
+            let (week, week_nanos) = first.to_time_of_week();
+
+            assert_eq!(piece.header.week, week);
+            assert_eq!(piece.header.week_nanos, week_nanos);
+            assert_eq!(piece.header.release_epoch, first);
+            assert_eq!(piece.header.num_epochs, piece.total_epochs() as u64);
+
+            // 2023-08-27 is MJD 60183
+            let mjd_fraction = (first - t0).to_unit(Unit::Day);
+            assert_eq!(piece.header.mjd, 60183);
+            assert!((piece.header.mjd_fraction - mjd_fraction).abs() < 1.0E-9);
+
+            let parsed = roundtrip(piece);
+
+            assert_eq!(parsed.first_epoch(), Some(first));
+            assert_eq!(parsed.header.num_epochs, piece.header.num_epochs);
+            assert_eq!(parsed.data, piece.data);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "flate2")]
+    #[cfg(feature = "processing")]
+    fn grg0mgxfin_split_even_dt() {
+        use hifitime::Unit;
+
+        let path = PathBuf::new()
+            .join(env!("CARGO_MANIFEST_DIR"))
+            .join("data/SP3")
+            .join("C")
+            .join("GRG0MGXFIN_20201760000_01D_15M_ORB.SP3.gz");
+
+        let sp3 = SP3::from_gzip_file(&path).unwrap();
+
+        let t0 = sp3.first_epoch().unwrap();
+        let dt = sp3.header.sampling_period;
+
+        let pieces = sp3.split_even_dt(6.0 * Unit::Hour);
+        assert_eq!(pieces.len(), 4);
+
+        for (i, piece) in pieces.iter().enumerate() {
+            let first = t0 + i as f64 * 6.0 * Unit::Hour;
+            let last = first + 6.0 * Unit::Hour - dt;
+
+            assert_eq!(piece.first_epoch(), Some(first));
+            assert_eq!(piece.last_epoch(), Some(last));
+            assert_eq!(piece.header.num_epochs, 24);
+
+            let parsed = roundtrip(piece);
+            assert_eq!(parsed.data, piece.data, "piece #{} - round trip failed", i);
+        }
+
+        let total = pieces.iter().map(|piece| piece.data.len()).sum::<usize>();
+        assert_eq!(total, sp3.data.len(), "some entries were lost");
+    }
+}
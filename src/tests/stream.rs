@@ -0,0 +1,161 @@
+//! Epoch streaming tests
+#[cfg(test)]
+mod test {
+    use crate::prelude::*;
+    use crate::tests::fixtures::{sp3_d_header, sp3_header};
+    use std::str::FromStr;
+
+    #[cfg(feature = "flate2")]
+    use flate2::read::GzDecoder;
+
+    #[cfg(feature = "flate2")]
+    use std::{fs::File, path::PathBuf};
+
+    #[test]
+    fn epochs_stream() {
+        let content = sp3_header(Version::D, DataType::Velocity, &["G01", "E05"], 2)
+            .replace("++         0  0", "++         5  7")
+            + "/* epochs stream
+*  2023  8 27  0  0  0.00000000
+PG01 -22335.782004 -14656.280389  -1218.238499   -176.397152
+VG01  -1436.471028  -2195.735237  -3181.326745     -0.004031
+PE05  13001.000000 -19000.000000  15000.123456     -9.500000
+*  2023  8 27  0 15  0.00000000
+PG01 -22335.000000 -14656.000000  -1218.000000   -176.400000
+VG01  -1436.000000  -2195.000000  -3181.000000     -0.004000
+PE05  13002.000000 -19001.000000  15001.000000     -9.600000
+EOF";
+
+        let stream = SP3::epochs_stream(content.as_bytes()).unwrap();
+
+        assert_eq!(stream.header.version, Version::D);
+        assert_eq!(stream.header.data_type, DataType::Velocity);
+        assert_eq!(stream.header.constellation, Constellation::Mixed);
+        assert_eq!(stream.header.timescale, TimeScale::GPST);
+        assert_eq!(stream.header.num_epochs, 2);
+        assert_eq!(stream.header.accuracy_exponents.len(), 2);
+        assert_eq!(stream.comments, vec!["epochs stream".to_string()]);
+
+        assert_eq!(
+            stream.header.satellites,
+            vec![SV::from_str("G01").unwrap(), SV::from_str("E05").unwrap()]
+        );
+
+        let sp3 = SP3::from_str(&content).unwrap();
+        assert_eq!(sp3.header.satellites, stream.header.satellites);
+
+        let mut num_epochs = 0;
+
+        for block in stream {
+            let (epoch, entries) = block.unwrap();
+
+            let expected = sp3
+                .data
+                .iter()
+                .filter_map(|(k, v)| {
+                    if k.epoch == epoch {
+                        Some((k.sv, *v))
+                    } else {
+                        None
+                    }
+                })
+                .collect::<Vec<_>>();
+
+            assert_eq!(entries.len(), 2);
+            assert_eq!(entries, expected, "{}: invalid content", epoch);
+
+            num_epochs += 1;
+        }
+
+        assert_eq!(num_epochs, 2);
+
+        // errors stop the iteration
+        let content = content.replace(
+            "*  2023  8 27  0 15  0.00000000",
+            "*  2023 13 27  0 15  0.00000000",
+        );
+
+        let results = SP3::epochs_stream(content.as_bytes())
+            .unwrap()
+            .collect::<Vec<_>>();
+
+        assert_eq!(results.len(), 2);
+        assert!(results[0].is_ok());
+        assert!(results[1].is_err());
+    }
+
+    #[test]
+    fn epochs_stream_with_opts() {
+        let content = sp3_d_header(1)
+            + "*  2023  8 27  0  0  0.00000000
+PG01 -22335.782004 -14656.280389  -1218.238499   -176.397152
+PG01 -22000.000000 -14000.000000  -1000.000000   -176.397152
+EOF";
+
+        // lenient (default)
+        let mut stream = SP3::epochs_stream(content.as_bytes()).unwrap();
+        let (_, entries) = stream.next().unwrap().unwrap();
+        assert_eq!(entries[0].1.position_km, (-22000.0, -14000.0, -1000.0));
+        assert!(entries[0].1.clock_us.is_some());
+
+        // options apply to each epoch
+        let opts = ParseOptions {
+            read_clocks: false,
+            ..Default::default()
+        };
+
+        let mut stream = SP3::epochs_stream_with_opts(content.as_bytes(), opts).unwrap();
+        let (_, entries) = stream.next().unwrap().unwrap();
+        assert!(entries[0].1.clock_us.is_none());
+
+        let opts = ParseOptions {
+            strict: true,
+            ..Default::default()
+        };
+
+        let mut stream = SP3::epochs_stream_with_opts(content.as_bytes(), opts).unwrap();
+
+        assert!(matches!(
+            stream.next(),
+            Some(Err(Error::ParsingError(
+                ParsingError::DuplicateEntry { .. }
+            )))
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "flate2")]
+    fn grg0mgxfin_epochs_stream() {
+        let path = PathBuf::new()
+            .join(env!("CARGO_MANIFEST_DIR"))
+            .join("data/SP3")
+            .join("C")
+            .join("GRG0MGXFIN_20201760000_01D_15M_ORB.SP3.gz");
+
+        let fd = File::open(&path).unwrap();
+
+        let stream = SP3::epochs_stream(GzDecoder::new(fd)).unwrap();
+
+        assert_eq!(stream.header.num_epochs, 96);
+
+        let expected_epochs = stream.header.num_epochs as usize;
+
+        let mut num_epochs = 0;
+        let mut previous = Option::<Epoch>::None;
+
+        for block in stream {
+            let (epoch, entries) = block.unwrap();
+
+            assert!(!entries.is_empty(), "{}: empty epoch", epoch);
+
+            if let Some(previous) = previous {
+                assert!(epoch > previous, "epochs should be increasing");
+            }
+
+            previous = Some(epoch);
+            num_epochs += 1;
+        }
+
+        assert_eq!(num_epochs, expected_epochs);
+    }
+}
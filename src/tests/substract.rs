@@ -29,3 +29,59 @@ fn substract_null() {
 
     assert_eq!(passed, 10_176);
 }
+
+#[test]
+#[cfg(feature = "flate2")]
+fn substract_different_products() {
+    let test_pool = PathBuf::new()
+        .join(env!("CARGO_MANIFEST_DIR"))
+        .join("data/SP3")
+        .join("C");
+
+    // rapid and ultra rapid products, that overlap on 2023-08-27 evening
+    let sp3_a = SP3::from_gzip_file(
+        test_pool
+            .clone()
+            .join("ESA0OPSRAP_20232390000_01D_15M_ORB.SP3.gz"),
+    )
+    .unwrap();
+
+    let sp3_b = SP3::from_gzip_file(
+        test_pool
+            .clone()
+            .join("EMR0OPSULT_20232391800_02D_15M_ORB.SP3.gz"),
+    )
+    .unwrap();
+
+    let residual = sp3_a.substract(&sp3_b);
+
+    assert!(!residual.data.is_empty(), "no common data");
+
+    let mut non_null_y = 0;
+
+    for (k, v) in residual.data.iter() {
+        let a = sp3_a.data.get(k).unwrap();
+        let b = sp3_b.data.get(k).unwrap();
+
+        let expected = *a - *b;
+
+        assert_eq!(v.position_km, expected.position_km, "{}({})", k.sv, k.epoch);
+
+        let (_, y_km, _) = v.position_km;
+
+        if y_km != 0.0 {
+            non_null_y += 1;
+        }
+
+        // independent products remain consistent
+        assert!(
+            y_km.abs() < 1.0E-2,
+            "{}({}) - y residual {}",
+            k.sv,
+            k.epoch,
+            y_km
+        );
+    }
+
+    assert!(non_null_y > 0, "y residual should not be null");
+}
@@ -157,25 +157,33 @@ mod test {
             .join("data/SP3")
             .join("A");
 
-        for file in [
-            // "emr08874.sp3", // TODO ? THH:MM:SS with omitted seconds..
-            // "sio06492.sp3", // TODO? THH:MM:SS with omitted seconds..
-            "NGA0OPSRAP_20251850000_01D_15M_ORB.SP3.gz",
-            "NGA0OPSRAP_20251860000_01D_15M_ORB.SP3.gz",
-            "NGA0OPSRAP_20251870000_01D_15M_ORB.SP3.gz",
-            "NGA0OPSRAP_20251880000_01D_15M_ORB.SP3.gz",
-            "NGA0OPSRAP_20251890000_01D_15M_ORB.SP3.gz",
-            "NGA0OPSRAP_20251900000_01D_15M_ORB.SP3.gz",
-            "NGA0OPSRAP_20251910000_01D_15M_ORB.SP3.gz",
-            "NGA0OPSRAP_20251920000_01D_15M_ORB.SP3.gz",
-            "NGA0OPSRAP_20251930000_01D_15M_ORB.SP3.gz",
+        for (file, is_gzip) in [
+            ("emr08874.sp3", false),
+            ("sio06492.sp3", false),
+            ("NGA0OPSRAP_20251850000_01D_15M_ORB.SP3.gz", true),
+            ("NGA0OPSRAP_20251860000_01D_15M_ORB.SP3.gz", true),
+            ("NGA0OPSRAP_20251870000_01D_15M_ORB.SP3.gz", true),
+            ("NGA0OPSRAP_20251880000_01D_15M_ORB.SP3.gz", true),
+            ("NGA0OPSRAP_20251890000_01D_15M_ORB.SP3.gz", true),
+            ("NGA0OPSRAP_20251900000_01D_15M_ORB.SP3.gz", true),
+            ("NGA0OPSRAP_20251910000_01D_15M_ORB.SP3.gz", true),
+            ("NGA0OPSRAP_20251920000_01D_15M_ORB.SP3.gz", true),
+            ("NGA0OPSRAP_20251930000_01D_15M_ORB.SP3.gz", true),
         ] {
             let file_path = prefix.clone().join(file);
             println!("Parsing file \"{}\"", file_path.to_string_lossy());
 
-            let sp3 = SP3::from_gzip_file(&file_path).unwrap_or_else(|e| {
-                panic!("failed to parse data/A/{}: {}", file, e);
-            });
+            let sp3 = if is_gzip {
+                SP3::from_gzip_file(&file_path).unwrap_or_else(|e| {
+                    panic!("failed to parse data/A/{}: {}", file, e);
+                })
+            } else {
+                SP3::from_file(&file_path).unwrap_or_else(|e| {
+                    panic!("failed to parse data/A/{}: {}", file, e);
+                })
+            };
+
+            assert!(sp3.total_epochs() > 0, "data/A/{}: empty record", file);
 
             let proposed = sp3.standardized_filename();
 
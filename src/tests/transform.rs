@@ -0,0 +1,205 @@
+//! SP3 transformation tests
+#[cfg(test)]
+mod test {
+    use crate::prelude::*;
+    use crate::tests::fixtures::t0;
+    use hifitime::Unit;
+    use std::str::FromStr;
+
+    #[test]
+    fn map_entries_x_shift() {
+        let g01 = SV::from_str("G01").unwrap();
+        let g02 = SV::from_str("G02").unwrap();
+
+        let t0 = t0();
+
+        let mut sp3 = SP3::default();
+        sp3.header.timescale = TimeScale::GPST;
+        sp3.header.sampling_period = 15.0 * Unit::Minute;
+
+        for i in 0..4 {
+            let epoch = t0 + i as f64 * 15.0 * Unit::Minute;
+
+            for (j, sv) in [g01, g02].iter().enumerate() {
+                sp3.data.insert(
+                    SP3Key { epoch, sv: *sv },
+                    SP3Entry::from_position_km((i as f64, j as f64, 3.0)).with_clock_offset_us(1.0),
+                );
+            }
+        }
+
+        let shifted = sp3.map_entries(|_, entry| {
+            let (x_km, y_km, z_km) = entry.position_km;
+            entry.with_position_km((x_km + 1.0, y_km, z_km))
+        });
+
+        assert_eq!(shifted.data.len(), sp3.data.len());
+
+        for (k, v) in sp3.data.iter() {
+            let shifted = shifted.data.get(k).expect("missing entry");
+
+            let (x_km, y_km, z_km) = v.position_km;
+            assert_eq!(shifted.position_km, (x_km + 1.0, y_km, z_km));
+            assert_eq!(shifted.clock_us, v.clock_us);
+        }
+
+        // rebuilt header
+        assert_eq!(shifted.header.satellites, vec![g01, g02]);
+        assert_eq!(shifted.header.num_epochs, 4);
+        assert_eq!(shifted.header.sampling_period, 15.0 * Unit::Minute);
+
+        // original is untouched
+        assert_eq!(
+            sp3.data.values().next().unwrap().position_km,
+            (0.0, 0.0, 3.0)
+        );
+    }
+
+    #[test]
+    fn helmert_transform() {
+        let g01 = SV::from_str("G01").unwrap();
+        let t0 = t0();
+
+        let mut sp3 = SP3::default();
+
+        for (i, position_km) in [
+            (26_560.0, 0.0, 0.0),
+            (0.0, 26_560.0, 0.0),
+            (0.0, 0.0, 26_560.0),
+            (15_000.0, -12_000.0, 18_000.0),
+        ]
+        .iter()
+        .enumerate()
+        {
+            sp3.data.insert(
+                SP3Key {
+                    epoch: t0 + i as f64 * 15.0 * Unit::Minute,
+                    sv: g01,
+                },
+                SP3Entry::from_position_km(*position_km),
+            );
+        }
+
+        // identity
+        let mut identity = sp3.clone();
+        identity.apply_helmert_mut((0.0, 0.0, 0.0), (0.0, 0.0, 0.0), 0.0);
+        assert_eq!(identity, sp3);
+
+        // 1 urad rotation around Z
+        let mut rotated = sp3.clone();
+        rotated.apply_helmert_mut((0.0, 0.0, 0.0), (0.0, 0.0, 1.0E-6), 0.0);
+
+        let positions = rotated
+            .data
+            .values()
+            .map(|v| v.position_km)
+            .collect::<Vec<_>>();
+
+        let (x_km, y_km, z_km) = positions[0];
+        assert_eq!(x_km, 26_560.0);
+        assert!(
+            (y_km - 26.56E-3).abs() < 1.0E-9,
+            "invalid y shift: {}",
+            y_km
+        );
+        assert_eq!(z_km, 0.0);
+
+        let (x_km, y_km, z_km) = positions[1];
+        assert!(
+            (x_km + 26.56E-3).abs() < 1.0E-9,
+            "invalid x shift: {}",
+            x_km
+        );
+        assert_eq!(y_km, 26_560.0);
+        assert_eq!(z_km, 0.0);
+
+        // not affected by Z rotation
+        assert_eq!(positions[2], (0.0, 0.0, 26_560.0));
+
+        // translation + scale
+        let mut shifted = sp3.clone();
+        shifted.apply_helmert_mut((1.0, -2.0, 3.0), (0.0, 0.0, 0.0), 1.0E3);
+
+        let (x_km, y_km, z_km) = shifted.data.values().next().unwrap().position_km;
+        assert!((x_km - 26_561.026_56).abs() < 1.0E-9, "invalid x: {}", x_km);
+        assert_eq!(y_km, -2.0);
+        assert_eq!(z_km, 3.0);
+    }
+
+    #[test]
+    fn helmert_estimation() {
+        let g01 = SV::from_str("G01").unwrap();
+        let g02 = SV::from_str("G02").unwrap();
+
+        let t0 = t0();
+
+        let mut sp3 = SP3::default();
+
+        for i in 0..8 {
+            let epoch = t0 + i as f64 * 15.0 * Unit::Minute;
+            let phase = i as f64 * 0.2;
+
+            for (sv, inclination) in [(g01, 0.96_f64), (g02, 0.5_f64)] {
+                sp3.data.insert(
+                    SP3Key { epoch, sv },
+                    SP3Entry::from_position_km((
+                        26_560.0 * phase.cos(),
+                        26_560.0 * phase.sin() * inclination.cos(),
+                        26_560.0 * phase.sin() * inclination.sin(),
+                    )),
+                );
+            }
+        }
+
+        // nothing in common
+        assert!(sp3.estimate_helmert(&SP3::default()).is_none());
+
+        // identity
+        let params = sp3.estimate_helmert(&sp3).unwrap();
+
+        assert!(params.translation_km.0.abs() < 1.0E-9);
+        assert!(params.rotation_rad.2.abs() < 1.0E-15);
+        assert!(params.scale_ppb.abs() < 1.0E-6);
+
+        let (translation_km, rotation_rad, scale_ppb) =
+            ((1.0E-3, -2.0E-3, 5.0E-4), (1.0E-8, -2.0E-8, 3.0E-8), 1.5);
+
+        let mut transformed = sp3.clone();
+        transformed.apply_helmert_mut(translation_km, rotation_rad, scale_ppb);
+
+        let params = sp3.estimate_helmert(&transformed).unwrap();
+
+        for (estimated, expected) in [
+            (params.translation_km.0, translation_km.0),
+            (params.translation_km.1, translation_km.1),
+            (params.translation_km.2, translation_km.2),
+        ] {
+            assert!(
+                (estimated - expected).abs() < 1.0E-8,
+                "translation: estimated {} expected {}",
+                estimated,
+                expected
+            );
+        }
+
+        for (estimated, expected) in [
+            (params.rotation_rad.0, rotation_rad.0),
+            (params.rotation_rad.1, rotation_rad.1),
+            (params.rotation_rad.2, rotation_rad.2),
+        ] {
+            assert!(
+                (estimated - expected).abs() < 1.0E-12,
+                "rotation: estimated {} expected {}",
+                estimated,
+                expected
+            );
+        }
+
+        assert!(
+            (params.scale_ppb - scale_ppb).abs() < 1.0E-3,
+            "scale: estimated {} expected {}",
+            params.scale_ppb,
+            scale_ppb
+        );
+    }
+}
@@ -0,0 +1,247 @@
+//! SP3 validation tests
+#[cfg(test)]
+mod test {
+    use crate::prelude::*;
+    use crate::tests::fixtures::{sp3_builder, sp3_header, t0};
+    use std::path::PathBuf;
+    use std::str::FromStr;
+
+    #[test]
+    fn validate_example_txt() {
+        let path = PathBuf::new()
+            .join(env!("CARGO_MANIFEST_DIR"))
+            .join("data/SP3")
+            .join("D")
+            .join("example.txt");
+
+        let sp3 = SP3::from_file(&path).unwrap();
+
+        assert_eq!(sp3.validate(), Ok(()));
+
+        // corrupt this file
+        let mut corrupted = sp3.clone();
+
+        let t0 = Epoch::from_str("2019-10-27T00:00:00 GPST").unwrap();
+        let g01 = SV::from_str("G01").unwrap();
+
+        corrupted.header.num_epochs += 1;
+
+        let entry = corrupted
+            .data
+            .get_mut(&SP3Key { epoch: t0, sv: g01 })
+            .unwrap();
+
+        entry.position_km = (1000.0, 0.0, 0.0);
+        entry.clock_us = None;
+        entry.predicted_clock = true;
+
+        let issues = corrupted.validate().unwrap_err();
+
+        assert_eq!(issues.len(), 3, "issues: {:?}", issues);
+
+        assert!(issues.contains(&ValidationIssue::EpochCount {
+            declared: 2,
+            found: 1,
+        }));
+
+        assert!(issues.contains(&ValidationIssue::ImplausibleRadius {
+            sv: g01,
+            epoch: t0,
+            radius_km: 1000.0,
+        }));
+
+        assert!(issues.contains(&ValidationIssue::PredictedClockWithoutData { sv: g01, epoch: t0 }));
+
+        // SP3-a is limited to GPS
+        corrupted.header.version = Version::A;
+
+        let issues = corrupted.validate().unwrap_err();
+
+        assert!(issues.contains(&ValidationIssue::RevisionConstellation(
+            Constellation::Galileo,
+            Version::A
+        )));
+    }
+
+    #[test]
+    fn validate_header_consistency() {
+        let g01 = SV::from_str("G01").unwrap();
+        let g02 = SV::from_str("G02").unwrap();
+        let e05 = SV::from_str("E05").unwrap();
+
+        let t0 = t0();
+        let dt = Duration::from_seconds(900.0);
+
+        let mut builder = sp3_builder(Constellation::Mixed).with_sampling_period(dt);
+
+        for i in 0..4 {
+            let t = t0 + i as f64 * dt;
+
+            builder.push_entry(
+                t,
+                g01,
+                SP3Entry::from_position_km((-22335.782004, -14656.280389, -1218.238499)),
+            );
+
+            builder.push_entry(
+                t,
+                e05,
+                SP3Entry::from_position_km((-7143.938315, 25966.946197, -9640.110765)),
+            );
+        }
+
+        let sp3 = builder.build().unwrap();
+        assert_eq!(sp3.validate(), Ok(()));
+
+        // deliberately inconsistent header
+        let mut corrupted = sp3.clone();
+
+        corrupted.header.satellites = vec![g01, g02];
+        corrupted.header.data_type = DataType::Velocity;
+        corrupted.header.mjd += 1;
+
+        let issues = corrupted.validate().unwrap_err();
+
+        assert_eq!(issues.len(), 4, "issues: {:?}", issues);
+
+        assert!(issues.contains(&ValidationIssue::UndeclaredSatellite(e05)));
+        assert!(issues.contains(&ValidationIssue::MissingSatellite(g02)));
+        assert!(issues.contains(&ValidationIssue::MissingVelocities));
+
+        assert!(issues.iter().any(|issue| matches!(
+            issue,
+            ValidationIssue::TimeReferenceMismatch { release_epoch, .. } if *release_epoch == t0
+        )));
+
+        // velocity file, with velocities
+        let mut with_velocities = sp3.clone();
+        with_velocities.header.data_type = DataType::Velocity;
+
+        for (_, v) in with_velocities.data.iter_mut() {
+            v.velocity_km_s = Some((1.0, 2.0, 3.0));
+        }
+
+        assert_eq!(with_velocities.validate(), Ok(()));
+
+        // declared count is correct, but not the satellites themselves
+        let mut swapped = sp3.clone();
+        swapped.header.satellites = vec![g01, g02];
+
+        let issues = swapped.validate().unwrap_err();
+
+        assert_eq!(
+            issues,
+            vec![
+                ValidationIssue::UndeclaredSatellite(e05),
+                ValidationIssue::MissingSatellite(g02),
+            ]
+        );
+    }
+
+    #[test]
+    fn altitude_bands() {
+        let path = PathBuf::new()
+            .join(env!("CARGO_MANIFEST_DIR"))
+            .join("data/SP3")
+            .join("D")
+            .join("example.txt");
+
+        let mut sp3 = SP3::from_file(&path).unwrap();
+
+        assert!(sp3.check_altitude_bands().is_empty());
+
+        let t0 = Epoch::from_str("2019-10-27T00:00:00 GPST").unwrap();
+        let g01 = SV::from_str("G01").unwrap();
+        let r01 = SV::from_str("R01").unwrap();
+
+        // 1000 km radius
+        sp3.data
+            .get_mut(&SP3Key { epoch: t0, sv: g01 })
+            .unwrap()
+            .position_km = (600.0, 800.0, 0.0);
+
+        // unit error (m instead of km)
+        let r01_entry = sp3.data.get_mut(&SP3Key { epoch: t0, sv: r01 }).unwrap();
+
+        r01_entry.position_km = (
+            r01_entry.position_km.0 * 1.0E3,
+            r01_entry.position_km.1 * 1.0E3,
+            r01_entry.position_km.2 * 1.0E3,
+        );
+
+        let flagged = sp3.check_altitude_bands();
+
+        assert_eq!(flagged.len(), 2, "flagged: {:?}", flagged);
+
+        assert!(flagged.contains(&(g01, t0, 1000.0)));
+
+        assert!(flagged
+            .iter()
+            .any(|(sv, t, radius_km)| *sv == r01 && *t == t0 && *radius_km > 1.0E6));
+    }
+
+    #[test]
+    fn velocity_consistency() {
+        // circular orbit, with consistent velocity
+        let radius_km = 26_560.0_f64;
+        let w_rad_s = 2.0 * std::f64::consts::PI / (12.0 * 3600.0);
+        let dt_s = 900.0;
+
+        let g01 = SV::from_str("G01").unwrap();
+
+        // X velocity field replaced by the Y velocity field when `corrupted`
+        let content = |corrupted: bool| {
+            let mut content = sp3_header(Version::D, DataType::Velocity, &["G01"], 24);
+
+            for i in 0..24 {
+                // phase offset, so no corrupted velocity vector is null (omitted)
+                let phase = 0.1 + w_rad_s * i as f64 * dt_s;
+
+                let (x_km, y_km) = (radius_km * phase.cos(), radius_km * phase.sin());
+
+                let vx_dm_s = -radius_km * w_rad_s * phase.sin() * 1.0E4;
+                let vy_dm_s = radius_km * w_rad_s * phase.cos() * 1.0E4;
+
+                let vx_dm_s = if corrupted { vy_dm_s } else { vx_dm_s };
+
+                content.push_str(&format!(
+                    "*  2023  8 27 {:2} {:2}  0.00000000\n",
+                    i / 4,
+                    (i % 4) * 15
+                ));
+
+                content.push_str(&format!(
+                    "PG01{:14.6}{:14.6}{:14.6}{:14.6}\n",
+                    x_km, y_km, 0.0, 0.0
+                ));
+
+                content.push_str(&format!(
+                    "VG01{:14.6}{:14.6}{:14.6}{:14.6}\n",
+                    vx_dm_s, vy_dm_s, 0.0, 0.0
+                ));
+            }
+
+            content.push_str("EOF");
+            content
+        };
+
+        let sp3 = SP3::from_str(&content(false)).unwrap();
+        assert_eq!(sp3.total_epochs(), 24);
+
+        let flagged = sp3.check_velocity_consistency(0.01);
+        assert!(flagged.is_empty(), "flagged: {:?}", flagged);
+
+        // X velocity parsed from the Y field
+        let corrupted = SP3::from_str(&content(true)).unwrap();
+
+        let flagged = corrupted.check_velocity_consistency(0.01);
+
+        // a single epoch might be (almost) correct, where vx = vy
+        assert!(flagged.len() >= 23, "flagged: {:?}", flagged);
+
+        for (sv, _, err_m_s) in flagged {
+            assert_eq!(sv, g01);
+            assert!(err_m_s > 0.01);
+        }
+    }
+}
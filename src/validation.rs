@@ -0,0 +1,347 @@
+use thiserror::Error;
+
+use crate::{
+    dynamics::lagrange_derivative_weights,
+    prelude::{Constellation, DataType, Epoch, Version, SP3, SV},
+};
+
+use hifitime::Unit;
+
+/// Minimal radius of a satellite orbit (Earth radius), in kilometers
+const MIN_ORBIT_RADIUS_KM: f64 = 6_378.0;
+
+/// Maximal plausible radius of a GNSS satellite orbit, in kilometers
+const MAX_ORBIT_RADIUS_KM: f64 = 50_000.0;
+
+/// Geostationary orbit radius, in kilometers
+const GEO_ORBIT_RADIUS_KM: f64 = 42_164.0;
+
+/// Tolerance on the expected orbit radius (ratio)
+const ORBIT_RADIUS_TOLERANCE: f64 = 0.1;
+
+/// Number of data points used to estimate the velocity from the positions
+const VELOCITY_ESTIMATE_WINDOW: usize = 10;
+
+/// Clock value used as "missing data" sentinel, in microseconds
+const CLOCK_SENTINEL_US: f64 = 999999.0;
+
+/// Tolerance between the publication [Epoch] and the MJD, in seconds.
+/// The line #1 epoch is not always expressed in the file timescale,
+/// so we tolerate the leap seconds.
+const TIME_REFERENCE_TOLERANCE_S: f64 = 60.0;
+
+/// [ValidationIssue]s reported by [SP3::validate].
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum ValidationIssue {
+    #[error("{0} satellites are not supported by SP3-{1}")]
+    RevisionConstellation(Constellation, Version),
+
+    #[error("declared {declared} satellites, found {found}")]
+    SatelliteCount { declared: usize, found: usize },
+
+    #[error("{0} is described but not declared")]
+    UndeclaredSatellite(SV),
+
+    #[error("{0} is declared but not described")]
+    MissingSatellite(SV),
+
+    #[error("declared {declared} epochs, found {found}")]
+    EpochCount { declared: u64, found: usize },
+
+    #[error("unsteady sampling")]
+    UnsteadySampling,
+
+    #[error("velocity file without any velocity vector")]
+    MissingVelocities,
+
+    #[error("publication epoch {release_epoch} does not match MJD epoch {mjd_epoch}")]
+    TimeReferenceMismatch {
+        release_epoch: Epoch,
+        mjd_epoch: Epoch,
+    },
+
+    #[error("{sv}({epoch}) - implausible orbit radius {radius_km:.3} km")]
+    ImplausibleRadius {
+        sv: SV,
+        epoch: Epoch,
+        radius_km: f64,
+    },
+
+    #[error("{sv}({epoch}) - clock sentinel used as value")]
+    ClockSentinel { sv: SV, epoch: Epoch },
+
+    #[error("{sv}({epoch}) - predicted clock without clock data")]
+    PredictedClockWithoutData { sv: SV, epoch: Epoch },
+}
+
+/// Returns true if this [Constellation] may be described by this SP3 [Version]
+fn revision_supports(version: Version, constellation: Constellation) -> bool {
+    match version {
+        Version::A => constellation == Constellation::GPS,
+        Version::B => matches!(constellation, Constellation::GPS | Constellation::Glonass),
+        Version::C | Version::D => true,
+    }
+}
+
+/// Returns the expected orbit radii (in kilometers) of this [Constellation],
+/// or None if we do not have this knowledge.
+fn expected_orbit_radii_km(constellation: Constellation) -> Option<Vec<f64>> {
+    match constellation {
+        Constellation::GPS => Some(vec![26_560.0]),
+        Constellation::Glonass => Some(vec![25_510.0]),
+        Constellation::Galileo => Some(vec![29_600.0]),
+        // MEO and GEO/IGSO
+        Constellation::BeiDou => Some(vec![27_900.0, GEO_ORBIT_RADIUS_KM]),
+        // IGSO and GEO
+        Constellation::QZSS | Constellation::IRNSS => Some(vec![GEO_ORBIT_RADIUS_KM]),
+        c => {
+            if c.is_sbas() {
+                Some(vec![GEO_ORBIT_RADIUS_KM])
+            } else {
+                None
+            }
+        },
+    }
+}
+
+impl SP3 {
+    /// Reports all positions whose orbit radius is outside the expected band
+    /// for this constellation (GPS ~26560 km, Glonass ~25510 km, Galileo ~29600 km,
+    /// BeiDou MEO ~27900 km, geostationary and inclined orbits ~42164 km), with a 10% tolerance.
+    /// When the constellation is unknown, the radius must simply remain plausible (6378 to 50000 km).
+    /// This catches unit errors and corrupt records.
+    ///
+    /// ## Output
+    /// - (satellite, epoch, orbit radius in kilometers) for each flagged position
+    pub fn check_altitude_bands(&self) -> Vec<(SV, Epoch, f64)> {
+        let mut flagged = Vec::new();
+
+        for (k, v) in self.data.iter() {
+            let (x_km, y_km, z_km) = v.position_km;
+            let radius_km = (x_km.powi(2) + y_km.powi(2) + z_km.powi(2)).sqrt();
+
+            let plausible = match expected_orbit_radii_km(k.sv.constellation) {
+                Some(radii_km) => radii_km.iter().any(|expected_km| {
+                    (radius_km - expected_km).abs() <= expected_km * ORBIT_RADIUS_TOLERANCE
+                }),
+                None => (MIN_ORBIT_RADIUS_KM..=MAX_ORBIT_RADIUS_KM).contains(&radius_km),
+            };
+
+            if !plausible {
+                flagged.push((k.sv, k.epoch, radius_km));
+            }
+        }
+
+        flagged
+    }
+
+    /// Reports all epochs where the native velocity vector disagrees with
+    /// the velocity estimated from the position vectors, beyond tolerance.
+    /// The velocity is estimated as the derivative of a Lagrangian fit of the
+    /// surrounding positions (up to 10 data points, centered when feasible).
+    /// This catches parsing or production errors that corrupt one velocity axis.
+    ///
+    /// ## Input
+    /// - tol_m_s: tolerance, in meters per second
+    ///
+    /// ## Output
+    /// - (satellite, epoch, error norm in meters per second) for each flagged epoch
+    pub fn check_velocity_consistency(&self, tol_m_s: f64) -> Vec<(SV, Epoch, f64)> {
+        let mut flagged = Vec::new();
+
+        let mut satellites = self.data.keys().map(|k| k.sv).collect::<Vec<_>>();
+        satellites.dedup();
+
+        for sv in satellites {
+            let states = self
+                .data
+                .iter()
+                .filter_map(|(k, v)| {
+                    if k.sv == sv {
+                        Some((k.epoch, v.position_km, v.velocity_km_s))
+                    } else {
+                        None
+                    }
+                })
+                .collect::<Vec<_>>();
+
+            let num_states = states.len();
+            let window_len = VELOCITY_ESTIMATE_WINDOW.min(num_states);
+
+            if window_len < 2 {
+                continue;
+            }
+
+            for (i, (t, _, velocity_km_s)) in states.iter().enumerate() {
+                let (vx_km_s, vy_km_s, vz_km_s) = match velocity_km_s {
+                    Some(velocity_km_s) => *velocity_km_s,
+                    None => continue,
+                };
+
+                let start = i
+                    .saturating_sub(window_len / 2)
+                    .min(num_states - window_len);
+                let window = &states[start..start + window_len];
+
+                let epochs = window.iter().map(|(t_i, _, _)| *t_i).collect::<Vec<_>>();
+                let weights = lagrange_derivative_weights(*t, &epochs);
+
+                let (mut est_x_km_s, mut est_y_km_s, mut est_z_km_s) = (0.0, 0.0, 0.0);
+
+                for (w_i, (_, (x_km, y_km, z_km), _)) in weights.iter().zip(window.iter()) {
+                    est_x_km_s += w_i * x_km;
+                    est_y_km_s += w_i * y_km;
+                    est_z_km_s += w_i * z_km;
+                }
+
+                let err_m_s = ((vx_km_s - est_x_km_s).powi(2)
+                    + (vy_km_s - est_y_km_s).powi(2)
+                    + (vz_km_s - est_z_km_s).powi(2))
+                .sqrt()
+                    * 1.0E3;
+
+                if err_m_s > tol_m_s {
+                    flagged.push((sv, *t, err_m_s));
+                }
+            }
+        }
+
+        flagged
+    }
+
+    /// Verifies this [SP3] against the format specifications, and the consistency
+    /// of the [Header] with the record. This is the one-stop verification
+    /// a producer should run prior publication, for example on synthesized files. We verify:
+    /// - that the constellations are supported by this revision
+    /// - the number of satellites, versus the declared number of satellites
+    /// - that the declared satellites are the ones actually described
+    /// - the number of epochs, versus the declared number of epochs
+    /// - that the sampling is steady (see [SP3::has_steady_sampling])
+    /// - that [DataType::Velocity] files actually provide velocity vectors
+    /// - that the publication [Epoch] and the MJD agree
+    /// - that the satellite coordinates are plausible (see [SP3::check_altitude_bands])
+    /// - that clock sentinel values are not used as actual data
+    /// - that predicted clock flags come with clock data.
+    ///
+    /// ## Output
+    /// - Err with all the [ValidationIssue]s that were found, not only the first one.
+    pub fn validate(&self) -> Result<(), Vec<ValidationIssue>> {
+        let mut issues = Vec::new();
+
+        let version = self.header.version;
+
+        let mut constellations = Vec::<Constellation>::new();
+        let mut satellites = Vec::<SV>::new();
+
+        for k in self.data.keys() {
+            if !satellites.contains(&k.sv) {
+                satellites.push(k.sv);
+            }
+
+            if !constellations.contains(&k.sv.constellation) {
+                constellations.push(k.sv.constellation);
+            }
+        }
+
+        for constellation in constellations {
+            if !revision_supports(version, constellation) {
+                issues.push(ValidationIssue::RevisionConstellation(
+                    constellation,
+                    version,
+                ));
+            }
+        }
+
+        let declared = self.header.num_satellites;
+
+        if declared != satellites.len() {
+            issues.push(ValidationIssue::SatelliteCount {
+                declared,
+                found: satellites.len(),
+            });
+        }
+
+        for sv in satellites.iter() {
+            if !self.header.satellites.contains(sv) {
+                issues.push(ValidationIssue::UndeclaredSatellite(*sv));
+            }
+        }
+
+        for sv in self.header.satellites.iter() {
+            if !satellites.contains(sv) {
+                issues.push(ValidationIssue::MissingSatellite(*sv));
+            }
+        }
+
+        let total_epochs = self.total_epochs();
+
+        if self.header.num_epochs != total_epochs as u64 {
+            issues.push(ValidationIssue::EpochCount {
+                declared: self.header.num_epochs,
+                found: total_epochs,
+            });
+        }
+
+        if !self.has_steady_sampling() {
+            issues.push(ValidationIssue::UnsteadySampling);
+        }
+
+        if self.header.data_type == DataType::Velocity
+            && self.data.values().all(|v| v.velocity_km_s.is_none())
+        {
+            issues.push(ValidationIssue::MissingVelocities);
+        }
+
+        // MJD is optional
+        if self.header.mjd > 0 {
+            let release_epoch = self.header.release_epoch;
+
+            let mjd_epoch = Epoch::from_mjd_in_time_scale(
+                self.header.mjd as f64 + self.header.mjd_fraction,
+                self.header.timescale,
+            );
+
+            if (release_epoch - mjd_epoch).abs() > TIME_REFERENCE_TOLERANCE_S * Unit::Second {
+                issues.push(ValidationIssue::TimeReferenceMismatch {
+                    release_epoch,
+                    mjd_epoch,
+                });
+            }
+        }
+
+        for (sv, epoch, radius_km) in self.check_altitude_bands() {
+            issues.push(ValidationIssue::ImplausibleRadius {
+                sv,
+                epoch,
+                radius_km,
+            });
+        }
+
+        for (k, v) in self.data.iter() {
+            match v.clock_us {
+                Some(clock_us) => {
+                    if clock_us.abs() >= CLOCK_SENTINEL_US {
+                        issues.push(ValidationIssue::ClockSentinel {
+                            sv: k.sv,
+                            epoch: k.epoch,
+                        });
+                    }
+                },
+                None => {
+                    if v.predicted_clock {
+                        issues.push(ValidationIssue::PredictedClockWithoutData {
+                            sv: k.sv,
+                            epoch: k.epoch,
+                        });
+                    }
+                },
+            }
+        }
+
+        if issues.is_empty() {
+            Ok(())
+        } else {
+            Err(issues)
+        }
+    }
+}
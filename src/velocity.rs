@@ -1,49 +1,65 @@
 //! Velocity entry parsing
 use crate::{
     errors::ParsingError,
+    position::parse_sv,
     prelude::{Constellation, Version, SV},
 };
 
 use std::str::FromStr;
 
+/// Converts a velocity component in km/s, to the SP3 velocity field, in dm/s.
+pub(crate) fn km_s_to_sp3_velocity_field(velocity_km_s: f64) -> f64 {
+    velocity_km_s * 1.0E4
+}
+
+/// Converts an SP3 velocity field (in dm/s) to a velocity component in km/s.
+/// This is the reciprocal of [km_s_to_sp3_velocity_field].
+pub(crate) fn sp3_velocity_field_to_km_s(field_dm_s: f64) -> f64 {
+    field_dm_s * 1.0E-4
+}
+
+/// Converts a clock drift in nanoseconds, to the SP3 clock drift field.
+pub(crate) fn clock_drift_ns_to_field(drift_ns: f64) -> f64 {
+    drift_ns * 1.0E-2
+}
+
+/// Converts an SP3 clock drift field to a clock drift in nanoseconds.
+/// This is the reciprocal of [clock_drift_ns_to_field].
+pub(crate) fn field_to_clock_drift_ns(field: f64) -> f64 {
+    field * 1.0E2
+}
+
 pub fn velocity_entry(content: &str) -> bool {
     content.starts_with('V')
 }
 
 pub struct VelocityEntry {
     sv: SV,
+    /// Velocity fields, as is (dm/s)
     velocity: (f64, f64, f64),
     clock: Option<f64>,
 }
 
 impl VelocityEntry {
-    pub fn parse(line: &str, revision: Version) -> Result<Self, ParsingError> {
+    /// Parses [VelocityEntry] from this line. `constellation` is the
+    /// header-declared system, assumed when the constellation letter is omitted.
+    pub fn parse(
+        line: &str,
+        revision: Version,
+        constellation: Constellation,
+    ) -> Result<Self, ParsingError> {
         let mut clock: Option<f64> = None;
 
-        let sv = match revision {
-            Version::A => {
-                // GPS-Only: constellation might be omitted
-                let prn = line[2..4].trim().parse::<u8>().or(Err(ParsingError::SV))?;
-
-                SV::new(Constellation::GPS, prn)
-            },
-            _ => {
-                // parsing needs to pass
-                SV::from_str(line[1..4].trim()).or(Err(ParsingError::SV))?
-            },
-        };
+        let sv = parse_sv(line, revision, constellation)?;
 
-        let x_km = f64::from_str(line[4..18].trim())
-            .or(Err(ParsingError::Coordinates(line[4..18].to_string())))?
-            * 1.0E-4;
+        let x_dm_s = f64::from_str(line[4..18].trim())
+            .or(Err(ParsingError::Coordinates(line[4..18].to_string())))?;
 
-        let y_km = f64::from_str(line[18..32].trim())
-            .or(Err(ParsingError::Coordinates(line[18..32].to_string())))?
-            * 1.0E-4;
+        let y_dm_s = f64::from_str(line[18..32].trim())
+            .or(Err(ParsingError::Coordinates(line[18..32].to_string())))?;
 
-        let z_km = f64::from_str(line[32..46].trim())
-            .or(Err(ParsingError::Coordinates(line[32..46].to_string())))?
-            * 1.0E-4;
+        let z_dm_s = f64::from_str(line[32..46].trim())
+            .or(Err(ParsingError::Coordinates(line[32..46].to_string())))?;
 
         if !line[45..52].trim().eq("999999.") {
             /*
@@ -57,7 +73,7 @@ impl VelocityEntry {
 
         Ok(Self {
             sv,
-            velocity: (x_km, y_km, z_km),
+            velocity: (x_dm_s, y_dm_s, z_dm_s),
             clock,
         })
     }
@@ -68,3 +84,101 @@ impl VelocityEntry {
         (self.sv, self.velocity, self.clock)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn velocity_field_scaling() {
+        for (velocity_km_s, field_dm_s) in [
+            (0.0, 0.0),
+            (1.0E-7, 1.0E-3),
+            (1.0E-4, 1.0),
+            (-0.888094, -8880.94),
+            (2.3142274905, 23142.274905),
+            (-3.9, -39000.0),
+            (7.5, 75000.0),
+        ] {
+            let field = km_s_to_sp3_velocity_field(velocity_km_s);
+            assert!(
+                (field - field_dm_s).abs() < 1.0E-9,
+                "{} km/s: invalid field {}",
+                velocity_km_s,
+                field
+            );
+
+            let reciprocal = sp3_velocity_field_to_km_s(field);
+            assert!(
+                (reciprocal - velocity_km_s).abs() < 1.0E-12,
+                "{} km/s: reciprocal failed: {}",
+                velocity_km_s,
+                reciprocal
+            );
+        }
+    }
+
+    #[test]
+    fn velocity_record_km_s() {
+        let line = "VG01  -8880.949046 -23142.274905     -0.001200      0.089376";
+
+        let (sv, (x_dm_s, y_dm_s, z_dm_s), clock) =
+            VelocityEntry::parse(line, Version::D, Constellation::GPS)
+                .unwrap()
+                .to_parts();
+
+        assert_eq!(sv, SV::from_str("G01").unwrap());
+
+        let (vx_km_s, vy_km_s, vz_km_s) = (
+            sp3_velocity_field_to_km_s(x_dm_s),
+            sp3_velocity_field_to_km_s(y_dm_s),
+            sp3_velocity_field_to_km_s(z_dm_s),
+        );
+
+        assert!(
+            (vx_km_s - -0.8880949046).abs() < 1.0E-12,
+            "bad vx: {}",
+            vx_km_s
+        );
+        assert!(
+            (vy_km_s - -2.3142274905).abs() < 1.0E-12,
+            "bad vy: {}",
+            vy_km_s
+        );
+        assert!((vz_km_s - -1.2E-7).abs() < 1.0E-15, "bad vz: {}", vz_km_s);
+
+        let drift_ns = field_to_clock_drift_ns(clock.unwrap());
+        assert!(
+            (drift_ns - 8.9376).abs() < 1.0E-10,
+            "bad drift: {}",
+            drift_ns
+        );
+    }
+
+    #[test]
+    fn clock_drift_field_scaling() {
+        for (drift_ns, expected) in [
+            (0.0, 0.0),
+            (8.9376, 0.089376),
+            (-8.9376, -0.089376),
+            (1.0E-4, 1.0E-6),
+            (123.456789, 1.23456789),
+        ] {
+            let field = clock_drift_ns_to_field(drift_ns);
+            assert!(
+                (field - expected).abs() < 1.0E-12,
+                "{} ns: invalid field {}",
+                drift_ns,
+                field
+            );
+
+            let reciprocal = field_to_clock_drift_ns(field);
+            assert!(
+                (reciprocal - drift_ns).abs() < 1.0E-10,
+                "{} ns: reciprocal failed: {}",
+                drift_ns,
+                reciprocal
+            );
+        }
+    }
+}